@@ -0,0 +1,140 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use crate::keccak_f_circuit::*;
+use lac::interface::*;
+use lac::utils::*;
+
+//Batch of independent Keccak instances side by side in the same layers. Every instance is
+//the same template circuit with its ids moved by a multiple of a fixed stride, except the
+//constants 0 and 1 that the instances share, so the gate g of the layer d of the instance i
+//is the gate g + i * stride of the template with its inputs moved the same way. A data
+//parallel prover can read the template once and the instances from get_instance_remap.
+
+/// N Keccak[r, c] instances for messages of the same size, with the input groups
+/// "message_i" and the output groups "digest_i" of every instance i
+pub struct KeccakBatch {
+    lac: LAC<i64>,
+    template: LAC<i64>,
+    stride: u64,
+    instances_amount: usize,
+}
+
+impl KeccakBatch {
+    /// Batch of instances_amount circuits of get_keccak_lac_circuit_for_size, their inputs
+    /// are 0 until they are set, see get_assignment
+    pub fn new(
+        instances_amount: usize,
+        input_size: u64,
+        r: u64,
+        c: u64,
+        l: u64,
+    ) -> Result<Self, KeccakError> {
+        check_keccak_parameters(r, c, l)?;
+        let rounds = get_keccak_f_rounds_amount((r + c) / 25);
+        let mut template: LAC<i64> = LAC::new();
+        template.set_basic_layer(get_keccak_basic_layer(vec![0; input_size as usize]));
+        template.append_layers(get_keccak_hash_layers(
+            input_size,
+            Vec::new(),
+            r,
+            c,
+            l,
+            rounds,
+        ));
+
+        let stride = template.get_max_id() + 1 - RESERVED_IDS;
+        let message_ids: Vec<u64> = (2..(2 + input_size)).collect();
+        let digest_ids = get_digest_ids(l);
+        let mut lac: LAC<i64> = LAC::new();
+        for i in 0..instances_amount {
+            let remap = IdRemap::new(i as u64 * stride);
+            lac.merge_lac(template.remapped(&remap)).unwrap();
+            lac.declare_input(
+                &format!("message_{}", i),
+                Visibility::Private,
+                remap.map_ids(&message_ids),
+            )
+            .unwrap();
+            lac.declare_output(&format!("digest_{}", i), remap.map_ids(&digest_ids))
+                .unwrap();
+        }
+
+        template
+            .declare_input("message", Visibility::Private, message_ids)
+            .unwrap();
+        template.declare_output("digest", digest_ids).unwrap();
+        Ok(KeccakBatch {
+            lac,
+            template,
+            stride,
+            instances_amount,
+        })
+    }
+
+    pub fn get_lac(&self) -> &LAC<i64> {
+        &self.lac
+    }
+
+    pub fn get_lac_mut(&mut self) -> &mut LAC<i64> {
+        &mut self.lac
+    }
+
+    /// One instance with the ids of the instance 0 and the groups "message" and "digest"
+    pub fn get_template(&self) -> &LAC<i64> {
+        &self.template
+    }
+
+    pub fn get_instances_amount(&self) -> usize {
+        self.instances_amount
+    }
+
+    /// Distance between the ids of consecutive instances
+    pub fn get_stride(&self) -> u64 {
+        self.stride
+    }
+
+    /// Moves the ids of the template to the ids of the instance i
+    pub fn get_instance_remap(&self, i: usize) -> IdRemap {
+        assert!(
+            i < self.instances_amount,
+            "the batch has {} instances, got the instance {}",
+            self.instances_amount,
+            i
+        );
+        IdRemap::new(i as u64 * self.stride)
+    }
+
+    /// Assignment of the bits of every message to the group "message_i" of its instance
+    pub fn get_assignment(&self, messages: &[Vec<i64>]) -> Assignment<i64> {
+        assert_eq!(
+            messages.len(),
+            self.instances_amount,
+            "the batch needs one message per instance"
+        );
+        let mut assignment: Assignment<i64> = Assignment::new();
+        for (i, message) in messages.iter().enumerate() {
+            assignment.set(&format!("message_{}", i), message.clone());
+        }
+        assignment
+    }
+
+    /// Digest bits of every instance after the last evaluation
+    pub fn get_digests(&self) -> Vec<Vec<i64>> {
+        let outputs = self.lac.get_named_outputs().unwrap();
+        (0..self.instances_amount)
+            .map(|i| outputs[&format!("digest_{}", i)].clone())
+            .collect()
+    }
+}
+
+/// Batch of instances_amount Keccak-256 circuits for messages of message_size bytes
+pub fn keccak256_batch_circuit(instances_amount: usize, message_size: usize) -> KeccakBatch {
+    KeccakBatch::new(
+        instances_amount,
+        8 * message_size as u64,
+        KECCAK256_RATE,
+        KECCAK256_CAPACITY,
+        KECCAK256_OUTPUT_BITS,
+    )
+    .unwrap()
+}
@@ -0,0 +1,323 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use crate::reference::*;
+use crate::rlp::*;
+use crate::variable_length::*;
+use lac::assertion::ASSERTIONS;
+use lac::builder::*;
+use lac::interface::*;
+use lac::utils::*;
+use std::fmt;
+
+//Ethereum block headers. A header is the RLP list of its fields and its block hash is the
+//Keccak-256 of the list, the first field parentHash is the block hash of the header before it,
+//so a chain of headers is valid iff every parentHash is the hash of the previous header. The
+//fields are those of the fork the header belongs to, every fork after Frontier appends fields
+//at the end of the list.
+//
+//The circuit takes the fields of every header as inputs, in the layout of the RLP encoder of
+//RlpListShape, encodes them with the hints of the encoder and hashes the encoding with the
+//variable length Keccak-256 circuit, with max_blocks blocks enough for the largest header of
+//the shape. The Keccak-256 of every header is computed side by side, then the parentHash of
+//every header is compared with the hash of the header before it. The block hashes and the
+//selected fields are outputs, every check is folded into the output group ASSERTIONS.
+
+/// Field of the parent block hash, which chains the headers
+pub const PARENT_HASH: &str = "parentHash";
+
+/// Fields of the header from the set of fields of a fork on, each fork appends its fields to
+/// those of the fork before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFork {
+    /// every block before London, from Frontier to Muir Glacier
+    Frontier,
+    /// baseFeePerGas, EIP-1559
+    London,
+    /// withdrawalsRoot, EIP-4895
+    Shanghai,
+    /// blobGasUsed, excessBlobGas and parentBeaconBlockRoot, EIP-4844 and EIP-4788
+    Cancun,
+    /// requestsHash, EIP-7685
+    Prague,
+}
+
+///Shape of the headers of the fork, the fields are named as in eth_getBlockByNumber
+pub fn get_header_shape(fork: HeaderFork) -> RlpListShape {
+    let mut fields = vec![
+        (PARENT_HASH, RlpField::Fixed(32)),
+        ("sha3Uncles", RlpField::Fixed(32)),
+        ("miner", RlpField::Fixed(20)),
+        ("stateRoot", RlpField::Fixed(32)),
+        ("transactionsRoot", RlpField::Fixed(32)),
+        ("receiptsRoot", RlpField::Fixed(32)),
+        ("logsBloom", RlpField::Fixed(256)),
+        ("difficulty", RlpField::Integer(32)),
+        ("number", RlpField::Integer(8)),
+        ("gasLimit", RlpField::Integer(8)),
+        ("gasUsed", RlpField::Integer(8)),
+        ("timestamp", RlpField::Integer(8)),
+        ("extraData", RlpField::Bytes(32)),
+        ("mixHash", RlpField::Fixed(32)),
+        ("nonce", RlpField::Fixed(8)),
+    ];
+    if fork != HeaderFork::Frontier {
+        fields.push(("baseFeePerGas", RlpField::Integer(32)));
+    }
+    if matches!(
+        fork,
+        HeaderFork::Shanghai | HeaderFork::Cancun | HeaderFork::Prague
+    ) {
+        fields.push(("withdrawalsRoot", RlpField::Fixed(32)));
+    }
+    if matches!(fork, HeaderFork::Cancun | HeaderFork::Prague) {
+        fields.push(("blobGasUsed", RlpField::Integer(8)));
+        fields.push(("excessBlobGas", RlpField::Integer(8)));
+        fields.push(("parentBeaconBlockRoot", RlpField::Fixed(32)));
+    }
+    if fork == HeaderFork::Prague {
+        fields.push(("requestsHash", RlpField::Fixed(32)));
+    }
+    RlpListShape::new(fields)
+}
+
+/// Chains of headers the native verifier or the circuit assignment reject, index is the header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderError {
+    EmptyChain,
+    InvalidHeader { index: usize, error: RlpError },
+    ParentMismatch { index: usize },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeaderError::EmptyChain => write!(f, "the chain has no headers"),
+            HeaderError::InvalidHeader { index, error } => {
+                write!(
+                    f,
+                    "header {} is not a header of the shape: {}",
+                    index, error
+                )
+            }
+            HeaderError::ParentMismatch { index } => write!(
+                f,
+                "the parent hash of header {} is not the hash of the header before it",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HeaderError {}
+
+///Blocks of the variable length Keccak-256 circuit enough for every header of the shape
+pub fn get_header_max_blocks(shape: &RlpListShape) -> u64 {
+    get_blocks_amount(8 * shape.get_max_size() as u64, KECCAK256_RATE)
+}
+
+///Checks natively that the RLP headers are of the shape and that every header is the parent
+///of the next one, returns their block hashes
+pub fn verify_header_chain(
+    shape: &RlpListShape,
+    headers: &[Vec<u8>],
+) -> Result<Vec<[u8; 32]>, HeaderError> {
+    get_header_chain_values(shape, headers)?;
+    Ok(headers
+        .iter()
+        .map(|header| keccak256_reference(header))
+        .collect())
+}
+
+///Circuit hashing headers_amount headers of the shape and checking that they form a chain.
+///The inputs of the header i are "header_i", the wires of its fields as
+///RlpListShape::get_fields_values with the visibility fields_visibility, and the private
+///"hints_i". The output "block_hash_i" has the bits of the block hash of the header i, for
+///every field name of public_fields the output "<name>_i" has its bits and "<name>_size_i" its
+///size for a field of variable size. The output group ASSERTIONS is 0 iff the fields fit the
+///shape and every parentHash is the hash of the header before it. Panics if a public field is
+///not in the shape or if the shape has no parentHash of 32 bytes to chain more than one
+///header.
+pub fn get_header_chain_circuit(
+    shape: &RlpListShape,
+    headers_amount: usize,
+    public_fields: &[&str],
+    fields_visibility: Visibility,
+) -> LAC<i64> {
+    assert!(headers_amount > 0, "the chain needs at least 1 header");
+    let public_indices: Vec<usize> = public_fields
+        .iter()
+        .map(|name| {
+            shape
+                .get_field_index(name)
+                .unwrap_or_else(|| panic!("the shape has no field {}", name))
+        })
+        .collect();
+    let parent_index = shape
+        .get_field_index(PARENT_HASH)
+        .filter(|index| shape.get_fields()[*index].1 == RlpField::Fixed(32));
+    assert!(
+        headers_amount == 1 || parent_index.is_some(),
+        "the shape needs a field {} of 32 bytes to chain the headers",
+        PARENT_HASH
+    );
+    let max_blocks = get_header_max_blocks(shape);
+
+    //the fields and the hints of every header, in this order after the constants
+    let mut size = 2;
+    let mut take = |amount: usize| -> Vec<u64> {
+        let ids = (size..(size + amount as u64)).collect();
+        size += amount as u64;
+        ids
+    };
+    let (field_ids, hint_ids): (Vec<Vec<u64>>, Vec<Vec<u64>>) = (0..headers_amount)
+        .map(|_| {
+            (
+                take(shape.get_fields_wires_amount()),
+                take(shape.get_hints_amount()),
+            )
+        })
+        .unzip();
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; size as usize - 2]));
+
+    //the encoding of every header from its fields, and the selected fields
+    let mut builder = CircuitBuilder::new(1, size);
+    let mut encodings: Vec<RlpEncodingWires> = Vec::new();
+    let mut outputs: Vec<(String, Vec<u64>)> = Vec::new();
+    let mut parents: Vec<Vec<u64>> = Vec::new();
+    for i in 0..headers_amount {
+        encodings.push(shape.append_encoder(&mut builder, &field_ids[i], &hint_ids[i]));
+        let fields = shape.split_fields(&field_ids[i]);
+        if let Some(parent_index) = parent_index.filter(|_| headers_amount > 1) {
+            parents.push(fields[parent_index].to_vec());
+        }
+        for index in public_indices.iter() {
+            let (name, field) = shape.get_fields()[*index];
+            let (bits, size_selector) = fields[*index].split_at(8 * field.get_max_size());
+            outputs.push((format!("{}_{}", name, i), bits.to_vec()));
+            if field.is_variable() {
+                let size: Combination = size_selector
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .map(|(k, u)| (*u, k as i64))
+                    .collect();
+                outputs.push((format!("{}_size_{}", name, i), vec![builder.linear(&size)]));
+            }
+        }
+    }
+    let encoding_check = builder.fold_checks();
+
+    let pass_through: Vec<u64> = [
+        vec![encoding_check],
+        outputs.iter().flat_map(|(_, ids)| ids.clone()).collect(),
+        parents.concat(),
+    ]
+    .concat();
+    let hash_inputs: Vec<u64> = [
+        pass_through.clone(),
+        encodings.iter().flat_map(|e| e.bits.clone()).collect(),
+        encodings.iter().flat_map(|e| e.mask.clone()).collect(),
+    ]
+    .concat();
+    lac.append_layers(builder.finish(&hash_inputs));
+
+    //the Keccak-256 of the headers side by side
+    let degree = lac.get_layers_amount() as u64 + 1;
+    let header_keccak = get_keccak256_variable_length_subcircuit(max_blocks);
+    let mut hashes: Vec<Vec<u64>> = Vec::new();
+    for (i, encoding) in encodings.iter().enumerate() {
+        let inputs =
+            get_keccak256_variable_length_inputs(max_blocks, &encoding.bits, &encoding.mask);
+        let remap = IdRemap::new(lac.get_max_id() + 1 - RESERVED_IDS);
+        let layers = header_keccak.instantiate(degree, &inputs, &remap, &pass_through);
+        //the first instance appends its layers, add_layers would pad with relays of ids the
+        //instance uses internally
+        if i == 0 {
+            lac.append_layers(layers);
+        } else {
+            lac.add_layers(layers).unwrap();
+        }
+        hashes.push(header_keccak.get_instance_output_ids(&remap));
+    }
+
+    //every parentHash is the hash of the header before it
+    let degree = lac.get_layers_amount() as u64 + 1;
+    let mut builder = CircuitBuilder::new(degree, lac.get_max_id() + 1);
+    for (hash, parent) in hashes.iter().zip(parents.iter().skip(1)) {
+        for (hash_bit, parent_bit) in hash.iter().zip(parent.iter()) {
+            builder.assert_zero(&[(*hash_bit, 1), (*parent_bit, -1)]);
+        }
+    }
+    let chain_check = builder.fold_checks();
+    let output_ids: Vec<u64> = [
+        vec![encoding_check, chain_check],
+        hashes.concat(),
+        outputs.iter().flat_map(|(_, ids)| ids.clone()).collect(),
+    ]
+    .concat();
+    lac.append_layers(builder.finish(&output_ids));
+
+    for (i, ((fields, hints), hash)) in field_ids.into_iter().zip(hint_ids).zip(hashes).enumerate()
+    {
+        lac.declare_input(&format!("header_{}", i), fields_visibility, fields)
+            .unwrap();
+        lac.declare_input(&format!("hints_{}", i), Visibility::Private, hints)
+            .unwrap();
+        lac.declare_output(&format!("block_hash_{}", i), hash)
+            .unwrap();
+    }
+    for (name, ids) in outputs {
+        lac.declare_output(&name, ids).unwrap();
+    }
+    lac.declare_output(ASSERTIONS, vec![encoding_check, chain_check])
+        .unwrap();
+    lac
+}
+
+///Inputs of get_header_chain_circuit for the RLP headers, the circuit must have one slot per
+///header. Fails if a header is not of the shape or if the headers do not form a chain.
+pub fn get_header_chain_assignment(
+    shape: &RlpListShape,
+    headers: &[Vec<u8>],
+) -> Result<Assignment<i64>, HeaderError> {
+    let values = get_header_chain_values(shape, headers)?;
+    let mut assignment: Assignment<i64> = Assignment::new();
+    for (i, header) in values.iter().enumerate() {
+        let invalid = |error| HeaderError::InvalidHeader { index: i, error };
+        assignment.set(
+            &format!("header_{}", i),
+            shape.get_fields_values(header).map_err(invalid)?,
+        );
+        assignment.set(
+            &format!("hints_{}", i),
+            shape.get_hints(header).map_err(invalid)?,
+        );
+    }
+    Ok(assignment)
+}
+
+/// Values of the fields of every header, checking the shape and the parent hashes
+fn get_header_chain_values(
+    shape: &RlpListShape,
+    headers: &[Vec<u8>],
+) -> Result<Vec<Vec<Vec<u8>>>, HeaderError> {
+    if headers.is_empty() {
+        return Err(HeaderError::EmptyChain);
+    }
+    let parent_index = shape.get_field_index(PARENT_HASH);
+    let mut values: Vec<Vec<Vec<u8>>> = Vec::new();
+    for (index, header) in headers.iter().enumerate() {
+        let fields = shape
+            .decode(header)
+            .map_err(|error| HeaderError::InvalidHeader { index, error })?;
+        if index > 0 {
+            let parent = parent_index.ok_or(HeaderError::ParentMismatch { index })?;
+            if fields[parent] != keccak256_reference(&headers[index - 1]) {
+                return Err(HeaderError::ParentMismatch { index });
+            }
+        }
+        values.push(fields);
+    }
+    Ok(values)
+}
@@ -0,0 +1,88 @@
+use crate::keccak_circuit::*;
+use lac::interface::*;
+use lac::utils::*;
+
+//Keccak-256 as used by Ethereum: Keccak[r=1088, c=512] with the original Keccak padding,
+//0x01 ... 0x80, and not the 0x06 ... 0x80 of SHA3-256. Bytes are turned into bits least
+//significant bit first, as the Keccak specification reads them.
+
+pub const KECCAK256_RATE: u64 = 1088;
+pub const KECCAK256_CAPACITY: u64 = 512;
+pub const KECCAK256_OUTPUT_BITS: u64 = 256;
+
+///Bits of bytes, least significant bit first in every byte
+pub fn bytes_to_bits(bytes: &[u8]) -> Vec<i64> {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| ((byte >> i) & 1) as i64))
+        .collect()
+}
+
+///Bytes of bits, least significant bit first in every byte, a last partial byte is 0 padded
+pub fn bits_to_bytes(bits: &[i64]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .map(|(i, bit)| (*bit as u8) << i)
+                .sum()
+        })
+        .collect()
+}
+
+///Keccak-256 circuit of message, with the input group "message" and the output group "digest"
+pub fn keccak256_circuit(message: &[u8]) -> LAC<i64> {
+    get_keccak_lac_circuit(
+        bytes_to_bits(message),
+        KECCAK256_RATE,
+        KECCAK256_CAPACITY,
+        KECCAK256_OUTPUT_BITS,
+    )
+}
+
+///Keccak-256 circuit for every message of message_size bytes, a message is set with
+///LAC::assign_inputs and get_message_assignment
+pub fn keccak256_circuit_for_size(message_size: usize) -> LAC<i64> {
+    get_keccak_lac_circuit_for_size(
+        8 * message_size as u64,
+        KECCAK256_RATE,
+        KECCAK256_CAPACITY,
+        KECCAK256_OUTPUT_BITS,
+    )
+    .unwrap()
+}
+
+///Assignment of the bits of message to the input group "message"
+pub fn get_message_assignment(message: &[u8]) -> Assignment<i64> {
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("message", bytes_to_bits(message));
+    assignment
+}
+
+///Circuit checking that message is a preimage of the public input "expected_digest"
+pub fn keccak256_digest_check_circuit(message: &[u8], expected_digest: &[u8; 32]) -> LAC<i64> {
+    let mut lac = get_keccak_digest_check_circuit(
+        8 * message.len() as u64,
+        KECCAK256_RATE,
+        KECCAK256_CAPACITY,
+        KECCAK256_OUTPUT_BITS,
+    )
+    .unwrap();
+    lac.set_input("message", &bytes_to_bits(message)).unwrap();
+    lac.set_input("expected_digest", &bytes_to_bits(expected_digest))
+        .unwrap();
+    lac
+}
+
+///Digest of an evaluated Keccak circuit as bytes
+pub fn get_digest_bytes(lac: &LAC<i64>) -> Vec<u8> {
+    let outputs = lac.get_named_outputs().unwrap();
+    bits_to_bytes(&outputs["digest"])
+}
+
+///Keccak-256 of message, computed by evaluating its circuit
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    let mut lac = keccak256_circuit(message);
+    lac.evaluate();
+    get_digest_bytes(&lac).try_into().unwrap()
+}
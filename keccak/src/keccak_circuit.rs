@@ -1,36 +1,399 @@
 use crate::keccak_f_circuit::*;
+use lac::interface::*;
+use lac::subcircuit::*;
 use lac::utils::*;
 use lac::xor::*;
+use std::fmt;
+
+/// Keccak parameters the circuit can not be built for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeccakError {
+    /// r + c is not 25w for w in 1, 2, 4, ..., 64
+    InvalidWidth { r: u64, c: u64 },
+    /// r or c is 0
+    InvalidRate { r: u64, c: u64 },
+    /// l is 0
+    InvalidOutputLength,
+    /// rounds is not between 1 and 12 + 2l for w = 2^l
+    InvalidRoundsAmount { rounds: usize, w: u64 },
+    /// max_blocks blocks of r bits have no room for the suffix and the padding
+    InvalidBlocksAmount { max_blocks: u64, r: u64 },
+}
+
+impl fmt::Display for KeccakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeccakError::InvalidWidth { r, c } => write!(
+                f,
+                "rate + capacity must be 25 * w for w in 1, 2, 4, ..., 64, got {}",
+                r + c
+            ),
+            KeccakError::InvalidRate { r, c } => write!(
+                f,
+                "rate and capacity must be positive, got {} and {}",
+                r, c
+            ),
+            KeccakError::InvalidOutputLength => write!(f, "output length must be positive"),
+            KeccakError::InvalidRoundsAmount { rounds, w } => write!(
+                f,
+                "rounds must be between 1 and {} for w = {}, got {}",
+                get_keccak_f_rounds_amount(*w),
+                w,
+                rounds
+            ),
+            KeccakError::InvalidBlocksAmount { max_blocks, r } => write!(
+                f,
+                "{} blocks of {} bits can not hold the suffix and the padding",
+                max_blocks, r
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeccakError {}
+
+///Checks that Keccak[r, c] with an output of l bits exists
+pub fn check_keccak_parameters(r: u64, c: u64, l: u64) -> Result<(), KeccakError> {
+    let w = (r + c) / 25;
+    if !(r + c).is_multiple_of(25) || !w.is_power_of_two() || w > 64 {
+        return Err(KeccakError::InvalidWidth { r, c });
+    }
+    if r == 0 || c == 0 {
+        return Err(KeccakError::InvalidRate { r, c });
+    }
+    if l == 0 {
+        return Err(KeccakError::InvalidOutputLength);
+    }
+    Ok(())
+}
+
+///Checks that Keccak[r, c] with an output of l bits exists and that Keccak-p[r + c, rounds]
+///is one of its permutations, from 1 round to the rounds of Keccak-f
+pub fn check_keccak_p_parameters(r: u64, c: u64, l: u64, rounds: usize) -> Result<(), KeccakError> {
+    check_keccak_parameters(r, c, l)?;
+    let w = (r + c) / 25;
+    if rounds == 0 || rounds > get_keccak_f_rounds_amount(w) {
+        return Err(KeccakError::InvalidRoundsAmount { rounds, w });
+    }
+    Ok(())
+}
 
 ///c - capacity, r - bitrate, l - output length
 pub fn get_keccak_lac_circuit(input: Vec<i64>, r: u64, c: u64, l: u64) -> LAC<i64> {
+    get_keccak_lac_circuit_with_suffix(input, Vec::new(), r, c, l).unwrap()
+}
+
+///Keccak circuit whose input is followed by the domain suffix bits before the pad10*1, [0, 1]
+///for SHA3 and [1, 1, 1, 1] for SHAKE, the suffix is not part of the input group "message"
+pub fn get_keccak_lac_circuit_with_suffix(
+    input: Vec<i64>,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+) -> Result<LAC<i64>, KeccakError> {
+    check_keccak_parameters(r, c, l)?;
+    let rounds = get_keccak_f_rounds_amount((r + c) / 25);
+    get_keccak_p_lac_circuit(input, suffix, r, c, l, rounds)
+}
+
+///Keccak circuit as get_keccak_lac_circuit_with_suffix whose sponge uses Keccak-p[r + c, rounds],
+///the last rounds of Keccak-f, as KangarooTwelve and TurboSHAKE with 12 rounds of Keccak-f[1600]
+pub fn get_keccak_p_lac_circuit(
+    input: Vec<i64>,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+    rounds: usize,
+) -> Result<LAC<i64>, KeccakError> {
+    let mut lac =
+        get_keccak_p_lac_circuit_for_size(input.len() as u64, suffix, r, c, l, rounds)?;
+    lac.set_input("message", &input).unwrap();
+    Ok(lac)
+}
+
+///Keccak circuit for every message of input_size bits, its shape only depends on the
+///parameters. The input group "message" is 0 until it is set, see LAC::assign_inputs.
+pub fn get_keccak_lac_circuit_for_size(
+    input_size: u64,
+    r: u64,
+    c: u64,
+    l: u64,
+) -> Result<LAC<i64>, KeccakError> {
+    check_keccak_parameters(r, c, l)?;
+    let rounds = get_keccak_f_rounds_amount((r + c) / 25);
+    get_keccak_p_lac_circuit_for_size(input_size, Vec::new(), r, c, l, rounds)
+}
+
+///get_keccak_p_lac_circuit for every message of input_size bits, see
+///get_keccak_lac_circuit_for_size
+pub fn get_keccak_p_lac_circuit_for_size(
+    input_size: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+    rounds: usize,
+) -> Result<LAC<i64>, KeccakError> {
+    check_keccak_p_parameters(r, c, l, rounds)?;
+    let input = vec![0; input_size as usize];
+    let mut lac = get_keccak_hash_lac(input, suffix, r, c, l, rounds);
+
+    let message_ids: Vec<u64> = (2..(2 + input_size)).collect();
+    lac.declare_input("message", Visibility::Private, message_ids).unwrap();
+    lac.declare_output("digest", get_digest_ids(l)).unwrap();
+
+    Ok(lac)
+}
+
+///Keccak circuit for every private "message" of input_size bits and the public input
+///"expected_digest" of l bits. Its output "digest_check" is 0 iff the digest of the message is
+///expected_digest and 1 otherwise, the output "digest" is that digest. The inputs are 0 until
+///they are set, see LAC::assign_inputs.
+pub fn get_keccak_digest_check_circuit(
+    input_size: u64,
+    r: u64,
+    c: u64,
+    l: u64,
+) -> Result<LAC<i64>, KeccakError> {
+    check_keccak_parameters(r, c, l)?;
+    let rounds = get_keccak_f_rounds_amount((r + c) / 25);
+    let mut lac = get_keccak_p_lac_circuit_for_size(input_size, Vec::new(), r, c, l, rounds)?;
+
+    let one_id = lac.get_max_id() + 1;
+    let expected_ids: Vec<u64> = ((one_id + 1)..(one_id + 1 + l)).collect();
+    let check_id = one_id + 1 + l;
+    let mut basic_layer = lac.get_basic_layer().clone();
+    for id in expected_ids.iter() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(*id, 0);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+
+    //the digest and the constants are relayed next to the check
+    let degree = lac.get_layers_amount() as u64;
+    let digest_ids = get_digest_ids(l);
+    let relay_layers = get_carried_layers(expected_ids.clone(), one_id, degree);
+    lac.add_layers(relay_layers).unwrap();
+    let mut check_layers = get_digest_check_layers(
+        digest_ids.clone(),
+        expected_ids.clone(),
+        one_id,
+        check_id,
+        degree + 1,
+    );
+    for layer in check_layers.iter_mut() {
+        let degree = layer.get_degree();
+        layer.add_gate_0_and_1(degree);
+        layer.copy_gates_by_ids(digest_ids.clone());
+    }
+    lac.append_layers(check_layers);
+
+    lac.declare_input("expected_digest", Visibility::Public, expected_ids).unwrap();
+    lac.declare_output("digest_check", vec![check_id]).unwrap();
+
+    Ok(lac)
+}
+
+/// Layers 1 to layers_amount carrying the basic values ids and a 1 at one_id, as g=x*1 with the
+/// carried 1, so they do not read the constants of the keccak layers
+pub fn get_carried_layers(ids: Vec<u64>, one_id: u64, layers_amount: u64) -> Vec<Layer<i64>> {
+    let mut layers: Vec<Layer<i64>> = Vec::new();
+    for degree in 1..=layers_amount {
+        let mut layer: Layer<i64> = Layer::new();
+        layer.set_degree(degree);
+        let one = if degree == 1 { 1 } else { one_id };
+        for id in ids.iter().chain([one_id].iter()) {
+            let input = if *id == one_id { one } else { *id };
+            let mut gate: Gate<i64> = Gate::new_mult_gate();
+            gate.set_all(Some(degree), Some(*id), Some([input, one]), None, None);
+            layer.append_gate(gate);
+        }
+        layers.push(layer);
+    }
+    layers
+}
+
+/// Compares the digest bits with the carried expected bits and the carried 1 of one_id, by an
+/// OR reduction of their XORs:
+/// layer1:    g_i=(z_i - e_i)*(z_i - e_i) at the ids of e_i, the XOR of the bits
+/// layer2:    g=(1 - x_a)*(1 - x_b) for each pair of XORs, 1 iff the bits of both pairs match
+/// layer3..:  g=x_a*x_b for each pair until one value is left, the AND of the matches
+/// last:      g_out=(1 - x)*1, the OR of the XORs
+/// An unpaired value is kept with 1 as its other factor, the 1 is carried up to the last layer.
+pub fn get_digest_check_layers(
+    digest_ids: Vec<u64>,
+    expected_ids: Vec<u64>,
+    one_id: u64,
+    out_id: u64,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    assert!(!expected_ids.is_empty(), "the digest check needs a bit");
+    let mut layer0: Layer<i64> = Layer::new();
+    layer0.set_degree(degree);
+    for (z, e) in digest_ids.into_iter().zip(expected_ids.clone()) {
+        let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+        gate.set_all(
+            Some(degree),
+            Some(e),
+            None,
+            Some([vec![z, e], vec![z, e]]),
+            Some([vec![1, -1], vec![1, -1]]),
+        );
+        layer0.append_gate(gate);
+    }
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(Some(degree), Some(one_id), Some([one_id, one_id]), None, None);
+    layer0.append_gate(gate);
+    let mut layers = vec![layer0];
+
+    let mut ids = expected_ids;
+    let mut degree = degree + 1;
+    loop {
+        let mut layer: Layer<i64> = Layer::new();
+        layer.set_degree(degree);
+        for pair in ids.chunks(2) {
+            let other = *pair.get(1).unwrap_or(&one_id);
+            let mut gate: Gate<i64>;
+            if layers.len() == 1 {
+                gate = Gate::new_R1CS_gate();
+                let (right, weights) = if pair.len() == 2 {
+                    (vec![one_id, other], vec![1, -1])
+                } else {
+                    (vec![one_id], vec![1])
+                };
+                gate.set_all(
+                    Some(degree),
+                    Some(pair[0]),
+                    None,
+                    Some([vec![one_id, pair[0]], right]),
+                    Some([vec![1, -1], weights]),
+                );
+            } else {
+                gate = Gate::new_mult_gate();
+                gate.set_all(Some(degree), Some(pair[0]), Some([pair[0], other]), None, None);
+            }
+            layer.append_gate(gate);
+        }
+        let mut gate: Gate<i64> = Gate::new_mult_gate();
+        gate.set_all(Some(degree), Some(one_id), Some([one_id, one_id]), None, None);
+        layer.append_gate(gate);
+        layers.push(layer);
+        ids = ids.into_iter().step_by(2).collect();
+        degree += 1;
+        if ids.len() == 1 {
+            break;
+        }
+    }
+
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        Some([vec![one_id, ids[0]], vec![one_id]]),
+        Some([vec![1, -1], vec![1]]),
+    );
+    layer.append_gate(gate);
+    layers.push(layer);
+    layers
+}
+
+/// Keccak layers without the interface, the digest is at the ids of get_digest_ids of the last
+/// layer
+fn get_keccak_hash_lac(
+    input: Vec<i64>,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+    rounds: usize,
+) -> LAC<i64> {
     let mut lac: LAC<i64> = LAC::new();
 
-    let mut degree: u64 = 0;
     let basic_layer = get_keccak_basic_layer(input.clone());
     lac.set_basic_layer(basic_layer);
-    degree += 1;
+    lac.append_layers(get_keccak_hash_layers(input.len() as u64, suffix, r, c, l, rounds));
+
+    lac
+}
 
-    let blocks_amount = (input.len() as u64 - 1) / r + 1;
+/// Layers of get_keccak_hash_lac for an input of input_size bits
+pub(crate) fn get_keccak_hash_layers(
+    input_size: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+    rounds: usize,
+) -> Vec<Layer<i64>> {
+    let blocks_amount = get_blocks_amount(input_size + suffix.len() as u64, r);
     let w = (r + c) / 25;
 
-    let first_layer = get_keccak_first_layer(input.len() as u64, r, w, blocks_amount);
-    lac.append_layer(first_layer);
-    degree += 1;
+    let mut layers = vec![get_keccak_first_layer(input_size, suffix, r, w, blocks_amount)];
 
-    let absorbing_phase_layers = get_keccak_absorbing_phase_layers(blocks_amount, r, w);
-    lac.append_layers(absorbing_phase_layers.clone());
-    degree += 2 + absorbing_phase_layers.len() as u64;
+    let mut absorbing_phase_layers = get_keccak_absorbing_phase_layers(blocks_amount, r, w, rounds);
+    let degree = 2 + absorbing_phase_layers.len() as u64;
+    layers.append(&mut absorbing_phase_layers);
 
-    let squeezing_phase_layers = get_keccak_squeezing_phase_layers(r, degree, w, l);
-    lac.append_layers(squeezing_phase_layers.clone());
+    let s_ids = get_state_ids(blocks_amount, r, w);
+    let mut squeezing_phase_layers = get_keccak_squeezing_phase_layers(s_ids, r, degree, w, l, rounds);
+    layers.append(&mut squeezing_phase_layers);
 
-    lac
+    layers
+}
+
+/// Template of the Keccak layers whose first layer reads input_ids, with the digest of l bits
+/// moved from the ids of get_digest_ids of the last layer to ids above all the others, so that
+/// instances can share their layers and be read after them
+pub(crate) fn get_keccak_hash_subcircuit(
+    mut layers: Vec<Layer<i64>>,
+    input_ids: Vec<u64>,
+    l: u64,
+) -> SubCircuit<i64> {
+    let digest_start = layers
+        .iter()
+        .flat_map(|layer| layer.get_gate_ids())
+        .chain(input_ids.iter().copied())
+        .max()
+        .unwrap()
+        + 1;
+    let last_layer = layers.pop().unwrap().remapped(
+        &|id| {
+            if id < RESERVED_IDS {
+                id
+            } else {
+                digest_start + id - RESERVED_IDS
+            }
+        },
+        &|id| id,
+    );
+    layers.push(last_layer);
+    SubCircuit::new(layers, input_ids, (digest_start..(digest_start + l)).collect())
+}
+
+/// Blocks of r bits of the padded input, pad10*1 appends at least 2 bits
+pub fn get_blocks_amount(input_size: u64, r: u64) -> u64 {
+    (input_size + 1) / r + 1
+}
+
+/// Ids of the digest of l bits in the last layer, after the constants 0 and 1 that it keeps so
+/// that the circuit can be padded and merged
+pub fn get_digest_ids(l: u64) -> Vec<u64> {
+    (RESERVED_IDS..(RESERVED_IDS + l)).collect()
+}
+
+/// Ids of the state, placed after the padded input
+pub(crate) fn get_state_ids(blocks_amount: u64, r: u64, w: u64) -> Vec<u64> {
+    ((2 + blocks_amount * r)..(2 + blocks_amount * r + 25 * w)).collect()
 }
 
 ///layer with 0, 1, input
 pub fn get_keccak_basic_layer(input: Vec<i64>) -> BasicLayer<i64> {
-    let size = input.len();
     let mut basic_layer = BasicLayer::new();
     let mut value0: Value<i64> = Value::new();
     let mut value1: Value<i64> = Value::new();
@@ -39,46 +402,59 @@ pub fn get_keccak_basic_layer(input: Vec<i64>) -> BasicLayer<i64> {
     value1.set_all(1, 1);
     basic_layer.append_values(vec![value0, value1]);
 
-    for i in 0..size {
+    for (i, bit) in input.iter().enumerate() {
         let mut value0: Value<i64> = Value::new();
         let id = i as u64 + 2;
-        value0.set_all(id, input[i]);
+        value0.set_all(id, *bit);
         basic_layer.append_value(value0);
     }
 
     basic_layer
 }
 
-/// First layer is 0, 1, input, padding, S[x][y][w]
-/// I'm using a 10*1 padding
-pub fn get_keccak_first_layer(input_size: u64, r: u64, w: u64, blocks_amount: u64) -> Layer<i64> {
-    //0, 1, input
+/// First layer is 0, 1, the padded blocks 1.. of the input and the state S, which is the
+/// block 0 followed by 0s. Bits are least significant bit first in every byte, the input is
+/// followed by the suffix bits and the 10*1 padding of Keccak, 0x01 ... 0x80 for inputs of
+/// whole bytes and no suffix.
+pub fn get_keccak_first_layer(
+    input_size: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    w: u64,
+    blocks_amount: u64,
+) -> Layer<i64> {
     let mut layer: Layer<i64> = Layer::new();
     layer.set_degree(1);
-    layer.copy_gates_by_ids((0..(input_size + 1)).collect());
-    //padding 10*1
-    let mut gate: Gate<i64> = Gate::new_add_gate();
-    gate.set_all(Some(1), Some(input_size + 1), Some([0, 1]), None, None);
-    layer.append_gate(gate);
-    for i in (input_size + 2)..(blocks_amount * r - 1) {
+    layer.add_gate_0_and_1(1);
+
+    assert!(
+        suffix.iter().all(|bit| *bit == 0 || *bit == 1),
+        "suffix bits must be 0 or 1"
+    );
+    let padded_size = blocks_amount * r;
+    let suffixed_size = input_size + suffix.len() as u64;
+    //id of the bit i of the padded input, a relay of the input or a padding constant
+    let padded_bit = |i: u64| -> [u64; 2] {
+        if i < input_size {
+            [0, 2 + i]
+        } else if i < suffixed_size {
+            [0, suffix[(i - input_size) as usize] as u64]
+        } else if i == suffixed_size || i == padded_size - 1 {
+            [0, 1]
+        } else {
+            [0, 0]
+        }
+    };
+
+    for i in r..padded_size {
         let mut gate: Gate<i64> = Gate::new_add_gate();
-        gate.set_all(Some(1), Some(i), Some([0, 0]), None, None);
+        gate.set_all(Some(1), Some(2 + i), Some(padded_bit(i)), None, None);
         layer.append_gate(gate);
     }
-    let mut gate: Gate<i64> = Gate::new_add_gate();
-    gate.set_all(
-        Some(1),
-        Some(blocks_amount * r - 1),
-        Some([0, 1]),
-        None,
-        None,
-    );
-    layer.append_gate(gate);
-
-    // S[x][y][w] = 0 for x, y int 0..4 and w in 0..(c+r)/25
-    for i in (blocks_amount * r)..(blocks_amount * r + 5 * 5 * w) {
+    for (i, id) in get_state_ids(blocks_amount, r, w).into_iter().enumerate() {
+        let input_id = if (i as u64) < r { padded_bit(i as u64) } else { [0, 0] };
         let mut gate: Gate<i64> = Gate::new_add_gate();
-        gate.set_all(Some(1), Some(i), Some([0, 0]), None, None);
+        gate.set_all(Some(1), Some(id), Some(input_id), None, None);
         layer.append_gate(gate);
     }
 
@@ -86,63 +462,90 @@ pub fn get_keccak_first_layer(input_size: u64, r: u64, w: u64, blocks_amount: u6
 }
 
 /// Absorbing phase is made by concatenating two operations,
-/// 1: S_i_subst = P_i | S_i_subst    where S_i_subst as size r/w
-/// 2: S_(i+1)1 = f(S_i)
-/// Where f is the Keccak-f
-pub fn get_keccak_absorbing_phase_layers(blocks_amount: u64, r: u64, w: u64) -> Vec<Layer<i64>> {
+/// 1: S_i_subst = P_i xor S_i_subst    where S_i_subst as size r, done by the first layer for P_0
+/// 2: S_(i+1) = f(S_i)
+/// Where f is the Keccak-p of rounds rounds, Keccak-f for 12 + 2l rounds
+pub fn get_keccak_absorbing_phase_layers(
+    blocks_amount: u64,
+    r: u64,
+    w: u64,
+    rounds: usize,
+) -> Vec<Layer<i64>> {
     let mut layers: Vec<Layer<i64>> = Vec::new();
     let mut degree = 2;
+    let s_i = get_state_ids(blocks_amount, r, w);
+    let keccak_f = get_keccak_p_subcircuit(s_i.clone(), w, rounds);
     for i in 0..blocks_amount {
-        let p_i: Vec<u64> = ((2 + i * r)..(2 + (i + 1) * r)).collect();
-        let s_i_subst: Vec<u64> = ((2 + blocks_amount * r)..(2 + blocks_amount * r + r)).collect();
-        let mut layers_xor = get_xor_bitstring_as_layers(s_i_subst.clone(), p_i, s_i_subst, 2);
-        layers_xor[0].copy_gates_by_ids(((2 + blocks_amount * r + r)..(2 + blocks_amount * r + 5 * 5 * w)).collect());
-        layers_xor[1].copy_gates_by_ids(((2 + blocks_amount * r + r)..(2 + blocks_amount * r + 5 * 5 * w)).collect());
-        layers.append(&mut layers_xor);
-        degree += 2;
-
-        let s_i: Vec<u64> =
-            ((2 + blocks_amount * r)..(2 + blocks_amount * r + 5 * 5 * w)).collect();
-
-        let mut keccak_f_layers: Vec<Layer<i64>> = get_keccak_f_layers(s_i, degree, w);
-        for i in 0..keccak_f_layers.len() {
-            keccak_f_layers[i].copy_gates_by_ids(((2 + (i as u64 + 1) * r)..(2 + (blocks_amount * r))).collect());
+        let remaining_blocks: Vec<u64> = ((2 + (i + 1) * r)..(2 + (blocks_amount * r))).collect();
+        if i > 0 {
+            let p_i: Vec<u64> = ((2 + i * r)..(2 + (i + 1) * r)).collect();
+            let s_i_subst: Vec<u64> = s_i[..(r as usize)].to_vec();
+            let mut layer = get_xor_bitstring_as_layer(s_i_subst.clone(), p_i, s_i_subst, degree);
+            layer.set_degree(degree);
+            layer.add_gate_0_and_1(degree);
+            layer.copy_gates_by_ids(s_i[(r as usize)..].to_vec());
+            layer.copy_gates_by_ids(remaining_blocks.clone());
+            layers.push(layer);
+            degree += 1;
         }
-        layers.append(&mut keccak_f_layers);
+
+        let mut keccak_f_layers: Vec<Layer<i64>> =
+            keccak_f.instantiate(degree, &s_i, &IdRemap::new(0), &remaining_blocks);
         degree += keccak_f_layers.len() as u64;
+        layers.append(&mut keccak_f_layers);
     }
 
     layers
 }
 
 /// Squeezing phase is made by concatenating two operations,
-/// 1: Z = Z || S_i_substr      where S_i_substr as size r/w
+/// 1: Z = Z || S_i_substr      where S_i_substr as size r
 /// 2: S_(i+1) = f(S_i)
-/// Where f is the Keccak-f
-/// We will iterate this loop until |Z| >= l, the output_size
-pub fn get_keccak_squeezing_phase_layers(r: u64, mut degree: u64, w: u64, l: u64) -> Vec<Layer<i64>> {
+/// Where f is the Keccak-p of rounds rounds, Keccak-f for 12 + 2l rounds
+/// We will iterate this loop until |Z| >= l, the output_size. The last layer holds the
+/// constants and the l bits of Z at the ids of get_digest_ids.
+pub fn get_keccak_squeezing_phase_layers(
+    s_ids: Vec<u64>,
+    r: u64,
+    mut degree: u64,
+    w: u64,
+    l: u64,
+    rounds: usize,
+) -> Vec<Layer<i64>> {
     let mut layers: Vec<Layer<i64>> = Vec::new();
-    let out_ids_start = 1e9 as u64;
-    for i in 0..((l-1)/r+1) {
-        let out_ids = ((out_ids_start + i*r)..(out_ids_start + (i+1)*r)).collect();
+    let out_ids_start = 3 * 1e9 as u64;
+    let keccak_f = get_keccak_p_subcircuit(s_ids.clone(), w, rounds);
+    let squeezes_amount = (l - 1) / r + 1;
+    for i in 0..(squeezes_amount - 1) {
         let mut layer: Layer<i64> = Layer::new();
         layer.set_degree(degree);
-        layer.copy_gates_by_ids(((out_ids_start)..(out_ids_start + i*r)).collect());
-        let curr_s_substr: Vec<u64> = ((2 + i * (25*w))..(2 + i * (25*w) + r)).collect();
-        layer.copy_gates_by_ids_set_out(curr_s_substr, out_ids);
+        layer.add_gate_0_and_1(degree);
+        layer.copy_gates_by_ids(s_ids.clone());
+        layer.copy_gates_by_ids((out_ids_start..(out_ids_start + i * r)).collect());
+        let out_ids = ((out_ids_start + i * r)..(out_ids_start + (i + 1) * r)).collect();
+        layer.copy_gates_by_ids_set_out(s_ids[..(r as usize)].to_vec(), out_ids);
+        layers.push(layer);
         degree += 1;
 
-        let curr_s: Vec<u64> = ((2 + i * (25*w))..(2 + (i + 1) * (25*w))).collect();
-        let mut layers_keccak_f = get_keccak_f_layers(curr_s, degree, w);
+        let squeezed: Vec<u64> = (out_ids_start..(out_ids_start + (i + 1) * r)).collect();
+        let mut layers_keccak_f = keccak_f.instantiate(degree, &s_ids, &IdRemap::new(0), &squeezed);
         degree += layers_keccak_f.len() as u64;
-        for j in 0..layers_keccak_f.len() {
-            layers_keccak_f[i as usize].copy_gates_by_ids(((out_ids_start)..(out_ids_start + (i as u64 +1)*r)).collect());
-            layers.push(layers_keccak_f[i as usize].clone());
-        }
+        layers.append(&mut layers_keccak_f);
     }
 
+    let squeezed = (squeezes_amount - 1) * r;
+    let digest_ids = get_digest_ids(l);
     let mut last_layer: Layer<i64> = Layer::new();
-    last_layer.copy_gates_by_ids_set_out(((out_ids_start)..(out_ids_start + l)).collect(), (0..l).collect());
+    last_layer.set_degree(degree);
+    last_layer.add_gate_0_and_1(degree);
+    last_layer.copy_gates_by_ids_set_out(
+        (out_ids_start..(out_ids_start + squeezed)).collect(),
+        digest_ids[..(squeezed as usize)].to_vec(),
+    );
+    last_layer.copy_gates_by_ids_set_out(
+        s_ids[..((l - squeezed) as usize)].to_vec(),
+        digest_ids[(squeezed as usize)..].to_vec(),
+    );
     layers.push(last_layer);
 
     layers
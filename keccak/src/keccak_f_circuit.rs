@@ -1,6 +1,7 @@
-use lac::and::*;
-use lac::not::*;
+use lac::subcircuit::*;
 use lac::utils::*;
+use lac::word::*;
+use lac::xnor::*;
 use lac::xor::*;
 
 /// RC for the first 24 rounds
@@ -36,283 +37,266 @@ const ROT: [u64; 25] = [
     0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
 ];
 
-pub fn get_keccak_f_layers(input_ids: Vec<u64>, degree: u64, w: u64) -> Vec<Layer<i64>> {
-    let mut layers: Vec<Layer<i64>> = Vec::new();
-    let mut n = 12;
-    let mut pow = 1;
-    while pow != w {
-        pow *= 2;
-        n += 2;
-    }
+//The state is 25 lanes of w bits, bit z of lane (x, y) is input_ids[(x + 5y)w + z], so lanes are
+//stored least significant bit first as in the Keccak specification (see get_lane_ids).
+//Gates compute over the integers, theta keeps some values as v in {-1, 0, 1}, standing for the
+//bit v^2, which lets it xor 3 bits with 1 gate. A round uses 7 layers, all of them hold the
+//constants 0 and 1:
+//  layer1:    p0=(a[x][0]-a[x][1])^2     p1=(a[x][2]-a[x][3])^2
+//  layer2:    t[x]=(p0+p1+a[x][4]-2)*(a[x][4]-p0-p1)                  C[x]=t[x]^2
+//  layer3:    d[x]=(t[x-1]-rot(t[x+1],1))*(t[x-1]+rot(t[x+1],1))      D[x]=d[x]^2
+//  layer4:    v=(a-d[x])*(a+d[x])                                     theta(a)=v^2
+//  layer5:    b=v*v at the rho and pi positions
+//  layer6:    u=(1-b[x+1])*b[x+2]
+//  layer7:    a=(b-u)^2, or 1-(b-u)^2 for the bits of RC in lane (0, 0)
+//Temporary values use the ids from 1e9 and 2e9.
+
+/// Layers of a round
+pub const ROUND_LAYERS: u64 = 7;
+
+/// Keccak-f as a template whose input and output ports are the state ids `input_ids`
+pub fn get_keccak_f_subcircuit(input_ids: Vec<u64>, w: u64) -> SubCircuit<i64> {
+    get_keccak_p_subcircuit(input_ids, w, get_keccak_f_rounds_amount(w))
+}
 
-    for i in 0..n {
-        let mut layer_f_fun = get_keccak_f_round_layers(input_ids.clone(), degree, w, i);
-        layers.append(&mut layer_f_fun);
-    }
+/// Keccak-p[25w, rounds] as a template whose input and output ports are the state ids
+/// `input_ids`
+pub fn get_keccak_p_subcircuit(input_ids: Vec<u64>, w: u64, rounds: usize) -> SubCircuit<i64> {
+    let layers = get_keccak_p_layers(input_ids.clone(), 1, w, rounds);
+    SubCircuit::new(layers, input_ids.clone(), input_ids)
+}
 
-    /*let mut gate_amount = 0;
-    for i in 0..layers.len() {
-        gate_amount += layers[i].gates_amount();
-    }
-    println!("keccak_f ===> layers: {:?}, gates: {:?}", layers.len(), gate_amount);*/
+/// Rounds of Keccak-f[25w], 12 + 2l for w = 2^l
+pub fn get_keccak_f_rounds_amount(w: u64) -> usize {
+    12 + 2 * w.trailing_zeros() as usize
+}
 
-    layers
+pub fn get_keccak_f_layers(input_ids: Vec<u64>, degree: u64, w: u64) -> Vec<Layer<i64>> {
+    get_keccak_p_layers(input_ids, degree, w, get_keccak_f_rounds_amount(w))
 }
 
-/// layers: 27      gates: 43940
-pub fn get_keccak_f_round_layers(
+/// Keccak-p[25w, rounds], the last rounds of Keccak-f as in FIPS 202, from the round
+/// 12 + 2l - rounds to the round 12 + 2l - 1. Rounds are between 1 and 12 + 2l.
+pub fn get_keccak_p_layers(
     input_ids: Vec<u64>,
-    mut degree: u64,
+    degree: u64,
     w: u64,
-    round: usize,
+    rounds: usize,
 ) -> Vec<Layer<i64>> {
+    let rounds_amount = get_keccak_f_rounds_amount(w);
+    assert!(
+        rounds > 0 && rounds <= rounds_amount,
+        "Keccak-p needs between 1 and {} rounds for w = {}, got {}",
+        rounds_amount,
+        w,
+        rounds
+    );
     let mut layers: Vec<Layer<i64>> = Vec::new();
-    let mut omega_step_layers = get_keccak_f_omega_step_layers(input_ids.clone(), degree, w);
-    for i in 0..omega_step_layers.len() {
-        let degree = omega_step_layers[i].get_degree();
-        omega_step_layers[i].add_gate_0_and_1(degree);
+    for (i, round) in ((rounds_amount - rounds)..rounds_amount).enumerate() {
+        let round_degree = degree + i as u64 * ROUND_LAYERS;
+        let mut round_layers = get_keccak_f_round_layers(input_ids.clone(), round_degree, w, round);
+        layers.append(&mut round_layers);
     }
-    layers.append(&mut omega_step_layers);
-    degree += omega_step_layers.len() as u64;
-
-    let mut pi_step_layer = get_keccak_f_pi_rho_steps_layer(input_ids.clone(), degree, w);
-    pi_step_layer.add_gate_0_and_1(degree);
-    layers.push(pi_step_layer);
-    degree += 1;
-
-    let mut chi_step_layer = get_keccak_f_chi_step_layer(input_ids.clone(), degree, w);
-    for i in 0..chi_step_layer.len() {
-        chi_step_layer[i].add_gate_0_and_1(degree);
-        layers.push(chi_step_layer[i].clone());
-        degree += 1;
-    }
-
-    let mut iota_step_layer = get_keccak_f_iota_step_layer(input_ids.clone(), degree, w, RC[round]);
-    iota_step_layer.add_gate_0_and_1(degree);
-    layers.push(iota_step_layer);
-
-    /*let mut gate_amount = 0;
-    for i in 0..layers.len() {
-        gate_amount += layers[i].gates_amount();
-    }
-    println!("keccak_f round ===> layers: {:?}, gates: {:?}", layers.len(), gate_amount);*/
-
     layers
 }
 
-/// layers: 12      gates: 23052
-#[allow(non_snake_case)]
-pub fn get_keccak_f_omega_step_layers(
+/// layers: 7       gates: 14094 for w = 64
+pub fn get_keccak_f_round_layers(
     input_ids: Vec<u64>,
-    mut degree: u64,
+    degree: u64,
     w: u64,
+    round: usize,
 ) -> Vec<Layer<i64>> {
     let mut layers: Vec<Layer<i64>> = Vec::new();
-    let mut curr_degree = degree;
-    for i in 0..6 {
-        let mut layer0: Layer<i64> = Layer::new();
-        let mut layer1: Layer<i64> = Layer::new();
-        layer0.set_degree(degree + 2 * i);
-        layer1.set_degree(degree + 2 * i + 1);
-        layer0.copy_gates_by_ids(input_ids.clone());
-        if i != 5 {
-            layer1.copy_gates_by_ids(input_ids.clone());
-        }
-        layers.append(&mut vec![layer0, layer1]);
+    layers.append(&mut get_keccak_f_theta_step_layers(
+        input_ids.clone(),
+        degree,
+        w,
+    ));
+    layers.push(get_keccak_f_pi_rho_steps_layer(
+        input_ids.clone(),
+        degree + 4,
+        w,
+    ));
+    layers.append(&mut get_keccak_f_chi_iota_steps_layers(
+        input_ids,
+        degree + 5,
+        w,
+        RC[round],
+    ));
+    for layer in layers.iter_mut() {
+        let degree = layer.get_degree();
+        layer.add_gate_0_and_1(degree);
     }
+    layers
+}
 
-    for i in 0..5 {
-        let out_ids_C: Vec<u64> = (((1e9 as u64) + i * w)..((1e9 as u64) + (i + 1) * w)).collect();
-        for j in 1..5 {
-            let in_ids0: Vec<u64> =
-                input_ids[(((i * 5 + j) * w) as usize)..(((i * 5 + j + 1) * w) as usize)].to_vec();
-            let in_ids1: Vec<u64>;
-            if j == 0 {
-                in_ids1 =
-                    input_ids[(((i * 5) * w) as usize)..(((i * 5 + 1) * w) as usize)].to_vec();
-            } else {
-                in_ids1 = out_ids_C.clone();
-            }
-            let layers_xor = get_xor_bitstring_as_layers(
-                in_ids0.clone(),
-                in_ids1.clone(),
-                out_ids_C.clone(),
-                curr_degree + 2 * (j - 1),
-            );
-            layers[(2 * (j - 1)) as usize].merge_layer(layers_xor[0].clone());
-            layers[(2 * (j - 1) + 1) as usize].merge_layer(layers_xor[1].clone());
-        }
+/// Theta step, the result is v with theta(a) = v^2 at input_ids
+/// layers: 4       gates: 7680 for w = 64
+pub fn get_keccak_f_theta_step_layers(input_ids: Vec<u64>, degree: u64, w: u64) -> Vec<Layer<i64>> {
+    let lane = |x: u64, y: u64| get_lane_ids(input_ids.clone(), x, y, w);
+    let temp_ids =
+        |start: u64, i: u64| -> Vec<u64> { ((start + i * w)..(start + (i + 1) * w)).collect() };
+    let (p_start, t_start, d_start) = (1e9 as u64, 2 * 1e9 as u64, 1e9 as u64);
+
+    //p0 and p1, the parities of the lanes y = 0, 1 and y = 2, 3 of every column
+    let mut layer1: Layer<i64> = Layer::new();
+    layer1.set_degree(degree);
+    layer1.copy_gates_by_ids(input_ids.clone());
+    for x in 0..5 {
+        layer1.merge_layer(get_xor_bitstring_as_layer(
+            lane(x, 0),
+            lane(x, 1),
+            temp_ids(p_start, 2 * x),
+            degree,
+        ));
+        layer1.merge_layer(get_xor_bitstring_as_layer(
+            lane(x, 2),
+            lane(x, 3),
+            temp_ids(p_start, 2 * x + 1),
+            degree,
+        ));
     }
-    curr_degree += 4 * 2;
-    for i in 0..5 {
-        let out_ids_D: Vec<u64> =
-            (((2 * 1e9 as u64) + i * w)..((2 * 1e9 as u64) + (i + 1) * w)).collect();
-        let in_ids0: Vec<u64> =
-            (((1e9 as u64) + (i + 4) % 5 * w)..((1e9 as u64) + ((i + 4) % 5 + 1) * w)).collect();
-        let in_ids1: Vec<u64> = rot_ids(
-            (((1e9 as u64) + (i + 1) % 5 * w)..((1e9 as u64) + ((i + 1) % 5 + 1) * w)).collect(),
-            1,
-        );
-        let mut layers_xor = get_xor_bitstring_as_layers(in_ids0, in_ids1, out_ids_D, curr_degree);
 
-        let C_ids: Vec<u64> = ((1e9 as u64)..((1e9 as u64) + 5 * w)).collect();
-        for i in 0..2 {
-            layers_xor[i].copy_gates_by_ids(C_ids.clone());
-            layers[(curr_degree-degree) as usize + i].merge_layer(layers_xor[i].clone());
+    //t with C = t^2, the parity of p0, p1 and a[x][4]
+    let mut layer2: Layer<i64> = Layer::new();
+    layer2.set_degree(degree + 1);
+    layer2.copy_gates_by_ids(input_ids.clone());
+    for x in 0..5 {
+        let (p0, p1, a4) = (
+            temp_ids(p_start, 2 * x),
+            temp_ids(p_start, 2 * x + 1),
+            lane(x, 4),
+        );
+        for (z, t) in temp_ids(t_start, x).into_iter().enumerate() {
+            layer2.append_gate(get_R1CS_gate(
+                degree + 1,
+                t,
+                [vec![p0[z], p1[z], a4[z], 1], vec![a4[z], p0[z], p1[z]]],
+                [vec![1, 1, 1, -2], vec![1, -1, -1]],
+            ));
         }
     }
-    curr_degree += 2;
-
-    for i in 0..5 {
-        for j in 0..5 {
-            let in_ids0: Vec<u64> =
-                input_ids[(((i * 5 + j) * w) as usize)..(((i * 5 + j + 1) * w) as usize)].to_vec();
-            let in_ids1: Vec<u64> =
-                (((2 * 1e9 as u64) + j * w)..((2 * 1e9 as u64) + (j + 1) * w)).collect();
 
-            let out_ids = in_ids0.clone();
-            let layers_xor = get_xor_bitstring_as_layers(in_ids0, in_ids1, out_ids, curr_degree);
-            for i in 0..2 {
-                layers[(curr_degree-degree) as usize + i].merge_layer(layers_xor[i].clone());
-            }
+    //d with D = d^2 = C[x-1] xor rot(C[x+1], 1)
+    let mut layer3: Layer<i64> = Layer::new();
+    layer3.set_degree(degree + 2);
+    layer3.copy_gates_by_ids(input_ids.clone());
+    for x in 0..5 {
+        let t0 = temp_ids(t_start, (x + 4) % 5);
+        let t1 = rotate_left_ids(temp_ids(t_start, (x + 1) % 5), 1);
+        for (z, d) in temp_ids(d_start, x).into_iter().enumerate() {
+            layer3.append_gate(get_R1CS_gate(
+                degree + 2,
+                d,
+                [vec![t0[z], t1[z]], vec![t0[z], t1[z]]],
+                [vec![1, -1], vec![1, 1]],
+            ));
         }
     }
 
-    /*let mut gate_amount = 0;
-    for i in 0..layers.len() {
-        gate_amount += layers[i].gates_amount();
+    //v = a^2 - d^2 with v^2 = a xor D
+    let mut layer4: Layer<i64> = Layer::new();
+    layer4.set_degree(degree + 3);
+    for x in 0..5 {
+        let d = temp_ids(d_start, x);
+        for y in 0..5 {
+            for (z, a) in lane(x, y).into_iter().enumerate() {
+                layer4.append_gate(get_R1CS_gate(
+                    degree + 3,
+                    a,
+                    [vec![a, d[z]], vec![a, d[z]]],
+                    [vec![1, -1], vec![1, 1]],
+                ));
+            }
+        }
     }
-    println!("omega step ===> layers: {:?}, gates: {:?}", layers.len(), gate_amount);*/
 
-    layers
+    vec![layer1, layer2, layer3, layer4]
 }
 
-/// layers: 1       gates: ?
+/// Rho and pi steps, squares the v of theta while moving lane (x, y) rotated by ROT[x + 5y]
+/// to lane (y, 2x + 3y)
+/// layers: 1       gates: 1600 for w = 64
 pub fn get_keccak_f_pi_rho_steps_layer(input_ids: Vec<u64>, degree: u64, w: u64) -> Layer<i64> {
     let mut layer: Layer<i64> = Layer::new();
     layer.set_degree(degree);
 
-    for i in 0..5 {
-        for j in 0..5 {
-            let in_ids: Vec<u64> = input_ids
-                [((((j + 5 * i) * w) as usize)..(((j + 5 * i + 1) * w) as usize))]
-                .to_vec();
-            let out_ids: Vec<u64> = (1e9 as u64 + (i + 5 * ((2 * j + 2 * i) % 5)) * w
-                ..1e9 as u64 + (i + 5 * ((2 * j + 2 * i) % 5) + 1) * w)
-                .collect();
-
-            layer.copy_gates_by_ids_set_out(rot_ids(in_ids, ROT[(j + 5 * i) as usize]), out_ids);
+    for y in 0..5 {
+        for x in 0..5 {
+            let in_ids = rotate_left_ids(
+                get_lane_ids(input_ids.clone(), x, y, w),
+                ROT[(x + 5 * y) as usize],
+            );
+            let out_ids = get_lane_ids(input_ids.clone(), y, (2 * x + 3 * y) % 5, w);
+            for (v, out_id) in in_ids.into_iter().zip(out_ids) {
+                let mut gate: Gate<i64> = Gate::new_mult_gate();
+                gate.set_all(Some(degree), Some(out_id), Some([v, v]), None, None);
+                layer.append_gate(gate);
+            }
         }
     }
-    layer.copy_gates_by_ids(input_ids.clone());
-
-    //println!("pi and rho steps ===> layers: {:?}, gates: {:?}", 1, layer.gates_amount());
 
     layer
 }
 
-/// layers: 4       gates: 17602
-pub fn get_keccak_f_chi_step_layer(input_ids: Vec<u64>, degree: u64, w: u64) -> Vec<Layer<i64>> {
-    let mut layers: Vec<Layer<i64>> = vec![];
-
-    for i in 0..4 {
-        let mut layer: Layer<i64> = Layer::new();
-        layer.set_degree(degree + i);
-        if i != 3 {
-            layer.copy_gates_by_ids(input_ids.clone());
-        }
-        layers.push(layer);
-    }
-
-    for i in 0..5 {
-        for j in 0..5 {
-            let mut curr_degree = degree;
-            let in_ids_not: Vec<u64> = (1e9 as u64 + ((j + 1) % 5 + 5 * i) * w
-                ..1e9 as u64 + ((j + 1) % 5 + 5 * i + 1) * w)
-                .collect();
-            let out_ids_not: Vec<u64> = (2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i) * w
-                ..2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i + 1) * w)
-                .collect();
-            let mut not_layer =
-                get_not_bitstring_as_layer(in_ids_not.clone(), out_ids_not, curr_degree);
-            not_layer.copy_gates_by_ids(in_ids_not.clone());
-            layers[0].merge_layer(not_layer);
-            curr_degree += 1;
-
-            let in_ids0_and: Vec<u64> = (1e9 as u64 + ((j + 2) % 5 + 5 * i) * w
-                ..1e9 as u64 + ((j + 2) % 5 + 5 * i + 1) * w)
-                .collect();
-            let in_ids1_and: Vec<u64> = (2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i) * w
-                ..2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i + 1) * w)
-                .collect();
-            let out_ids_and: Vec<u64> = (2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i) * w
-                ..2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i + 1) * w)
-                .collect();
-            let mut and_layer =
-                get_and_bitstring_as_layers(in_ids0_and, in_ids1_and, out_ids_and, curr_degree);
-            and_layer.copy_gates_by_ids(in_ids_not.clone());
-            layers[1].merge_layer(and_layer);
-            curr_degree += 1;
-
-            let in_ids1_xor: Vec<u64> =
-                (1e9 as u64 + (j + 5 * i) * w..1e9 as u64 + (j + 5 * i + 1) * w).collect();
-            let in_ids2_xor: Vec<u64> = (2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i) * w
-                ..2 * 1e9 as u64 + ((j + 1) % 5 + 5 * i + 1) * w)
-                .collect();
-            let out_ids_xor: Vec<u64> = input_ids
-                [((((j + 5 * i) * w) as usize)..(((j + 5 * i + 1) * w) as usize))]
-                .to_vec();
-            let mut xor_layers =
-                get_xor_bitstring_as_layers(in_ids1_xor, in_ids2_xor, out_ids_xor, curr_degree);
-            xor_layers[0].copy_gates_by_ids(in_ids_not.clone());
-            xor_layers[1].copy_gates_by_ids(in_ids_not.clone());
-            layers[2].merge_layer(xor_layers[0].clone());
-            layers[3].merge_layer(xor_layers[1].clone());
-        }
-    }
-
-    /*let mut gate_amount = 0;
-    for i in 0..layers.len() {
-        gate_amount += layers[i].gates_amount();
-    }
-    println!("chi step ===> layers: {:?}, gates: {:?}", layers.len(), gate_amount);*/
-
-    layers
-}
-
-/// layers: 1       gates: 64
+/// Chi step a = b xor (not b[x+1] and b[x+2]) with the iota step folded into its last layer
+/// layers: 2       gates: 4800 for w = 64
 #[allow(non_snake_case)]
-pub fn get_keccak_f_iota_step_layer(
-    in_ids: Vec<u64>,
+pub fn get_keccak_f_chi_iota_steps_layers(
+    input_ids: Vec<u64>,
     degree: u64,
     w: u64,
-    mut RC_round: u64,
-) -> Layer<i64> {
-    let mut layer: Layer<i64> = Layer::new();
-    layer.set_degree(degree);
-    for i in 0..w {
-        let curr = RC_round % 2;
-        RC_round /= 2;
-        if curr == 1 {
-            layer.merge_layer(get_not_as_layer(
-                in_ids[(w - i - 1) as usize],
-                in_ids[(w - i - 1) as usize],
-                degree,
-            ))
-        } else {
-            layer.copy_gates_by_ids(vec![in_ids[(w - i - 1) as usize]]);
+    RC_round: u64,
+) -> Vec<Layer<i64>> {
+    let lane = |x: u64, y: u64| get_lane_ids(input_ids.clone(), x, y, w);
+    let u_start = 1e9 as u64;
+
+    let mut layer1: Layer<i64> = Layer::new();
+    layer1.set_degree(degree);
+    layer1.copy_gates_by_ids(input_ids.clone());
+    let mut layer2: Layer<i64> = Layer::new();
+    layer2.set_degree(degree + 1);
+
+    for y in 0..5 {
+        for x in 0..5 {
+            let (b0, b1, b2) = (lane(x, y), lane((x + 1) % 5, y), lane((x + 2) % 5, y));
+            let u_ids: Vec<u64> =
+                ((u_start + (x + 5 * y) * w)..(u_start + (x + 5 * y + 1) * w)).collect();
+            for z in 0..(w as usize) {
+                layer1.append_gate(get_R1CS_gate(
+                    degree,
+                    u_ids[z],
+                    [vec![1, b1[z]], vec![b2[z]]],
+                    [vec![1, -1], vec![1]],
+                ));
+                let in_ids = vec![b0[z], u_ids[z]];
+                if x == 0 && y == 0 && (RC_round >> z) & 1 == 1 {
+                    layer2.merge_layer(get_xnor_as_layer(in_ids, b0[z], degree + 1));
+                } else {
+                    layer2.merge_layer(get_xor_as_layer(in_ids, b0[z], degree + 1));
+                }
+            }
         }
     }
 
-    //println!("iota step ===> layers: {:?}, gates: {:?}", 1, layer.gates_amount());
-
-    layer
+    vec![layer1, layer2]
 }
 
-pub fn rot_ids(vec: Vec<u64>, n: u64) -> Vec<u64> {
-    let mut res: Vec<u64> = vec![];
-    for i in 0..vec.len() {
-        res.push(vec[((i + n as usize) % vec.len()) as usize])
-    }
-    res
+#[allow(non_snake_case)]
+fn get_R1CS_gate(
+    degree: u64,
+    id: u64,
+    input_id_R1CS: [Vec<u64>; 2],
+    R1CS_weights: [Vec<i64>; 2],
+) -> Gate<i64> {
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+    gate.set_all(
+        Some(degree),
+        Some(id),
+        None,
+        Some(input_id_R1CS),
+        Some(R1CS_weights),
+    );
+    gate
 }
@@ -1,2 +1,12 @@
+pub mod batch;
+pub mod header;
+pub mod keccak256;
 pub mod keccak_circuit;
-mod keccak_f_circuit;
+pub mod keccak_f_circuit;
+pub mod merkle;
+pub mod mpt;
+pub mod reference;
+pub mod rlp;
+pub mod sha3;
+pub mod sponge;
+pub mod variable_length;
@@ -0,0 +1,257 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use crate::keccak_f_circuit::*;
+use crate::reference::*;
+use lac::interface::*;
+use lac::mux::*;
+use lac::subcircuit::*;
+use lac::utils::*;
+
+//Merkle trees whose nodes are the Keccak-256 of their two children of 32 bytes, left first,
+//as the trees of Ethereum. A pair fits in one block, so hashing it is a single Keccak-f
+//instance whose input state is the pair, the padding 0x01 ... 0x80 read from the constants
+//0 and 1 and the capacity of 0s. The pairs of a level are hashed by instances side by side
+//in the same layers, a tree of N leaves uses log2(N) Keccak-f.
+
+/// Bits of a node, a Keccak-256 digest
+pub const NODE_BITS: u64 = 256;
+
+///Circuit of the root of leaves_amount leaves, a power of 2 from 2 on. The private input
+///"leaves" has the bits of the leaves, in order, and the output "root" the bits of the root.
+pub fn get_merkle_root_circuit(leaves_amount: usize) -> LAC<i64> {
+    assert!(
+        leaves_amount >= 2 && leaves_amount.is_power_of_two(),
+        "the tree needs a power of 2 of leaves from 2 on, got {}",
+        leaves_amount
+    );
+    let leaves_size = leaves_amount as u64 * NODE_BITS;
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; leaves_size as usize]));
+
+    let keccak_f = get_pair_keccak_f_subcircuit();
+    let leaf_ids: Vec<u64> = (2..(2 + leaves_size)).collect();
+    let mut nodes: Vec<Vec<u64>> = leaf_ids
+        .chunks(NODE_BITS as usize)
+        .map(|ids| ids.to_vec())
+        .collect();
+    while nodes.len() > 1 {
+        let pairs: Vec<[Vec<u64>; 2]> = nodes
+            .chunks(2)
+            .map(|pair| [pair[0].clone(), pair[1].clone()])
+            .collect();
+        nodes = append_pair_hashes(&mut lac, &keccak_f, &pairs, &[]);
+    }
+
+    lac.declare_input("leaves", Visibility::Private, leaf_ids)
+        .unwrap();
+    lac.declare_output("root", nodes.pop().unwrap()).unwrap();
+    lac
+}
+
+///Circuit of the root reached from the private input "leaf" by a path of depth levels. The
+///private input "siblings" has the bits of the sibling of every level from the leaf up, and
+///"directions" a bit per level, 1 iff the node of the path is the right child. A MUX orders
+///the children of every level, the output "root" has the bits of the root and the output
+///group ASSERTIONS checks that the directions are bits.
+pub fn get_merkle_path_circuit(depth: usize) -> LAC<i64> {
+    let (mut lac, root_ids) = get_merkle_path_lac(depth);
+    lac.declare_output("root", root_ids).unwrap();
+    declare_merkle_path_inputs(&mut lac, depth);
+    lac
+}
+
+///Merkle path circuit of get_merkle_path_circuit for the public input "expected_root". Its
+///output "root_check" is 0 iff the path reaches expected_root and 1 otherwise.
+pub fn get_merkle_path_check_circuit(depth: usize) -> LAC<i64> {
+    let (mut lac, root_ids) = get_merkle_path_lac(depth);
+
+    let one_id = lac.get_max_id() + 1;
+    let expected_ids: Vec<u64> = ((one_id + 1)..(one_id + 1 + NODE_BITS)).collect();
+    let check_id = one_id + 1 + NODE_BITS;
+    let inputs_size = (NODE_BITS as usize + 1) * (depth + 1) - 1;
+    let mut basic_layer = get_keccak_basic_layer(vec![0; inputs_size]);
+    for id in expected_ids.iter() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(*id, 0);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+
+    let degree = lac.get_layers_amount() as u64;
+    lac.add_layers(get_carried_layers(expected_ids.clone(), one_id, degree))
+        .unwrap();
+    lac.append_layers(get_digest_check_layers(
+        root_ids,
+        expected_ids.clone(),
+        one_id,
+        check_id,
+        degree + 1,
+    ));
+
+    lac.declare_input("expected_root", Visibility::Public, expected_ids)
+        .unwrap();
+    lac.declare_output("root_check", vec![check_id]).unwrap();
+    declare_merkle_path_inputs(&mut lac, depth);
+    lac
+}
+
+///Assignment of the inputs of get_merkle_path_circuit, directions are 1 for a right child
+pub fn get_merkle_path_assignment(
+    leaf: &[u8; 32],
+    siblings: &[[u8; 32]],
+    directions: &[i64],
+) -> Assignment<i64> {
+    assert_eq!(
+        siblings.len(),
+        directions.len(),
+        "the path needs a direction per sibling"
+    );
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("leaf", bytes_to_bits(leaf));
+    assignment.set("siblings", bytes_to_bits(&siblings.concat()));
+    assignment.set("directions", directions.to_vec());
+    assignment
+}
+
+///Root of the tree of leaves, a power of 2 of them, computed with keccak256_reference
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    assert!(
+        !leaves.is_empty() && leaves.len().is_power_of_two(),
+        "the tree needs a power of 2 of leaves, got {}",
+        leaves.len()
+    );
+    let mut nodes = leaves.to_vec();
+    while nodes.len() > 1 {
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| keccak256_reference(&pair.concat()))
+            .collect();
+    }
+    nodes[0]
+}
+
+///Siblings and directions of the path from the leaf index to the root, from the leaf up
+pub fn get_merkle_path(leaves: &[[u8; 32]], index: usize) -> (Vec<[u8; 32]>, Vec<i64>) {
+    let (mut siblings, mut directions) = (Vec::new(), Vec::new());
+    let mut nodes = leaves.to_vec();
+    let mut index = index;
+    while nodes.len() > 1 {
+        siblings.push(nodes[index ^ 1]);
+        directions.push((index & 1) as i64);
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| keccak256_reference(&pair.concat()))
+            .collect();
+        index /= 2;
+    }
+    (siblings, directions)
+}
+
+/// Ids of the leaf, the siblings and the directions in the basic layer of a path of depth
+/// levels, in this order after the constants
+fn get_merkle_path_input_ids(depth: usize) -> (Vec<u64>, Vec<u64>, Vec<u64>) {
+    let siblings_start = 2 + NODE_BITS;
+    let directions_start = siblings_start + depth as u64 * NODE_BITS;
+    (
+        (2..siblings_start).collect(),
+        (siblings_start..directions_start).collect(),
+        (directions_start..(directions_start + depth as u64)).collect(),
+    )
+}
+
+/// Declares the private inputs of a path of depth levels and asserts that the directions
+/// are bits, once the circuit is built
+fn declare_merkle_path_inputs(lac: &mut LAC<i64>, depth: usize) {
+    let (leaf_ids, sibling_ids, direction_ids) = get_merkle_path_input_ids(depth);
+    lac.declare_input("leaf", Visibility::Private, leaf_ids)
+        .unwrap();
+    lac.declare_input("siblings", Visibility::Private, sibling_ids)
+        .unwrap();
+    lac.declare_input("directions", Visibility::Private, direction_ids.clone())
+        .unwrap();
+    lac.assert_boolean(direction_ids).unwrap();
+}
+
+/// Layers of the path circuit without the interface and the ids of the root in the last layer
+fn get_merkle_path_lac(depth: usize) -> (LAC<i64>, Vec<u64>) {
+    assert!(depth > 0, "the path needs at least 1 level");
+    let (leaf_ids, sibling_ids, direction_ids) = get_merkle_path_input_ids(depth);
+    let inputs_size = leaf_ids.len() + sibling_ids.len() + direction_ids.len();
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; inputs_size]));
+
+    let keccak_f = get_pair_keccak_f_subcircuit();
+    let mut node = leaf_ids;
+    for (level, sibling) in sibling_ids.chunks(NODE_BITS as usize).enumerate() {
+        //siblings and directions of the levels above
+        let remaining: Vec<u64> = sibling_ids[((level + 1) * NODE_BITS as usize)..]
+            .iter()
+            .chain(direction_ids[(level + 1)..].iter())
+            .copied()
+            .collect();
+        let degree = lac.get_layers_amount() as u64 + 1;
+        let first_id = lac.get_max_id() + 1;
+        let left: Vec<u64> = (first_id..(first_id + NODE_BITS)).collect();
+        let right: Vec<u64> = ((first_id + NODE_BITS)..(first_id + 2 * NODE_BITS)).collect();
+        let direction = direction_ids[level];
+
+        //left = MUX(d, node, sibling) and right = MUX(d, sibling, node)
+        let mut layers = get_mux_bitstring_as_layers(
+            direction,
+            node.clone(),
+            sibling.to_vec(),
+            left.clone(),
+            degree,
+        );
+        let right_layers =
+            get_mux_bitstring_as_layers(direction, sibling.to_vec(), node, right.clone(), degree);
+        for (i, (layer, right_layer)) in layers.iter_mut().zip(right_layers).enumerate() {
+            layer.merge_layer(right_layer);
+            layer.add_gate_0_and_1(degree + i as u64);
+            layer.copy_gates_by_ids(remaining.clone());
+        }
+        lac.append_layers(layers);
+
+        node = append_pair_hashes(&mut lac, &keccak_f, &[[left, right]], &remaining).remove(0);
+    }
+
+    (lac, node)
+}
+
+/// Keccak-f[1600] template whose input and output ports are the state
+fn get_pair_keccak_f_subcircuit() -> SubCircuit<i64> {
+    get_keccak_f_subcircuit((RESERVED_IDS..(RESERVED_IDS + 1600)).collect(), 64)
+}
+
+/// Appends the Keccak-256 of every pair of nodes of the last layer, each with a Keccak-f
+/// instance with fresh ids in the same layers, relaying pass_through. Returns the ids of the
+/// digests.
+fn append_pair_hashes(
+    lac: &mut LAC<i64>,
+    keccak_f: &SubCircuit<i64>,
+    pairs: &[[Vec<u64>; 2]],
+    pass_through: &[u64],
+) -> Vec<Vec<u64>> {
+    let degree = lac.get_layers_amount() as u64 + 1;
+    let mut digests = Vec::new();
+    for [left, right] in pairs {
+        //the padding of a message of 64 bytes sets the bits 512 and r - 1
+        let mut state: Vec<u64> = [left.clone(), right.clone()].concat();
+        state.resize(1600, 0);
+        state[2 * NODE_BITS as usize] = 1;
+        state[KECCAK256_RATE as usize - 1] = 1;
+
+        let remap = IdRemap::new(lac.get_max_id().max(RESERVED_IDS - 1) + 1 - RESERVED_IDS);
+        let layers = keccak_f.instantiate(degree, &state, &remap, pass_through);
+        //the first instance appends its layers, add_layers would pad with relays of ids the
+        //instance uses internally
+        if lac.get_layers_amount() < degree as usize {
+            lac.append_layers(layers);
+        } else {
+            lac.add_layers(layers).unwrap();
+        }
+        let output_ids = keccak_f.get_instance_output_ids(&remap);
+        digests.push(output_ids[..(NODE_BITS as usize)].to_vec());
+    }
+    digests
+}
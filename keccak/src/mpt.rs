@@ -0,0 +1,923 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use crate::keccak_f_circuit::*;
+use crate::reference::*;
+use crate::rlp::*;
+use crate::variable_length::*;
+use lac::assertion::ASSERTIONS;
+use lac::builder::*;
+use lac::interface::*;
+use lac::utils::*;
+use std::collections::HashMap;
+use std::fmt;
+
+//Proofs of the Merkle-Patricia tries of Ethereum, as returned by eth_getProof for an account
+//or a storage slot. The path of a key is the 64 nibbles of keccak256(key), high nibble first,
+//and a proof is the list of nodes from the root to the leaf of the key. A node is the RLP list
+//  branch      [child_0, ..., child_15, value]    a child is empty or the 32 bytes of a hash
+//  extension   [path, child]                      path is the hex-prefix of shared nibbles
+//  leaf        [path, value]                      path is the hex-prefix of the last nibbles
+//where the hex-prefix is a flag nibble, 0 or 1 for extensions and 2 or 3 for leaves, odd iff
+//the amount of nibbles is odd, followed by a 0 nibble when it is even and by the nibbles.
+//
+//The circuit has a slot of max_blocks Keccak blocks per node, the bytes of a node are private
+//with its length and the length mask of get_keccak_variable_length_circuit. The prover gives
+//hints on the layout of every node: the position in the path, the item offsets of a branch
+//as one-hot selectors, the child flags, the header sizes of the other nodes. The circuit
+//checks that the hints decode the bytes, that the items of a branch tile it exactly and that
+//the nibbles of the path are those of the key, then selects the 32 bytes of the reference to
+//the next node and the value of the leaf. The Keccak-256 of every node is computed side by
+//side and compared with the root and the references. Every check is folded into the output
+//group ASSERTIONS.
+//
+//Only inclusion proofs whose references are hashes are supported, as in the state trie. A
+//node under 32 bytes is inlined in its parent instead of hashed, which only happens in
+//storage tries with keys sharing 62 nibbles or more, such proofs fail with InlineNode.
+
+/// Nibbles of the path of a key
+pub const MPT_KEY_NIBBLES: usize = 64;
+
+/// Items of a branch node, 16 children and the value
+const BRANCH_ITEMS: usize = 17;
+
+/// Bytes of a reference to a node, its Keccak-256
+const REFERENCE_SIZE: usize = 32;
+
+/// Proofs the native verifier or the circuit assignment reject, index is the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MptError {
+    EmptyProof,
+    InvalidNode {
+        index: usize,
+        error: RlpError,
+    },
+    UnexpectedNode {
+        index: usize,
+    },
+    HashMismatch {
+        index: usize,
+    },
+    KeyMismatch {
+        index: usize,
+    },
+    InlineNode {
+        index: usize,
+    },
+    NodeTooLong {
+        index: usize,
+        size: usize,
+        max_size: usize,
+    },
+    ValueTooLong {
+        size: usize,
+        max_size: usize,
+    },
+}
+
+impl fmt::Display for MptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MptError::EmptyProof => write!(f, "the proof has no nodes"),
+            MptError::InvalidNode { index, error } => {
+                write!(f, "node {} is not an RLP list: {}", index, error)
+            }
+            MptError::UnexpectedNode { index } => {
+                write!(f, "node {} is not a node the proof can have there", index)
+            }
+            MptError::HashMismatch { index } => {
+                write!(f, "node {} is not the node its parent references", index)
+            }
+            MptError::KeyMismatch { index } => {
+                write!(f, "node {} does not follow the path of the key", index)
+            }
+            MptError::InlineNode { index } => write!(
+                f,
+                "node {} inlines a child node of less than 32 bytes instead of its hash",
+                index
+            ),
+            MptError::NodeTooLong {
+                index,
+                size,
+                max_size,
+            } => write!(
+                f,
+                "node {} has {} bytes, the circuit holds at most {}",
+                index, size, max_size
+            ),
+            MptError::ValueTooLong { size, max_size } => write!(
+                f,
+                "the value has {} bytes, the circuit holds at most {}",
+                size, max_size
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MptError {}
+
+///Largest node the circuit of max_blocks Keccak blocks per node holds, in bytes
+pub fn get_mpt_max_node_size(max_blocks: u64) -> usize {
+    (get_variable_length_max_size(max_blocks, 0, KECCAK256_RATE).unwrap() / 8) as usize
+}
+
+///Checks the proof natively and returns the value of the key, the payload of the leaf value
+pub fn verify_mpt_proof(
+    root: &[u8; 32],
+    key: &[u8],
+    nodes: &[Vec<u8>],
+) -> Result<Vec<u8>, MptError> {
+    let steps = get_mpt_proof_steps(root, key, nodes)?;
+    let value = steps.last().unwrap().get_value_item();
+    Ok(value.get_payload(nodes.last().unwrap()).to_vec())
+}
+
+///Circuit verifying a proof of nodes_amount nodes of at most get_mpt_max_node_size(max_blocks)
+///bytes for a key of key_size bytes, 20 for an account and 32 for a storage slot. The public
+///inputs "root" and "key" have the bits of the root and of the key, the private inputs are
+///"key_hash", the bits of keccak256(key), "nodes", "node_lengths", "node_masks" and "hints",
+///see get_mpt_proof_assignment. The output "value" has the max_value_size bytes of the value
+///of the key, 0 after its size, "value_size" its size and the output group ASSERTIONS is 0 iff
+///the proof is valid. Panics if the key needs more than max_blocks blocks or if the value
+///does not fit a header of 2 bytes.
+pub fn get_mpt_proof_circuit(
+    nodes_amount: usize,
+    max_blocks: u64,
+    key_size: usize,
+    max_value_size: usize,
+) -> LAC<i64> {
+    assert!(nodes_amount > 0, "the proof needs at least 1 node");
+    assert!(
+        get_blocks_amount(8 * key_size as u64, KECCAK256_RATE) <= max_blocks,
+        "a key of {} bytes needs more than {} blocks",
+        key_size,
+        max_blocks
+    );
+    assert!(
+        max_value_size > 0 && max_value_size < 256,
+        "the value size must be between 1 and 255, got {}",
+        max_value_size
+    );
+    let layout = MptLayout::new(nodes_amount, max_blocks, key_size, max_value_size);
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; layout.size as usize - 2]));
+
+    //the checks of the layout of the nodes, from the basic values
+    let mut builder = CircuitBuilder::new(1, layout.size);
+    let key_nibbles: Vec<Combination> = (0..MPT_KEY_NIBBLES)
+        .map(|q| get_nibble(&layout.key_hash_ids[(8 * (q / 2))..], q % 2 == 0))
+        .collect();
+    let mut position: Combination = Vec::new();
+    let mut references: Vec<Vec<u64>> = Vec::new();
+    let mut value = NodeWires::default();
+    for i in 0..nodes_amount {
+        let wires = append_node_checks(&mut builder, &layout, i, &position, &key_nibbles);
+        position = wires.next_position.clone();
+        if i + 1 < nodes_amount {
+            references.push(wires.reference);
+        } else {
+            value = wires;
+        }
+    }
+    builder.assert_bits(&layout.key_ids);
+    for bits in layout.node_ids.iter() {
+        builder.assert_bits(bits);
+    }
+    let layout_check = builder.fold_checks();
+
+    let pass_through: Vec<u64> = [
+        vec![layout_check, value.value_size],
+        references.concat(),
+        value.value.clone(),
+        layout.root_ids.clone(),
+        layout.key_hash_ids.clone(),
+    ]
+    .concat();
+    let hash_inputs: Vec<u64> = [
+        pass_through.clone(),
+        layout.key_ids.clone(),
+        layout.node_ids.concat(),
+        layout.mask_ids.concat(),
+    ]
+    .concat();
+    lac.append_layers(builder.finish(&hash_inputs));
+
+    //the Keccak-256 of the nodes side by side, and of the key ending with them
+    let degree = lac.get_layers_amount() as u64 + 1;
+    let node_keccak = get_keccak256_variable_length_subcircuit(max_blocks);
+    let node_pass_through = [pass_through.clone(), layout.key_ids.clone()].concat();
+    let mut digests: Vec<Vec<u64>> = Vec::new();
+    for i in 0..nodes_amount {
+        let inputs = get_keccak256_variable_length_inputs(
+            max_blocks,
+            &layout.node_ids[i],
+            &layout.mask_ids[i],
+        );
+        let remap = IdRemap::new(lac.get_max_id() + 1 - RESERVED_IDS);
+        let layers = node_keccak.instantiate(degree, &inputs, &remap, &node_pass_through);
+        //the first instance appends its layers, add_layers would pad with relays of ids the
+        //instance uses internally
+        if i == 0 {
+            lac.append_layers(layers);
+        } else {
+            lac.add_layers(layers).unwrap();
+        }
+        digests.push(node_keccak.get_instance_output_ids(&remap));
+    }
+    let key_size_bits = 8 * key_size as u64;
+    let key_keccak = get_keccak_hash_subcircuit(
+        get_keccak_hash_layers(
+            key_size_bits,
+            Vec::new(),
+            KECCAK256_RATE,
+            KECCAK256_CAPACITY,
+            KECCAK256_OUTPUT_BITS,
+            get_keccak_f_rounds_amount(64),
+        ),
+        (2..(2 + key_size_bits)).collect(),
+        KECCAK256_OUTPUT_BITS,
+    );
+    let key_degree = degree + node_keccak.get_depth() - key_keccak.get_depth();
+    let remap = IdRemap::new(lac.get_max_id() + 1 - RESERVED_IDS);
+    let layers = key_keccak.instantiate(key_degree, &layout.key_ids, &remap, &pass_through);
+    lac.add_layers(layers).unwrap();
+    let key_digest = key_keccak.get_instance_output_ids(&remap);
+
+    //the root and the references are the digests of the nodes, the hint the digest of the key
+    let degree = lac.get_layers_amount() as u64 + 1;
+    let mut builder = CircuitBuilder::new(degree, lac.get_max_id() + 1);
+    for (digest_bit, root_bit) in digests[0].iter().zip(layout.root_ids.iter()) {
+        builder.assert_zero(&[(*digest_bit, 1), (*root_bit, -1)]);
+    }
+    for (reference, digest) in references.iter().zip(digests[1..].iter()) {
+        for (k, byte) in reference.iter().enumerate() {
+            let mut check = get_byte(&digest[(8 * k)..]);
+            check.push((*byte, -1));
+            builder.assert_zero(&check);
+        }
+    }
+    for (digest_bit, hint_bit) in key_digest.iter().zip(layout.key_hash_ids.iter()) {
+        builder.assert_zero(&[(*digest_bit, 1), (*hint_bit, -1)]);
+    }
+    let hash_check = builder.fold_checks();
+    let outputs = [
+        vec![layout_check, hash_check, value.value_size],
+        value.value.clone(),
+    ]
+    .concat();
+    lac.append_layers(builder.finish(&outputs));
+
+    lac.declare_input("root", Visibility::Public, layout.root_ids.clone())
+        .unwrap();
+    lac.declare_input("key", Visibility::Public, layout.key_ids.clone())
+        .unwrap();
+    lac.declare_input("key_hash", Visibility::Private, layout.key_hash_ids.clone())
+        .unwrap();
+    lac.declare_input("nodes", Visibility::Private, layout.node_ids.concat())
+        .unwrap();
+    lac.declare_input(
+        "node_lengths",
+        Visibility::Private,
+        layout.length_ids.clone(),
+    )
+    .unwrap();
+    lac.declare_input("node_masks", Visibility::Private, layout.mask_ids.concat())
+        .unwrap();
+    lac.declare_input("hints", Visibility::Private, layout.get_hint_ids())
+        .unwrap();
+    lac.declare_output("value", value.value).unwrap();
+    lac.declare_output("value_size", vec![value.value_size])
+        .unwrap();
+    lac.declare_output(ASSERTIONS, vec![layout_check, hash_check])
+        .unwrap();
+    lac
+}
+
+///Inputs of get_mpt_proof_circuit for the proof nodes of key under root, the circuit must have
+///one slot per node. Fails if the proof is not valid or does not fit the circuit.
+pub fn get_mpt_proof_assignment(
+    root: &[u8; 32],
+    key: &[u8],
+    nodes: &[Vec<u8>],
+    max_blocks: u64,
+    max_value_size: usize,
+) -> Result<Assignment<i64>, MptError> {
+    let steps = get_mpt_proof_steps(root, key, nodes)?;
+    let node_size = get_mpt_max_node_size(max_blocks);
+    for (index, node) in nodes.iter().enumerate() {
+        if node.len() > node_size {
+            return Err(MptError::NodeTooLong {
+                index,
+                size: node.len(),
+                max_size: node_size,
+            });
+        }
+    }
+    let value = steps.last().unwrap().get_value_item();
+    if value.payload_size > max_value_size {
+        return Err(MptError::ValueTooLong {
+            size: value.payload_size,
+            max_size: max_value_size,
+        });
+    }
+
+    let key_hash = keccak256_reference(key);
+    let key_nibbles = get_key_nibbles(&key_hash);
+    let (mut node_bits, mut masks, mut hints) = (Vec::new(), Vec::new(), Vec::new());
+    for (i, (node, step)) in nodes.iter().zip(steps.iter()).enumerate() {
+        let last = i + 1 == nodes.len();
+        let mut bits = bytes_to_bits(node);
+        bits.resize(8 * node_size, 0);
+        node_bits.extend(bits);
+        masks.extend((0..(8 * node_size)).map(|j| (j < 8 * node.len()) as i64));
+
+        let mut values = get_step_hints(step, node_size, max_value_size, &key_nibbles);
+        for (name, size) in get_node_hint_fields(last, node_size, max_value_size) {
+            let field = values.remove(name).unwrap();
+            assert_eq!(field.len(), size, "hint {} of node {}", name, i);
+            hints.extend(field);
+        }
+    }
+
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("root", bytes_to_bits(root));
+    assignment.set("key", bytes_to_bits(key));
+    assignment.set("key_hash", bytes_to_bits(&key_hash));
+    assignment.set("nodes", node_bits);
+    assignment.set(
+        "node_lengths",
+        nodes.iter().map(|node| node.len() as i64).collect(),
+    );
+    assignment.set("node_masks", masks);
+    assignment.set("hints", hints);
+    Ok(assignment)
+}
+
+/// A node of a proof decoded natively, at the position of the path where it starts
+struct ProofStep {
+    position: usize,
+    items: Vec<RlpItem>,
+    list_header_size: usize,
+    //nibbles of the path of an extension or a leaf
+    path_nibbles: usize,
+    //offset of the reference to the next node
+    reference: Option<usize>,
+}
+
+impl ProofStep {
+    fn is_branch(&self) -> bool {
+        self.items.len() == BRANCH_ITEMS
+    }
+
+    fn get_value_item(&self) -> RlpItem {
+        self.items[1]
+    }
+}
+
+/// Nibbles of the bytes, high nibble first
+fn get_key_nibbles(bytes: &[u8]) -> Vec<usize> {
+    bytes
+        .iter()
+        .flat_map(|byte| [(byte >> 4) as usize, (byte & 15) as usize])
+        .collect()
+}
+
+/// Decodes the nodes of the proof of key along its path, checking every hash
+fn get_mpt_proof_steps(
+    root: &[u8; 32],
+    key: &[u8],
+    nodes: &[Vec<u8>],
+) -> Result<Vec<ProofStep>, MptError> {
+    if nodes.is_empty() {
+        return Err(MptError::EmptyProof);
+    }
+    let key_nibbles = get_key_nibbles(&keccak256_reference(key));
+    let mut expected = root.to_vec();
+    let mut position = 0;
+    let mut steps = Vec::new();
+    for (index, node) in nodes.iter().enumerate() {
+        let unexpected = MptError::UnexpectedNode { index };
+        let last = index + 1 == nodes.len();
+        if keccak256_reference(node).to_vec() != expected {
+            return Err(MptError::HashMismatch { index });
+        }
+        let items =
+            decode_rlp_list(node).map_err(|error| MptError::InvalidNode { index, error })?;
+        let list_header_size = decode_rlp_item(node, 0).unwrap().header_size;
+        let is_reference = |item: &RlpItem| !item.is_list && item.payload_size == REFERENCE_SIZE;
+        let mut step = ProofStep {
+            position,
+            items: items.clone(),
+            list_header_size,
+            path_nibbles: 0,
+            reference: None,
+        };
+
+        if items.len() == BRANCH_ITEMS {
+            if items[..16].iter().any(|item| item.is_list) {
+                return Err(MptError::InlineNode { index });
+            }
+            let children_valid = items[..16]
+                .iter()
+                .all(|item| is_reference(item) || (!item.is_list && item.payload_size == 0));
+            let value = items[16];
+            if last
+                || !children_valid
+                || value.is_list
+                || value.payload_size != 0
+                || !(2..=3).contains(&list_header_size)
+            {
+                return Err(unexpected);
+            }
+            let child = items[key_nibbles[position]];
+            if child.payload_size == 0 {
+                return Err(MptError::KeyMismatch { index });
+            }
+            step.reference = Some(child.offset + 1);
+            position += 1;
+        } else if items.len() == 2 {
+            let (path, second) = (items[0], items[1]);
+            if path.is_list
+                || path.payload_size == 0
+                || path.header_size > 1
+                || list_header_size > 2
+            {
+                return Err(unexpected);
+            }
+            let path_bytes = path.get_payload(node);
+            let (flag, first_nibble) = ((path_bytes[0] >> 4) as usize, path_bytes[0] & 15);
+            let (odd, leaf) = (flag & 1 == 1, flag & 2 == 2);
+            if flag > 3 || (!odd && first_nibble != 0) || leaf != last {
+                return Err(unexpected);
+            }
+            let mut nibbles: Vec<usize> = get_key_nibbles(&path_bytes[1..]);
+            if odd {
+                nibbles.insert(0, first_nibble as usize);
+            }
+            let end = position + nibbles.len();
+            if end > MPT_KEY_NIBBLES || key_nibbles[position..end] != nibbles[..] {
+                return Err(MptError::KeyMismatch { index });
+            }
+            if leaf {
+                if end != MPT_KEY_NIBBLES {
+                    return Err(MptError::KeyMismatch { index });
+                }
+                if second.is_list || second.header_size > 2 {
+                    return Err(unexpected);
+                }
+            } else {
+                if second.is_list {
+                    return Err(MptError::InlineNode { index });
+                }
+                if !is_reference(&second) || second.header_size != 1 {
+                    return Err(unexpected);
+                }
+                step.reference = Some(second.offset + 1);
+            }
+            step.path_nibbles = nibbles.len();
+            position = end;
+        } else {
+            return Err(unexpected);
+        }
+
+        if let Some(reference) = step.reference {
+            expected = node[reference..(reference + REFERENCE_SIZE)].to_vec();
+        }
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+/// Hints of a node by name, see get_node_hint_fields. The hints of the kind of node it is not
+/// describe an empty layout that passes the checks, as offsets 2 + j for the branch items.
+fn get_step_hints(
+    step: &ProofStep,
+    node_size: usize,
+    value_size: usize,
+    key_nibbles: &[usize],
+) -> HashMap<&'static str, Vec<i64>> {
+    let mut hints: HashMap<&'static str, Vec<i64>> = HashMap::new();
+    let nibble = key_nibbles.get(step.position).copied().unwrap_or(0);
+    hints.insert("branch", vec![step.is_branch() as i64]);
+    hints.insert("long_branch", vec![0]);
+    hints.insert("children", vec![0; 16]);
+    hints.insert(
+        "items",
+        (0..BRANCH_ITEMS)
+            .flat_map(|j| get_one_hot(2 + j, node_size))
+            .collect(),
+    );
+    hints.insert("nibble", get_one_hot(nibble, 16));
+    hints.insert("position", get_one_hot(step.position, MPT_KEY_NIBBLES + 1));
+    hints.insert("long_list", vec![0]);
+    hints.insert("prefixed_path", vec![0]);
+    hints.insert("path_size", vec![1]);
+    hints.insert("path_start", get_one_hot(0, 3));
+    hints.insert("path_mask", vec![0; MPT_KEY_NIBBLES]);
+    hints.insert("second_item", get_one_hot(2, node_size));
+    if let Some(reference) = step.reference {
+        hints.insert("reference", get_one_hot(reference, node_size));
+    }
+
+    if step.is_branch() {
+        hints.insert("long_branch", vec![step.list_header_size as i64 - 2]);
+        hints.insert(
+            "children",
+            step.items[..16]
+                .iter()
+                .map(|item| (item.payload_size > 0) as i64)
+                .collect(),
+        );
+        hints.insert(
+            "items",
+            step.items
+                .iter()
+                .flat_map(|item| get_one_hot(item.offset, node_size))
+                .collect(),
+        );
+    } else {
+        let (path, second) = (step.items[0], step.items[1]);
+        hints.insert("long_list", vec![step.list_header_size as i64 - 1]);
+        hints.insert("prefixed_path", vec![path.header_size as i64]);
+        hints.insert("path_size", vec![path.payload_size as i64]);
+        hints.insert(
+            "path_start",
+            get_one_hot(step.list_header_size - 1 + path.header_size, 3),
+        );
+        hints.insert(
+            "path_mask",
+            (0..MPT_KEY_NIBBLES)
+                .map(|t| (t < step.path_nibbles) as i64)
+                .collect(),
+        );
+        hints.insert("second_item", get_one_hot(second.offset, node_size));
+        hints.insert("value_kind", get_one_hot(second.header_size, 3));
+        hints.insert("value_size", vec![second.payload_size as i64]);
+        hints.insert(
+            "value_mask",
+            (0..value_size)
+                .map(|k| (k < second.payload_size) as i64)
+                .collect(),
+        );
+    }
+    hints
+}
+
+/// Hints of a node in the order of the group "hints", name and size:
+///   branch          1 iff the node is a branch, not for the last node that is a leaf
+///   long_branch     1 iff the list header of the branch has 3 bytes instead of 2
+///   children        1 iff the child j is not empty
+///   items           one-hot selectors of the offsets of the 17 items of the branch
+///   nibble          one-hot selector of the nibble of the key at the position
+///   reference       one-hot selector of the offset of the reference to the next node
+///   position        one-hot selector of the nibbles of the path before the node
+///   long_list       1 iff the list header of an extension or a leaf has 2 bytes
+///   prefixed_path   1 iff the path has a header byte, 0 when it is a single byte
+///   path_size       bytes of the path
+///   path_start      one-hot selector of the offset of the path, 1, 2 or 3
+///   path_mask       1 for the nibbles of the path
+///   second_item     one-hot selector of the offset of the child or the value
+///   value_kind      one-hot selector of the header size of the value, 0, 1 or 2
+///   value_size      bytes of the value
+///   value_mask      1 for the bytes of the value
+fn get_node_hint_fields(
+    last: bool,
+    node_size: usize,
+    value_size: usize,
+) -> Vec<(&'static str, usize)> {
+    let mut fields = Vec::new();
+    if !last {
+        fields.extend([
+            ("branch", 1),
+            ("long_branch", 1),
+            ("children", 16),
+            ("items", BRANCH_ITEMS * node_size),
+            ("nibble", 16),
+            ("reference", node_size),
+        ]);
+    }
+    fields.extend([
+        ("position", MPT_KEY_NIBBLES + 1),
+        ("long_list", 1),
+        ("prefixed_path", 1),
+        ("path_size", 1),
+        ("path_start", 3),
+        ("path_mask", MPT_KEY_NIBBLES),
+        ("second_item", node_size),
+    ]);
+    if last {
+        fields.extend([
+            ("value_kind", 3),
+            ("value_size", 1),
+            ("value_mask", value_size),
+        ]);
+    }
+    fields
+}
+
+/// Ids of the basic layer of get_mpt_proof_circuit, in this order after the constants
+struct MptLayout {
+    node_size: usize,
+    value_size: usize,
+    root_ids: Vec<u64>,
+    key_ids: Vec<u64>,
+    key_hash_ids: Vec<u64>,
+    node_ids: Vec<Vec<u64>>,
+    length_ids: Vec<u64>,
+    mask_ids: Vec<Vec<u64>>,
+    hints: Vec<HashMap<&'static str, Vec<u64>>>,
+    //first id after the basic layer
+    size: u64,
+}
+
+impl MptLayout {
+    fn new(nodes_amount: usize, max_blocks: u64, key_size: usize, value_size: usize) -> Self {
+        let node_size = get_mpt_max_node_size(max_blocks);
+        let mut next_id = 2;
+        let mut take = |size: usize| -> Vec<u64> {
+            let ids = (next_id..(next_id + size as u64)).collect();
+            next_id += size as u64;
+            ids
+        };
+        let root_ids = take(KECCAK256_OUTPUT_BITS as usize);
+        let key_ids = take(8 * key_size);
+        let key_hash_ids = take(KECCAK256_OUTPUT_BITS as usize);
+        let node_ids = (0..nodes_amount).map(|_| take(8 * node_size)).collect();
+        let length_ids = take(nodes_amount);
+        let mask_ids = (0..nodes_amount).map(|_| take(8 * node_size)).collect();
+        let hints = (0..nodes_amount)
+            .map(|i| {
+                get_node_hint_fields(i + 1 == nodes_amount, node_size, value_size)
+                    .into_iter()
+                    .map(|(name, size)| (name, take(size)))
+                    .collect()
+            })
+            .collect();
+        MptLayout {
+            node_size,
+            value_size,
+            root_ids,
+            key_ids,
+            key_hash_ids,
+            node_ids,
+            length_ids,
+            mask_ids,
+            hints,
+            size: next_id,
+        }
+    }
+
+    fn get_hint_ids(&self) -> Vec<u64> {
+        let first = self.mask_ids.last().unwrap().last().unwrap() + 1;
+        (first..self.size).collect()
+    }
+}
+
+/// Wires of the checks of a node
+#[derive(Default)]
+struct NodeWires {
+    next_position: Combination,
+    //bytes of the reference to the next node
+    reference: Vec<u64>,
+    //bytes of the value and its size, for the leaf
+    value: Vec<u64>,
+    value_size: u64,
+}
+
+/// Byte of the 8 bits, least significant bit first
+fn get_byte(bits: &[u64]) -> Combination {
+    (0..8).map(|b| (bits[b], 1 << b)).collect()
+}
+
+/// High or low nibble of the byte of the 8 bits
+fn get_nibble(bits: &[u64], high: bool) -> Combination {
+    let start = if high { 4 } else { 0 };
+    (0..4).map(|j| (bits[start + j], 1 << j)).collect()
+}
+
+/// Checks the layout of the node i from the position of the path before it, returns the
+/// position after it and the reference to the next node or the value of the leaf
+fn append_node_checks(
+    builder: &mut CircuitBuilder,
+    layout: &MptLayout,
+    i: usize,
+    position: &Combination,
+    key_nibbles: &[Combination],
+) -> NodeWires {
+    let m = layout.node_size;
+    let last = i + 1 == layout.node_ids.len();
+    let hints = &layout.hints[i];
+    let hint = |name: &str| hints[name][0];
+    let bits = &layout.node_ids[i];
+    let length = layout.length_ids[i];
+    let bytes: Vec<Combination> = (0..m).map(|p| get_byte(&bits[(8 * p)..])).collect();
+    let bit = |p: usize, b: usize| -> Combination {
+        if p < m {
+            vec![(bits[8 * p + b], 1)]
+        } else {
+            Vec::new()
+        }
+    };
+    let mut wires = NodeWires::default();
+
+    builder.assert_prefix_mask(&layout.mask_ids[i], &[(length, 8)]);
+    let selector = &hints["position"];
+    builder.assert_one_hot(selector, position);
+    //z_t, the nibble t of the key from the position
+    let z = builder.select_window(selector, key_nibbles, MPT_KEY_NIBBLES);
+
+    //a branch: offsets o_0 = header size and o_(j+1) = o_j + 1 + 32c_j, the items start with
+    //0x80 or 0xa0 and tile the node, the child of the nibble is not empty
+    let branch = if last { 0 } else { hint("branch") };
+    let short: Combination = vec![(1, 1), (branch, -1)];
+    let mut branch_offset: u64 = 0;
+    if !last {
+        let long_branch = hint("long_branch");
+        let children = &hints["children"];
+        builder.assert_bits(&[branch, long_branch]);
+        builder.assert_bits(children);
+        let mut offsets: Vec<Combination> = vec![vec![(1, 2), (long_branch, 1)]];
+        for child in children.iter() {
+            let next = with(offsets.last().unwrap(), &[(1, 1), (*child, 32)]);
+            offsets.push(next);
+        }
+        for (j, offset) in offsets.iter().enumerate() {
+            let selector = &hints["items"][(j * m)..((j + 1) * m)];
+            builder.assert_one_hot(selector, offset);
+            let first = builder.select(selector, &bytes);
+            let mut prefix = vec![(first, 1), (1, -0x80)];
+            if j < 16 {
+                prefix.push((children[j], -32));
+            }
+            builder.assert_product_zero(&[(branch, 1)], &prefix);
+        }
+        builder.assert_product_zero(
+            &[(branch, 1)],
+            &with(&bytes[0], &[(1, -0xf8), (long_branch, -1)]),
+        );
+        let short_header = builder.r1cs(&[(branch, 1)], &[(1, 1), (long_branch, -1)]);
+        builder.assert_product_zero(
+            &[(short_header, 1)],
+            &with(&bytes[1], &[(length, -1), (1, 2)]),
+        );
+        let long_header = builder.mult(branch, long_branch);
+        let size: Combination = bytes[1]
+            .iter()
+            .map(|(id, weight)| (*id, 256 * weight))
+            .chain(bytes[2].iter().copied())
+            .collect();
+        builder.assert_product_zero(&[(long_header, 1)], &with(&size, &[(length, -1), (1, 3)]));
+        builder.assert_product_zero(&[(branch, 1)], &with(&offsets[16], &[(1, 1), (length, -1)]));
+
+        let nibble = &hints["nibble"];
+        builder.assert_one_hot(nibble, &[(z[0], 1)]);
+        let child_values: Vec<Combination> = children.iter().map(|c| vec![(*c, 1)]).collect();
+        let child = builder.select(nibble, &child_values);
+        builder.assert_product_zero(&[(branch, 1)], &[(child, 1), (1, -1)]);
+        branch_offset = builder.select(nibble, &offsets[..16]);
+    }
+
+    //an extension or a leaf: the list header, the path header at 1 + long_list and the path
+    //from a = 1 + long_list + prefixed_path, its flag nibble and its nibbles
+    let (long_list, prefixed, path_size) =
+        (hint("long_list"), hint("prefixed_path"), hint("path_size"));
+    let path_start = &hints["path_start"];
+    builder.assert_bits(&[long_list, prefixed]);
+    builder.assert_one_hot(path_start, &[(long_list, 1), (prefixed, 1)]);
+    //bits of the bytes g_k of the path from a, g_0 holds the flag
+    let path_bits: Vec<Vec<u64>> = (0..=REFERENCE_SIZE)
+        .map(|k| {
+            (0..8)
+                .map(|b| {
+                    let values: Vec<Combination> = (1..=3).map(|a| bit(a + k, b)).collect();
+                    builder.select(path_start, &values)
+                })
+                .collect()
+        })
+        .collect();
+    let flag = &path_bits[0];
+    let odd = flag[4];
+
+    let short_list = builder.r1cs(&short, &[(1, 1), (long_list, -1)]);
+    builder.assert_product_zero(
+        &[(short_list, 1)],
+        &with(&bytes[0], &[(length, -1), (1, -0xbf)]),
+    );
+    let long_list_wire = builder.r1cs(&short, &[(long_list, 1)]);
+    builder.assert_product_zero(&[(long_list_wire, 1)], &with(&bytes[0], &[(1, -0xf8)]));
+    builder.assert_product_zero(
+        &[(long_list_wire, 1)],
+        &with(&bytes[1], &[(length, -1), (1, 2)]),
+    );
+    let difference: Combination = with(
+        &bytes[2],
+        &bytes[1]
+            .iter()
+            .map(|(id, weight)| (*id, -weight))
+            .collect::<Combination>(),
+    );
+    let shift = builder.r1cs(&[(long_list, 1)], &difference);
+    let path_header = with(&bytes[1], &[(shift, 1)]);
+    let prefixed_wire = builder.r1cs(&short, &[(prefixed, 1)]);
+    builder.assert_product_zero(
+        &[(prefixed_wire, 1)],
+        &with(&path_header, &[(1, -0x80), (path_size, -1)]),
+    );
+    let single_wire = builder.r1cs(&short, &[(1, 1), (prefixed, -1)]);
+    builder.assert_product_zero(&[(single_wire, 1)], &[(path_size, 1), (1, -1)]);
+    builder.assert_product_zero(&short, &[(flag[7], 1)]);
+    builder.assert_product_zero(&short, &[(flag[6], 1)]);
+    builder.assert_product_zero(&short, &[(flag[5], 1), (1, -(last as i64))]);
+    let even = builder.r1cs(&short, &[(1, 1), (odd, -1)]);
+    builder.assert_product_zero(&[(even, 1)], &get_nibble(flag, false));
+
+    //the path has e = 2*(path_size - 1) + odd nibbles, the mask selects them and they are the
+    //nibbles of the key from the position
+    let nibbles_amount: Combination = vec![(path_size, 2), (1, -2), (odd, 1)];
+    let mask = &hints["path_mask"];
+    if last {
+        builder.assert_prefix_mask(mask, &nibbles_amount);
+    } else {
+        let short_amount = builder.r1cs(&short, &nibbles_amount);
+        builder.assert_prefix_mask(mask, &[(short_amount, 1)]);
+    }
+    //H_u the nibble u of g_1, g_2, ..., the nibble t is H_t for an even path, else the low
+    //nibble of g_0 and H_(t-1)
+    let even_nibbles: Vec<Combination> = (0..MPT_KEY_NIBBLES)
+        .map(|u| get_nibble(&path_bits[1 + u / 2], u % 2 == 0))
+        .collect();
+    for t in 0..MPT_KEY_NIBBLES {
+        let odd_nibble = if t == 0 {
+            get_nibble(flag, false)
+        } else {
+            even_nibbles[t - 1].clone()
+        };
+        let difference = with(
+            &odd_nibble,
+            &even_nibbles[t]
+                .iter()
+                .map(|(id, weight)| (*id, -weight))
+                .collect::<Combination>(),
+        );
+        let shift = builder.r1cs(&[(odd, 1)], &difference);
+        let check = with(&even_nibbles[t], &[(shift, 1), (z[t], -1)]);
+        builder.assert_product_zero(&[(mask[t], 1)], &check);
+    }
+    let path_nibbles: Combination = mask.iter().map(|id| (*id, 1)).collect();
+    wires.next_position = [position.clone(), vec![(branch, 1)], path_nibbles.clone()].concat();
+
+    //the second item starts after the path
+    let second_offset: Combination = vec![(1, 1), (long_list, 1), (prefixed, 1), (path_size, 1)];
+    let second = &hints["second_item"];
+    builder.assert_one_hot(second, &second_offset);
+    if !last {
+        //an extension references the next node with 0xa0 and 32 bytes that end it
+        let first = builder.select(second, &bytes);
+        builder.assert_product_zero(&short, &[(first, 1), (1, -0xa0)]);
+        builder.assert_product_zero(&short, &with(&second_offset, &[(1, 33), (length, -1)]));
+        let branch_reference = builder.mult(branch, branch_offset);
+        let short_reference = builder.r1cs(&short, &second_offset);
+        let reference = &hints["reference"];
+        builder.assert_one_hot(
+            reference,
+            &[(branch_reference, 1), (1, 1), (short_reference, 1)],
+        );
+        wires.reference = builder.select_window(reference, &bytes, REFERENCE_SIZE);
+        return wires;
+    }
+
+    //the value of the leaf is a single byte below 0x80, 0x80 + size then the bytes or 0xb8,
+    //the size and the bytes, and it ends the leaf at the end of the path of the key
+    let v = layout.value_size;
+    let window = builder.select_window(second, &bytes, v + 2);
+    let kind = &hints["value_kind"];
+    let value_size = hint("value_size");
+    builder.assert_bits(kind);
+    builder.assert_zero(&[(kind[0], 1), (kind[1], 1), (kind[2], 1), (1, -1)]);
+    builder.assert_product_zero(&[(kind[0], 1)], &[(value_size, 1), (1, -1)]);
+    builder.assert_product_zero(
+        &[(kind[1], 1)],
+        &[(window[0], 1), (1, -0x80), (value_size, -1)],
+    );
+    builder.assert_product_zero(&[(kind[2], 1)], &[(window[0], 1), (1, -0xb8)]);
+    builder.assert_product_zero(&[(kind[2], 1)], &[(window[1], 1), (value_size, -1)]);
+    builder.assert_zero(&with(
+        &second_offset,
+        &[(kind[1], 1), (kind[2], 2), (value_size, 1), (length, -1)],
+    ));
+    builder.assert_zero(&with(
+        &with(position, &path_nibbles),
+        &[(1, -(MPT_KEY_NIBBLES as i64))],
+    ));
+    let value_mask = &hints["value_mask"];
+    builder.assert_prefix_mask(value_mask, &[(value_size, 1)]);
+    wires.value = (0..v)
+        .map(|k| {
+            let terms: Combination = (0..3)
+                .map(|h| (builder.mult(kind[h], window[k + h]), 1))
+                .collect();
+            builder.r1cs(&[(value_mask[k], 1)], &terms)
+        })
+        .collect();
+    wires.value_size = value_size;
+    wires
+}
@@ -0,0 +1,216 @@
+use crate::keccak256::*;
+
+//Plain Keccak-f[25w] for w in 1, 2, 4, ..., 64, used to test the circuit. Lane (x, y) is
+//state[x + 5y] and bit z of the lane is bit z of the u64, the bits above w are always 0.
+//The round constants and rotation offsets are computed as in the Keccak specification instead
+//of being read from the tables of the circuit.
+
+pub type KeccakState = [u64; 25];
+
+/// States after every step of a round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundSteps {
+    pub theta: KeccakState,
+    pub rho: KeccakState,
+    pub pi: KeccakState,
+    pub chi: KeccakState,
+    pub iota: KeccakState,
+}
+
+fn get_mask(w: u64) -> u64 {
+    if w == 64 {
+        u64::MAX
+    } else {
+        (1 << w) - 1
+    }
+}
+
+fn rotate_lane(lane: u64, n: u64, w: u64) -> u64 {
+    let n = n % w;
+    if n == 0 {
+        lane
+    } else {
+        ((lane << n) | (lane >> (w - n))) & get_mask(w)
+    }
+}
+
+/// Bit t of the LFSR x^8 + x^6 + x^5 + x^4 + 1 generating the round constants
+fn get_rc_bit(t: u64) -> u64 {
+    let mut r: u64 = 1;
+    for _ in 0..(t % 255) {
+        r <<= 1;
+        if r & 0x100 != 0 {
+            r ^= 0x171;
+        }
+    }
+    r & 1
+}
+
+/// Round constant of round, truncated to w bits
+pub fn get_round_constant(round: usize, w: u64) -> u64 {
+    let mut rc = 0;
+    for j in 0..=6 {
+        let z = (1 << j) - 1;
+        if z < w {
+            rc |= get_rc_bit(j + 7 * round as u64) << z;
+        }
+    }
+    rc
+}
+
+/// Rotation offset of every lane, (t + 1)(t + 2) / 2 for the lane reached after t steps of
+/// (x, y) -> (y, 2x + 3y) from (1, 0)
+pub fn get_rotation_offsets(w: u64) -> [u64; 25] {
+    let mut offsets = [0; 25];
+    let (mut x, mut y) = (1, 0);
+    for t in 0..24 {
+        offsets[x + 5 * y] = ((t + 1) * (t + 2) / 2) % w;
+        (x, y) = (y, (2 * x + 3 * y) % 5);
+    }
+    offsets
+}
+
+pub fn theta(state: &KeccakState, w: u64) -> KeccakState {
+    let mut c = [0; 5];
+    for (x, c) in c.iter_mut().enumerate() {
+        *c = (0..5).fold(0, |parity, y| parity ^ state[x + 5 * y]);
+    }
+    let mut res = [0; 25];
+    for (i, lane) in res.iter_mut().enumerate() {
+        let x = i % 5;
+        *lane = state[i] ^ c[(x + 4) % 5] ^ rotate_lane(c[(x + 1) % 5], 1, w);
+    }
+    res
+}
+
+pub fn rho(state: &KeccakState, w: u64) -> KeccakState {
+    let offsets = get_rotation_offsets(w);
+    let mut res = [0; 25];
+    for (i, lane) in res.iter_mut().enumerate() {
+        *lane = rotate_lane(state[i], offsets[i], w);
+    }
+    res
+}
+
+/// Moves lane (x, y) to lane (y, 2x + 3y)
+pub fn pi(state: &KeccakState) -> KeccakState {
+    let mut res = [0; 25];
+    for y in 0..5 {
+        for x in 0..5 {
+            res[y + 5 * ((2 * x + 3 * y) % 5)] = state[x + 5 * y];
+        }
+    }
+    res
+}
+
+pub fn chi(state: &KeccakState, w: u64) -> KeccakState {
+    let mut res = [0; 25];
+    for (i, lane) in res.iter_mut().enumerate() {
+        let (x, y) = (i % 5, i / 5);
+        let not_next = !state[(x + 1) % 5 + 5 * y] & get_mask(w);
+        *lane = state[i] ^ (not_next & state[(x + 2) % 5 + 5 * y]);
+    }
+    res
+}
+
+pub fn iota(state: &KeccakState, w: u64, round: usize) -> KeccakState {
+    let mut res = *state;
+    res[0] ^= get_round_constant(round, w);
+    res
+}
+
+/// Every step of the round of index round applied to state
+pub fn get_round_steps(state: &KeccakState, w: u64, round: usize) -> RoundSteps {
+    let theta = theta(state, w);
+    let rho = rho(&theta, w);
+    let pi = pi(&rho);
+    let chi = chi(&pi, w);
+    let iota = iota(&chi, w, round);
+    RoundSteps {
+        theta,
+        rho,
+        pi,
+        chi,
+        iota,
+    }
+}
+
+/// Rounds of Keccak-f[25w], 12 + 2l for w = 2^l
+pub fn get_rounds_amount(w: u64) -> usize {
+    12 + 2 * w.trailing_zeros() as usize
+}
+
+pub fn keccak_f(state: &KeccakState, w: u64) -> KeccakState {
+    keccak_p(state, w, get_rounds_amount(w))
+}
+
+/// Keccak-p[25w, rounds], the last rounds of Keccak-f. Rounds are between 1 and 12 + 2l.
+pub fn keccak_p(state: &KeccakState, w: u64, rounds: usize) -> KeccakState {
+    let rounds_amount = get_rounds_amount(w);
+    assert!(
+        rounds > 0 && rounds <= rounds_amount,
+        "Keccak-p needs between 1 and {} rounds for w = {}, got {}",
+        rounds_amount,
+        w,
+        rounds
+    );
+    ((rounds_amount - rounds)..rounds_amount).fold(*state, |state, round| {
+        get_round_steps(&state, w, round).iota
+    })
+}
+
+/// Bits of the state in the order of the circuit, lane x + 5y from bit (x + 5y)w, least
+/// significant bit first
+pub fn state_to_bits(state: &KeccakState, w: u64) -> Vec<i64> {
+    state
+        .iter()
+        .flat_map(|lane| (0..w).map(move |z| ((lane >> z) & 1) as i64))
+        .collect()
+}
+
+/// Inverse of state_to_bits
+pub fn bits_to_state(bits: &[i64], w: u64) -> KeccakState {
+    let mut state = [0; 25];
+    for (lane, chunk) in state.iter_mut().zip(bits.chunks(w as usize)) {
+        *lane = chunk
+            .iter()
+            .enumerate()
+            .map(|(z, bit)| (*bit as u64) << z)
+            .sum();
+    }
+    state
+}
+
+/// Keccak[r, c] of the bits of message followed by the suffix bits and the pad10*1, l bits
+/// of output, computed with keccak_f
+pub fn keccak_sponge(message: &[i64], suffix: &[i64], r: u64, c: u64, l: u64) -> Vec<i64> {
+    let w = (r + c) / 25;
+    let mut padded = [message, suffix, &[1]].concat();
+    while !(padded.len() as u64 + 1).is_multiple_of(r) {
+        padded.push(0);
+    }
+    padded.push(1);
+
+    let mut bits = vec![0; 25 * w as usize];
+    for block in padded.chunks(r as usize) {
+        for (bit, p) in bits.iter_mut().zip(block) {
+            *bit ^= p;
+        }
+        bits = state_to_bits(&keccak_f(&bits_to_state(&bits, w), w), w);
+    }
+    let mut output = Vec::new();
+    loop {
+        output.extend_from_slice(&bits[..(r as usize)]);
+        if output.len() as u64 >= l {
+            output.truncate(l as usize);
+            return output;
+        }
+        bits = state_to_bits(&keccak_f(&bits_to_state(&bits, w), w), w);
+    }
+}
+
+/// Keccak-256 of message with keccak_sponge, without building a circuit
+pub fn keccak256_reference(message: &[u8]) -> [u8; 32] {
+    let digest = keccak_sponge(&bytes_to_bits(message), &[], 1088, 512, 256);
+    bits_to_bytes(&digest).try_into().unwrap()
+}
@@ -0,0 +1,815 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use lac::assertion::ASSERTIONS;
+use lac::builder::*;
+use lac::interface::*;
+use lac::utils::*;
+use std::fmt;
+
+//Recursive Length Prefix, the serialization of Ethereum. An item is a byte string or a list of
+//items, encoded as a header followed by its payload:
+//  a single byte below 0x80            the byte itself, no header
+//  a string of n <= 55 bytes           0x80 + n
+//  a string of n > 55 bytes            0xb7 + size of n, then n big endian
+//  a list of payload n <= 55 bytes     0xc0 + n
+//  a list of payload n > 55 bytes      0xf7 + size of n, then n big endian
+//The decoder only accepts the canonical encoding, the shortest one of every item.
+//
+//The gadgets encode lists of a fixed shape, a list of fields that are byte strings of a fixed
+//size like hashes, byte strings up to a size or big endian integers without leading zeros. A
+//field is given to the circuit as its bits, left aligned and followed by 0s, and for the
+//fields of variable size as a one-hot selector of its size. The encoding depends on the sizes,
+//the prover gives one-hot selectors of the size of the payload and of the offsets of the
+//fields that can move as hints, and the circuit checks them against the sizes. The decoder
+//takes the fields as hints too, encodes them and checks that the result is the input, the
+//encoder only writes canonical encodings.
+
+/// Position of an item in its encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RlpItem {
+    pub offset: usize,
+    pub header_size: usize,
+    pub payload_size: usize,
+    pub is_list: bool,
+}
+
+impl RlpItem {
+    /// Size of the header and the payload
+    pub fn get_size(&self) -> usize {
+        self.header_size + self.payload_size
+    }
+
+    /// Offset of the first byte after the item
+    pub fn get_end(&self) -> usize {
+        self.offset + self.get_size()
+    }
+
+    pub fn get_payload<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
+        &bytes[(self.offset + self.header_size)..self.get_end()]
+    }
+}
+
+/// Encodings the decoder rejects, with the offset of the item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RlpError {
+    Truncated { offset: usize },
+    NonCanonical { offset: usize },
+    NotAList { offset: usize },
+    TrailingBytes { offset: usize },
+    FieldMismatch { index: usize },
+}
+
+impl fmt::Display for RlpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RlpError::Truncated { offset } => {
+                write!(f, "the item at {} goes past the end of the bytes", offset)
+            }
+            RlpError::NonCanonical { offset } => {
+                write!(f, "the item at {} is not encoded canonically", offset)
+            }
+            RlpError::NotAList { offset } => write!(f, "the item at {} is not a list", offset),
+            RlpError::TrailingBytes { offset } => {
+                write!(f, "bytes after the end of the item, from {}", offset)
+            }
+            RlpError::FieldMismatch { index } => {
+                write!(f, "the field {} does not fit the shape of the list", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RlpError {}
+
+///Item whose header starts at offset, it must end before the end of bytes
+pub fn decode_rlp_item(bytes: &[u8], offset: usize) -> Result<RlpItem, RlpError> {
+    let truncated = RlpError::Truncated { offset };
+    let non_canonical = RlpError::NonCanonical { offset };
+    let first = *bytes.get(offset).ok_or(truncated.clone())?;
+    let (is_list, short_base) = if first < 0xc0 {
+        (false, 0x80)
+    } else {
+        (true, 0xc0)
+    };
+    let (header_size, payload_size) = if first < 0x80 {
+        (0, 1)
+    } else if first <= short_base + 55 {
+        (1, (first - short_base) as usize)
+    } else {
+        let size_size = (first - short_base - 55) as usize;
+        let size_bytes = bytes
+            .get((offset + 1)..(offset + 1 + size_size))
+            .ok_or(truncated.clone())?;
+        if size_bytes[0] == 0 || size_size > 4 {
+            return Err(non_canonical);
+        }
+        let payload_size = size_bytes
+            .iter()
+            .fold(0, |size, byte| (size << 8) | *byte as usize);
+        if payload_size <= 55 {
+            return Err(non_canonical);
+        }
+        (1 + size_size, payload_size)
+    };
+    let item = RlpItem {
+        offset,
+        header_size,
+        payload_size,
+        is_list,
+    };
+    if item.get_end() > bytes.len() {
+        return Err(truncated);
+    }
+    //a single byte below 0x80 has no header
+    if !is_list && header_size == 1 && payload_size == 1 && bytes[offset + 1] < 0x80 {
+        return Err(non_canonical);
+    }
+    Ok(item)
+}
+
+///Items of the list encoded by bytes, which must be exactly one list
+pub fn decode_rlp_list(bytes: &[u8]) -> Result<Vec<RlpItem>, RlpError> {
+    let list = decode_rlp_item(bytes, 0)?;
+    if !list.is_list {
+        return Err(RlpError::NotAList { offset: 0 });
+    }
+    if list.get_end() != bytes.len() {
+        return Err(RlpError::TrailingBytes {
+            offset: list.get_end(),
+        });
+    }
+    let mut items = Vec::new();
+    let mut offset = list.header_size;
+    while offset < bytes.len() {
+        let item = decode_rlp_item(bytes, offset)?;
+        offset = item.get_end();
+        items.push(item);
+    }
+    Ok(items)
+}
+
+///Header of a string (base 0x80) or of a list (base 0xc0) of payload size bytes
+fn get_rlp_header(size: usize, base: u8) -> Vec<u8> {
+    if size <= 55 {
+        return vec![base + size as u8];
+    }
+    let size_bytes = get_integer_bytes(size as u64);
+    [vec![base + 55 + size_bytes.len() as u8], size_bytes].concat()
+}
+
+///Encoding of a byte string
+pub fn encode_rlp_string(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    [get_rlp_header(bytes.len(), 0x80), bytes.to_vec()].concat()
+}
+
+///Encoding of the list of the items, each of them already encoded
+pub fn encode_rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    [get_rlp_header(payload.len(), 0xc0), payload].concat()
+}
+
+///Big endian bytes of the integer without leading zeros, none for 0
+pub fn get_integer_bytes(value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(8);
+    bytes[start..].to_vec()
+}
+
+/// Field of a list of fixed shape, with the size in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RlpField {
+    /// byte string of exactly this size, at least 2
+    Fixed(usize),
+    /// byte string of at most this size, at most 55
+    Bytes(usize),
+    /// big endian integer of at most this size without leading zeros, at most 55
+    Integer(usize),
+}
+
+impl RlpField {
+    pub fn get_max_size(&self) -> usize {
+        match self {
+            RlpField::Fixed(size) | RlpField::Bytes(size) | RlpField::Integer(size) => *size,
+        }
+    }
+
+    pub fn is_variable(&self) -> bool {
+        !matches!(self, RlpField::Fixed(_))
+    }
+
+    /// Wires of the field in the gadgets: its bits, then the one-hot selector of its size for
+    /// a field of variable size
+    pub fn get_wires_amount(&self) -> usize {
+        let size = self.get_max_size();
+        if self.is_variable() {
+            9 * size + 1
+        } else {
+            8 * size
+        }
+    }
+
+    /// Smallest and largest size of the encoding of the field
+    fn get_encoding_range(&self) -> (usize, usize) {
+        match self {
+            RlpField::Fixed(size) => {
+                let encoding_size = get_rlp_header(*size, 0x80).len() + size;
+                (encoding_size, encoding_size)
+            }
+            RlpField::Bytes(size) | RlpField::Integer(size) => (1, size + 1),
+        }
+    }
+
+    /// Checks that the value fits the field
+    fn check_value(&self, value: &[u8]) -> bool {
+        match self {
+            RlpField::Fixed(size) => value.len() == *size,
+            RlpField::Bytes(size) => value.len() <= *size,
+            RlpField::Integer(size) => value.len() <= *size && value.first() != Some(&0),
+        }
+    }
+}
+
+/// Shape of a list whose items are named fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RlpListShape {
+    fields: Vec<(&'static str, RlpField)>,
+}
+
+impl RlpListShape {
+    /// Panics if a field has a size the gadgets do not support or if the names repeat
+    pub fn new(fields: Vec<(&'static str, RlpField)>) -> Self {
+        assert!(!fields.is_empty(), "the list needs at least 1 field");
+        for (i, (name, field)) in fields.iter().enumerate() {
+            let size = field.get_max_size();
+            match field {
+                RlpField::Fixed(_) => assert!(
+                    size >= 2,
+                    "the fixed field {} needs at least 2 bytes, got {}",
+                    name,
+                    size
+                ),
+                _ => assert!(
+                    (1..=55).contains(&size),
+                    "the field {} needs a size between 1 and 55, got {}",
+                    name,
+                    size
+                ),
+            }
+            assert!(
+                fields[..i].iter().all(|(other, _)| other != name),
+                "the field {} is repeated",
+                name
+            );
+        }
+        let shape = RlpListShape { fields };
+        assert!(
+            shape.get_payload_range().1 <= 0xffff,
+            "the payload of the list must fit a header of 3 bytes"
+        );
+        shape
+    }
+
+    pub fn get_fields(&self) -> &[(&'static str, RlpField)] {
+        &self.fields
+    }
+
+    /// Index of the field of name name
+    pub fn get_field_index(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|(field, _)| *field == name)
+    }
+
+    /// Largest size of the encoding of the list
+    pub fn get_max_size(&self) -> usize {
+        let payload = self.get_payload_range().1;
+        get_rlp_header(payload, 0xc0).len() + payload
+    }
+
+    /// Wires of all the fields in the gadgets
+    pub fn get_fields_wires_amount(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|(_, field)| field.get_wires_amount())
+            .sum()
+    }
+
+    /// Hints of the gadgets, the one-hot selector of the payload size if it can change and of
+    /// the offset of every field that can move
+    pub fn get_hints_amount(&self) -> usize {
+        let (payload_min, payload_max) = self.get_payload_range();
+        let payload = if payload_max > payload_min {
+            payload_max - payload_min + 1
+        } else {
+            0
+        };
+        payload
+            + self
+                .get_offset_ranges()
+                .iter()
+                .filter(|(min, max)| max > min)
+                .map(|(min, max)| max - min + 1)
+                .sum::<usize>()
+    }
+
+    /// Encoding of the list of the values, fails if a value does not fit its field
+    pub fn encode(&self, values: &[Vec<u8>]) -> Result<Vec<u8>, RlpError> {
+        self.check_values(values)?;
+        let items: Vec<Vec<u8>> = values
+            .iter()
+            .map(|value| encode_rlp_string(value))
+            .collect();
+        Ok(encode_rlp_list(&items))
+    }
+
+    /// Values of the fields of the encoded list, fails if it is not a list of this shape
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<Vec<u8>>, RlpError> {
+        let items = decode_rlp_list(bytes)?;
+        for (index, item) in items.iter().enumerate() {
+            if item.is_list || index >= self.fields.len() {
+                return Err(RlpError::FieldMismatch { index });
+            }
+        }
+        let values: Vec<Vec<u8>> = items
+            .iter()
+            .map(|item| item.get_payload(bytes).to_vec())
+            .collect();
+        self.check_values(&values)?;
+        Ok(values)
+    }
+
+    /// Values of the wires of the fields for the gadgets
+    pub fn get_fields_values(&self, values: &[Vec<u8>]) -> Result<Vec<i64>, RlpError> {
+        self.check_values(values)?;
+        let mut wires = Vec::new();
+        for ((_, field), value) in self.fields.iter().zip(values) {
+            wires.extend(self.get_field_values(field, value));
+        }
+        Ok(wires)
+    }
+
+    /// Values of the hints of the gadgets
+    pub fn get_hints(&self, values: &[Vec<u8>]) -> Result<Vec<i64>, RlpError> {
+        self.check_values(values)?;
+        let sizes: Vec<usize> = values
+            .iter()
+            .map(|value| encode_rlp_string(value).len())
+            .collect();
+        let payload: usize = sizes.iter().sum();
+        let mut hints = Vec::new();
+        let (payload_min, payload_max) = self.get_payload_range();
+        if payload_max > payload_min {
+            hints.extend(get_one_hot(
+                payload - payload_min,
+                payload_max - payload_min + 1,
+            ));
+        }
+        let mut offset = get_rlp_header(payload, 0xc0).len();
+        for ((min, max), size) in self.get_offset_ranges().into_iter().zip(sizes) {
+            if max > min {
+                hints.extend(get_one_hot(offset - min, max - min + 1));
+            }
+            offset += size;
+        }
+        Ok(hints)
+    }
+
+    /// Encodes the fields with the hints on the builder, see get_fields_values and get_hints
+    /// for their layout. Checks that the fields are bits and fit their size and that the hints
+    /// are the selectors of the payload size and of the offsets.
+    pub fn append_encoder(
+        &self,
+        builder: &mut CircuitBuilder,
+        fields: &[u64],
+        hints: &[u64],
+    ) -> RlpEncodingWires {
+        assert_eq!(
+            fields.len(),
+            self.get_fields_wires_amount(),
+            "wrong amount of field wires"
+        );
+        assert_eq!(
+            hints.len(),
+            self.get_hints_amount(),
+            "wrong amount of hints"
+        );
+        let size = self.get_max_size();
+        let mut encoding: Vec<Combination> = vec![Vec::new(); 8 * size];
+
+        //the payload size P with the weight that selects it, and the list header
+        let (payload_min, payload_max) = self.get_payload_range();
+        let (payload_selector, mut hints) = if payload_max > payload_min {
+            hints.split_at(payload_max - payload_min + 1)
+        } else {
+            (&hints[..0], hints)
+        };
+        let payloads: Vec<(usize, Combination)> = if payload_selector.is_empty() {
+            vec![(payload_min, vec![(1, 1)])]
+        } else {
+            (payload_min..=payload_max)
+                .zip(payload_selector)
+                .map(|(payload, s)| (payload, vec![(*s, 1)]))
+                .collect()
+        };
+        let mut header_size: Combination = Vec::new();
+        for (payload, weight) in payloads.iter() {
+            let header = get_rlp_header(*payload, 0xc0);
+            append_constant_bytes(&mut encoding, 0, &header, weight);
+            header_size.extend(scale(weight, header.len() as i64));
+        }
+
+        //every field at its offset, moved by its selector when it can move
+        let mut offset = header_size;
+        let mut payload: Combination = Vec::new();
+        let mut fields = fields;
+        for ((_, field), (min, max)) in self.fields.iter().zip(self.get_offset_ranges()) {
+            let (wires, rest) = fields.split_at(field.get_wires_amount());
+            fields = rest;
+            let (field_encoding, field_size) = append_field_encoding(builder, field, wires);
+            if max == min {
+                for (j, bits) in field_encoding.iter().enumerate() {
+                    for (b, bit) in bits.iter().enumerate() {
+                        encoding[8 * (min + j) + b].extend(bit.iter().copied());
+                    }
+                }
+            } else {
+                let (selector, rest) = hints.split_at(max - min + 1);
+                hints = rest;
+                builder.assert_one_hot(selector, &with(&offset, &[(1, -(min as i64))]));
+                for (k, s) in selector.iter().enumerate() {
+                    for (j, bits) in field_encoding.iter().enumerate() {
+                        for (b, bit) in bits.iter().enumerate() {
+                            if !bit.is_empty() {
+                                let moved = builder.r1cs(&[(*s, 1)], bit);
+                                encoding[8 * (min + k + j) + b].push((moved, 1));
+                            }
+                        }
+                    }
+                }
+            }
+            offset.extend(field_size.iter().copied());
+            payload.extend(field_size);
+        }
+        if !payload_selector.is_empty() {
+            builder.assert_one_hot(
+                payload_selector,
+                &with(&payload, &[(1, -(payload_min as i64))]),
+            );
+        }
+
+        let bits = encoding
+            .iter()
+            .map(|bit| {
+                if bit.is_empty() {
+                    0
+                } else {
+                    builder.linear(bit)
+                }
+            })
+            .collect();
+        let mut mask = Vec::new();
+        for p in 0..size {
+            let byte: Combination = payloads
+                .iter()
+                .filter(|(payload, _)| p < get_rlp_header(*payload, 0xc0).len() + payload)
+                .flat_map(|(_, weight)| weight.iter().copied())
+                .collect();
+            let id = if byte.is_empty() {
+                0
+            } else {
+                builder.linear(&byte)
+            };
+            mask.extend([id; 8]);
+        }
+        let length: Combination = payloads
+            .iter()
+            .flat_map(|(payload, weight)| {
+                scale(
+                    weight,
+                    (get_rlp_header(*payload, 0xc0).len() + payload) as i64,
+                )
+            })
+            .collect();
+        RlpEncodingWires {
+            bits,
+            mask,
+            length: builder.linear(&length),
+        }
+    }
+
+    /// Checks that the bits of encoding are the encoding of the fields, given as hints with
+    /// the hints of append_encoder, and returns the wire of its length
+    pub fn append_decoder(
+        &self,
+        builder: &mut CircuitBuilder,
+        encoding: &[u64],
+        fields: &[u64],
+        hints: &[u64],
+    ) -> u64 {
+        assert_eq!(
+            encoding.len(),
+            8 * self.get_max_size(),
+            "the encoding needs the bits of the largest list"
+        );
+        let wires = self.append_encoder(builder, fields, hints);
+        for (bit, encoded) in encoding.iter().zip(wires.bits.iter()) {
+            builder.assert_zero(&[(*bit, 1), (*encoded, -1)]);
+        }
+        wires.length
+    }
+
+    /// Wires of every field among the wires of all of them
+    pub fn split_fields<'a, T>(&self, mut fields: &'a [T]) -> Vec<&'a [T]> {
+        let mut split = Vec::new();
+        for (_, field) in self.fields.iter() {
+            let (wires, rest) = fields.split_at(field.get_wires_amount());
+            split.push(wires);
+            fields = rest;
+        }
+        split
+    }
+
+    fn check_values(&self, values: &[Vec<u8>]) -> Result<(), RlpError> {
+        if values.len() != self.fields.len() {
+            return Err(RlpError::FieldMismatch {
+                index: values.len().min(self.fields.len()),
+            });
+        }
+        for (index, ((_, field), value)) in self.fields.iter().zip(values).enumerate() {
+            if !field.check_value(value) {
+                return Err(RlpError::FieldMismatch { index });
+            }
+        }
+        Ok(())
+    }
+
+    fn get_field_values(&self, field: &RlpField, value: &[u8]) -> Vec<i64> {
+        let size = field.get_max_size();
+        let mut bits = bytes_to_bits(value);
+        bits.resize(8 * size, 0);
+        if field.is_variable() {
+            bits.extend(get_one_hot(value.len(), size + 1));
+        }
+        bits
+    }
+
+    fn get_payload_range(&self) -> (usize, usize) {
+        self.fields
+            .iter()
+            .map(|(_, field)| field.get_encoding_range())
+            .fold((0, 0), |(min, max), (field_min, field_max)| {
+                (min + field_min, max + field_max)
+            })
+    }
+
+    /// Smallest and largest offset of every field in the encoding
+    fn get_offset_ranges(&self) -> Vec<(usize, usize)> {
+        let (payload_min, payload_max) = self.get_payload_range();
+        let mut offset = (
+            get_rlp_header(payload_min, 0xc0).len(),
+            get_rlp_header(payload_max, 0xc0).len(),
+        );
+        let mut ranges = Vec::new();
+        for (_, field) in self.fields.iter() {
+            ranges.push(offset);
+            let (min, max) = field.get_encoding_range();
+            offset = (offset.0 + min, offset.1 + max);
+        }
+        ranges
+    }
+}
+
+/// Wires of an encoding of the gadgets: the bits of the largest list, 0 after the end, the
+/// bit mask of the length as the variable length Keccak circuit reads it and the length
+pub struct RlpEncodingWires {
+    pub bits: Vec<u64>,
+    pub mask: Vec<u64>,
+    pub length: u64,
+}
+
+/// Adds the bits of the constant bytes from offset to the encoding, times weight
+fn append_constant_bytes(
+    encoding: &mut [Combination],
+    offset: usize,
+    bytes: &[u8],
+    weight: &Combination,
+) {
+    for (j, byte) in bytes.iter().enumerate() {
+        for b in 0..8 {
+            if (byte >> b) & 1 == 1 {
+                encoding[8 * (offset + j) + b].extend(weight.iter().copied());
+            }
+        }
+    }
+}
+
+/// Bits of the encoding of the field from its first byte and its size, checking the field
+fn append_field_encoding(
+    builder: &mut CircuitBuilder,
+    field: &RlpField,
+    wires: &[u64],
+) -> (Vec<Vec<Combination>>, Combination) {
+    let size = field.get_max_size();
+    let (bits, size_selector) = wires.split_at(8 * size);
+    builder.assert_bits(bits);
+    let data: Vec<Vec<Combination>> = bits
+        .chunks(8)
+        .map(|byte| byte.iter().map(|bit| vec![(*bit, 1)]).collect())
+        .collect();
+    if !field.is_variable() {
+        let header = get_rlp_header(size, 0x80);
+        let mut encoding: Vec<Combination> = vec![Vec::new(); 8 * header.len()];
+        append_constant_bytes(&mut encoding, 0, &header, &vec![(1, 1)]);
+        let header_bits: Vec<Vec<Combination>> =
+            encoding.chunks(8).map(|byte| byte.to_vec()).collect();
+        let encoding_size = (header.len() + size) as i64;
+        return ([header_bits, data].concat(), vec![(1, encoding_size)]);
+    }
+
+    //the size selector u is one-hot and the bytes from the size on are 0
+    builder.assert_bits(size_selector);
+    let mut sum: Combination = size_selector.iter().map(|u| (*u, 1)).collect();
+    sum.push((1, -1));
+    builder.assert_zero(&sum);
+    for k in 0..size {
+        let before: Combination = size_selector[..=k].iter().map(|u| (*u, 1)).collect();
+        let byte: Combination = bits[(8 * k)..(8 * k + 8)].iter().map(|b| (*b, 1)).collect();
+        builder.assert_product_zero(&before, &byte);
+    }
+    //an integer that is not 0 starts with a byte that is not 0
+    if let RlpField::Integer(_) = field {
+        let mut zeros: Vec<u64> = bits[..8]
+            .chunks(2)
+            .map(|b| builder.r1cs(&[(1, 1), (b[0], -1)], &[(1, 1), (b[1], -1)]))
+            .collect();
+        while zeros.len() > 1 {
+            zeros = zeros.chunks(2).map(|z| builder.mult(z[0], z[1])).collect();
+        }
+        let zero = zeros[0];
+        builder.assert_product_zero(&[(1, 1), (size_selector[0], -1)], &[(zero, 1)]);
+    }
+
+    //a single byte below 0x80 is its own encoding, else the header 0x80 + size comes first
+    let single = builder.r1cs(&[(size_selector[1], 1)], &[(1, 1), (bits[7], -1)]);
+    let mut first: Vec<Combination> = Vec::new();
+    let mut second: Vec<Combination> = Vec::new();
+    for (b, bit) in bits[..8].iter().enumerate() {
+        let header: Combination = size_selector
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| ((0x80 + k) >> b) & 1 == 1)
+            .map(|(_, u)| (*u, 1))
+            .collect();
+        let single_bit = builder.mult(single, *bit);
+        let single_header = if header.is_empty() {
+            Vec::new()
+        } else {
+            vec![(builder.r1cs(&[(single, 1)], &header), -1)]
+        };
+        first.push([header, vec![(single_bit, 1)], single_header].concat());
+        second.push(vec![(*bit, 1), (single_bit, -1)]);
+    }
+    let encoding_size: Combination = size_selector
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(k, u)| (*u, k as i64))
+        .chain([(1, 1), (single, -1)])
+        .collect();
+    let mut encoding = vec![first, second];
+    encoding.extend(data.into_iter().skip(1));
+    (encoding, encoding_size)
+}
+
+/// Ids of the fields and of the hints in the basic layer from first_id
+fn get_shape_ids(shape: &RlpListShape, first_id: u64) -> (Vec<u64>, Vec<u64>) {
+    let fields_end = first_id + shape.get_fields_wires_amount() as u64;
+    (
+        (first_id..fields_end).collect(),
+        (fields_end..(fields_end + shape.get_hints_amount() as u64)).collect(),
+    )
+}
+
+///Circuit encoding a list of the shape. The input groups are the fields by name, their bits,
+///with the one-hot selector of the size "<name>_size" for the fields of variable size, and the
+///private hints. The outputs are the bits of the encoding, 0 after its end, as "encoding" and
+///its length in bytes as "length". The output group ASSERTIONS is 0 iff the fields fit the
+///shape and the hints hold.
+pub fn get_rlp_encoding_circuit(shape: &RlpListShape) -> LAC<i64> {
+    let (field_ids, hint_ids) = get_shape_ids(shape, 2);
+    let size = 2 + field_ids.len() + hint_ids.len();
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; size - 2]));
+
+    let mut builder = CircuitBuilder::new(1, size as u64);
+    let wires = shape.append_encoder(&mut builder, &field_ids, &hint_ids);
+    let check = builder.fold_checks();
+    let outputs = [wires.bits.clone(), vec![wires.length, check]].concat();
+    lac.append_layers(builder.finish(&outputs));
+
+    for ((name, field), ids) in shape.fields.iter().zip(shape.split_fields(&field_ids)) {
+        let (bits, size_selector) = ids.split_at(8 * field.get_max_size());
+        lac.declare_input(name, Visibility::Public, bits.to_vec())
+            .unwrap();
+        if field.is_variable() {
+            lac.declare_input(
+                &format!("{}_size", name),
+                Visibility::Public,
+                size_selector.to_vec(),
+            )
+            .unwrap();
+        }
+    }
+    lac.declare_input("hints", Visibility::Private, hint_ids)
+        .unwrap();
+    lac.declare_output("encoding", wires.bits).unwrap();
+    lac.declare_output("length", vec![wires.length]).unwrap();
+    lac.declare_output(ASSERTIONS, vec![check]).unwrap();
+    lac
+}
+
+///Inputs of get_rlp_encoding_circuit for the values of the fields
+pub fn get_rlp_encoding_assignment(
+    shape: &RlpListShape,
+    values: &[Vec<u8>],
+) -> Result<Assignment<i64>, RlpError> {
+    let fields = shape.get_fields_values(values)?;
+    let mut assignment: Assignment<i64> = Assignment::new();
+    for ((name, field), wires) in shape.fields.iter().zip(shape.split_fields(&fields)) {
+        let (bits, size_selector) = wires.split_at(8 * field.get_max_size());
+        assignment.set(name, bits.to_vec());
+        if field.is_variable() {
+            assignment.set(&format!("{}_size", name), size_selector.to_vec());
+        }
+    }
+    assignment.set("hints", shape.get_hints(values)?);
+    Ok(assignment)
+}
+
+///Circuit decoding a list of the shape given as the private input "encoding", the bits of the
+///bytes of the list followed by 0s up to get_max_size bytes. The fields and the hints are the
+///private inputs "fields" and "hints". The outputs are the bits of every field by name, 0
+///after its size, the size "<name>_size" of the fields of variable size and the length of the
+///list "length". The output group ASSERTIONS is 0 iff the encoding is the canonical encoding
+///of a list of the shape.
+pub fn get_rlp_decoding_circuit(shape: &RlpListShape) -> LAC<i64> {
+    let encoding_ids: Vec<u64> = (2..(2 + 8 * shape.get_max_size() as u64)).collect();
+    let (field_ids, hint_ids) = get_shape_ids(shape, 2 + encoding_ids.len() as u64);
+    let size = 2 + encoding_ids.len() + field_ids.len() + hint_ids.len();
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(vec![0; size - 2]));
+
+    let mut builder = CircuitBuilder::new(1, size as u64);
+    let length = shape.append_decoder(&mut builder, &encoding_ids, &field_ids, &hint_ids);
+    let mut outputs: Vec<(String, Vec<u64>)> = Vec::new();
+    for ((name, field), ids) in shape.fields.iter().zip(shape.split_fields(&field_ids)) {
+        let (bits, size_selector) = ids.split_at(8 * field.get_max_size());
+        outputs.push((name.to_string(), bits.to_vec()));
+        if field.is_variable() {
+            let size: Combination = size_selector
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(k, u)| (*u, k as i64))
+                .collect();
+            outputs.push((format!("{}_size", name), vec![builder.linear(&size)]));
+        }
+    }
+    let check = builder.fold_checks();
+    let output_ids: Vec<u64> = outputs
+        .iter()
+        .flat_map(|(_, ids)| ids.iter().copied())
+        .chain([length, check])
+        .collect();
+    lac.append_layers(builder.finish(&output_ids));
+
+    lac.declare_input("encoding", Visibility::Private, encoding_ids)
+        .unwrap();
+    lac.declare_input("fields", Visibility::Private, field_ids)
+        .unwrap();
+    lac.declare_input("hints", Visibility::Private, hint_ids)
+        .unwrap();
+    for (name, ids) in outputs {
+        lac.declare_output(&name, ids).unwrap();
+    }
+    lac.declare_output("length", vec![length]).unwrap();
+    lac.declare_output(ASSERTIONS, vec![check]).unwrap();
+    lac
+}
+
+///Inputs of get_rlp_decoding_circuit for the encoded list, fails if it is not a list of the
+///shape
+pub fn get_rlp_decoding_assignment(
+    shape: &RlpListShape,
+    bytes: &[u8],
+) -> Result<Assignment<i64>, RlpError> {
+    let values = shape.decode(bytes)?;
+    let mut encoding = bytes_to_bits(bytes);
+    encoding.resize(8 * shape.get_max_size(), 0);
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("encoding", encoding);
+    assignment.set("fields", shape.get_fields_values(&values)?);
+    assignment.set("hints", shape.get_hints(&values)?);
+    Ok(assignment)
+}
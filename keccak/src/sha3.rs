@@ -0,0 +1,57 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use lac::utils::*;
+
+//FIPS 202 hashes, Keccak[r, c] with the domain suffix 01 for SHA3 and 1111 for SHAKE before
+//the pad10*1. The capacity is twice the output length for SHA3 and twice the security
+//strength for SHAKE, the rate is 1600 - c. Messages are bytes, see bytes_to_bits.
+
+/// Domain suffix of the SHA3 hashes
+pub const SHA3_SUFFIX: [i64; 2] = [0, 1];
+/// Domain suffix of the SHAKE extendable output functions
+pub const SHAKE_SUFFIX: [i64; 4] = [1, 1, 1, 1];
+
+///SHA3 circuit with a digest of l bits, for l in 224, 256, 384 and 512
+fn get_sha3_circuit(message: &[u8], l: u64) -> LAC<i64> {
+    let c = 2 * l;
+    get_keccak_lac_circuit_with_suffix(bytes_to_bits(message), SHA3_SUFFIX.to_vec(), 1600 - c, c, l)
+        .unwrap()
+}
+
+///SHAKE circuit of security strength s bits, for s in 128 and 256, with an output of l bits
+fn get_shake_circuit(message: &[u8], s: u64, l: u64) -> Result<LAC<i64>, KeccakError> {
+    let c = 2 * s;
+    get_keccak_lac_circuit_with_suffix(
+        bytes_to_bits(message),
+        SHAKE_SUFFIX.to_vec(),
+        1600 - c,
+        c,
+        l,
+    )
+}
+
+pub fn sha3_224_circuit(message: &[u8]) -> LAC<i64> {
+    get_sha3_circuit(message, 224)
+}
+
+pub fn sha3_256_circuit(message: &[u8]) -> LAC<i64> {
+    get_sha3_circuit(message, 256)
+}
+
+pub fn sha3_384_circuit(message: &[u8]) -> LAC<i64> {
+    get_sha3_circuit(message, 384)
+}
+
+pub fn sha3_512_circuit(message: &[u8]) -> LAC<i64> {
+    get_sha3_circuit(message, 512)
+}
+
+///SHAKE128 with an output of output_bits bits, rejects an empty output
+pub fn shake128_circuit(message: &[u8], output_bits: u64) -> Result<LAC<i64>, KeccakError> {
+    get_shake_circuit(message, 128, output_bits)
+}
+
+///SHAKE256 with an output of output_bits bits, rejects an empty output
+pub fn shake256_circuit(message: &[u8], output_bits: u64) -> Result<LAC<i64>, KeccakError> {
+    get_shake_circuit(message, 256, output_bits)
+}
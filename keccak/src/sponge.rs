@@ -0,0 +1,176 @@
+use crate::keccak_circuit::*;
+use crate::keccak_f_circuit::*;
+use lac::subcircuit::*;
+use lac::utils::*;
+use lac::xor::*;
+use std::collections::HashSet;
+
+//A sponge that appends its layers to a circuit built by the caller, as a hasher absorbing and
+//squeezing wires. Wires are ids the next layer can read, the gates of the last layer of the
+//circuit or its basic values when it has no layers, and the circuit must hold the constants 0
+//and 1 there. Every layer added by the sponge relays all the wires of the layer before it, so
+//the wires of the caller stay readable after each call, except the states of the sponge that
+//are no longer needed. The state starts as the constant 0 and absorbed wires are used as the
+//state without any layer while they meet a 0, a block only costs an xor layer when the state
+//is not 0, and one Keccak-p instance with fresh ids.
+
+/// Keccak[r, c] sponge appending its layers to a `LAC`, see `absorb` and `squeeze`
+pub struct KeccakSpongeGadget {
+    r: u64,
+    w: u64,
+    suffix: Vec<i64>,
+    keccak_p: SubCircuit<i64>,
+    state_ids: Vec<u64>,
+    block: Vec<u64>,
+    squeezed: Option<u64>,
+    private_ids: HashSet<u64>,
+}
+
+impl KeccakSpongeGadget {
+    /// Sponge with the rate r and the capacity c, using Keccak-f[r + c] and no suffix
+    pub fn new(r: u64, c: u64) -> Result<Self, KeccakError> {
+        check_keccak_parameters(r, c, 1)?;
+        let w = (r + c) / 25;
+        Ok(KeccakSpongeGadget {
+            r,
+            w,
+            suffix: Vec::new(),
+            keccak_p: get_keccak_f_subcircuit(get_template_state_ids(w), w),
+            state_ids: vec![0; 25 * w as usize],
+            block: Vec::new(),
+            squeezed: None,
+            private_ids: HashSet::new(),
+        })
+    }
+
+    /// Domain suffix bits added before the pad10*1, as SHA3_SUFFIX and SHAKE_SUFFIX
+    pub fn set_suffix(&mut self, suffix: Vec<i64>) {
+        assert!(
+            suffix.iter().all(|bit| *bit == 0 || *bit == 1),
+            "suffix bits must be 0 or 1"
+        );
+        self.suffix = suffix;
+    }
+
+    /// Uses Keccak-p[r + c, rounds], the last rounds of Keccak-f, from the next block on
+    pub fn set_rounds(&mut self, rounds: usize) -> Result<(), KeccakError> {
+        check_keccak_p_parameters(self.r, 25 * self.w - self.r, 1, rounds)?;
+        self.keccak_p = get_keccak_p_subcircuit(get_template_state_ids(self.w), self.w, rounds);
+        Ok(())
+    }
+
+    pub fn get_rate(&self) -> u64 {
+        self.r
+    }
+
+    /// Absorbs the bits `wires`, a block is xored into the state and permuted as soon as it
+    /// is full. Panics once the sponge has been squeezed.
+    pub fn absorb(&mut self, lac: &mut LAC<i64>, wires: &[u64]) {
+        assert!(self.squeezed.is_none(), "can not absorb after squeezing");
+        for id in wires {
+            self.block.push(*id);
+            if self.block.len() as u64 == self.r {
+                let block = std::mem::take(&mut self.block);
+                self.absorb_block(lac, block);
+            }
+        }
+    }
+
+    /// Wires of the next n output bits. The first call pads the absorbed bits with the
+    /// suffix and the pad10*1, the state is permuted again every r bits.
+    pub fn squeeze(&mut self, lac: &mut LAC<i64>, n: u64) -> Vec<u64> {
+        if self.squeezed.is_none() {
+            let mut padded = std::mem::take(&mut self.block);
+            padded.extend(self.suffix.iter().map(|bit| *bit as u64));
+            padded.push(1);
+            while !(padded.len() as u64 + 1).is_multiple_of(self.r) {
+                padded.push(0);
+            }
+            padded.push(1);
+            for block in padded.chunks(self.r as usize) {
+                self.absorb_block(lac, block.to_vec());
+            }
+            self.squeezed = Some(0);
+        }
+
+        let mut out_ids: Vec<u64> = Vec::new();
+        while (out_ids.len() as u64) < n {
+            let mut squeezed = self.squeezed.unwrap();
+            if squeezed == self.r {
+                self.permute(lac);
+                squeezed = 0;
+            }
+            let amount = (n - out_ids.len() as u64).min(self.r - squeezed);
+            for id in &self.state_ids[(squeezed as usize)..((squeezed + amount) as usize)] {
+                //returned wires are relayed by the next permutations
+                self.private_ids.remove(id);
+                out_ids.push(*id);
+            }
+            self.squeezed = Some(squeezed + amount);
+        }
+        out_ids
+    }
+
+    /// S = S xor P followed by S = f(S), with one layer when a bit of P meets a bit of S
+    /// that is not the constant 0
+    fn absorb_block(&mut self, lac: &mut LAC<i64>, block: Vec<u64>) {
+        let (mut in_ids0, mut in_ids1, mut positions) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, id) in block.into_iter().enumerate() {
+            if self.state_ids[i] == 0 {
+                self.state_ids[i] = id;
+            } else if id != 0 {
+                in_ids0.push(self.state_ids[i]);
+                in_ids1.push(id);
+                positions.push(i);
+            }
+        }
+
+        if !positions.is_empty() {
+            let degree = lac.get_layers_amount() as u64 + 1;
+            let first_id = lac.get_max_id() + 1;
+            let out_ids: Vec<u64> = (first_id..(first_id + positions.len() as u64)).collect();
+            let mut layer =
+                get_xor_bitstring_as_layer(in_ids0.clone(), in_ids1, out_ids.clone(), degree);
+            layer.copy_gates_by_ids(self.get_pass_through(lac, &in_ids0));
+            lac.append_layer(layer);
+            for (i, id) in positions.into_iter().zip(out_ids) {
+                self.state_ids[i] = id;
+                self.private_ids.insert(id);
+            }
+        }
+        self.permute(lac);
+    }
+
+    /// S = f(S) with a Keccak-p instance whose ids are above every id of the circuit
+    fn permute(&mut self, lac: &mut LAC<i64>) {
+        let degree = lac.get_layers_amount() as u64 + 1;
+        let remap = IdRemap::new(lac.get_max_id().max(RESERVED_IDS - 1) + 1 - RESERVED_IDS);
+        let pass_through = self.get_pass_through(lac, &self.state_ids.clone());
+        let layers = self
+            .keccak_p
+            .instantiate(degree, &self.state_ids, &remap, &pass_through);
+        lac.append_layers(layers);
+
+        self.state_ids = self.keccak_p.get_instance_output_ids(&remap);
+        self.private_ids.extend(self.state_ids.iter().copied());
+    }
+
+    /// Wires of the last layer to relay, all but the ids in consumed_ids created by the sponge
+    /// and never returned by squeeze
+    fn get_pass_through(&mut self, lac: &LAC<i64>, consumed_ids: &[u64]) -> Vec<u64> {
+        let dead: HashSet<u64> = consumed_ids
+            .iter()
+            .copied()
+            .filter(|id| self.private_ids.remove(id))
+            .collect();
+        lac.get_last_layer_ids()
+            .into_iter()
+            .filter(|id| !dead.contains(id))
+            .collect()
+    }
+}
+
+/// Input and output ports of the Keccak-p template
+fn get_template_state_ids(w: u64) -> Vec<u64> {
+    (RESERVED_IDS..(RESERVED_IDS + 25 * w)).collect()
+}
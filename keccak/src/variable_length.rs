@@ -0,0 +1,413 @@
+use crate::keccak256::*;
+use crate::keccak_circuit::*;
+use crate::keccak_f_circuit::*;
+use lac::interface::*;
+use lac::mux::*;
+use lac::subcircuit::*;
+use lac::utils::*;
+use lac::xor::*;
+use std::collections::BTreeMap;
+
+//Keccak circuit for every message of n bits up to a maximum length, its shape only depends on
+//the maximum amount of blocks. The private input "message" has the bits of max_blocks blocks,
+//the bits from n on are ignored, "length" is n and the hint "length_mask" holds the bits m_i,
+//1 iff i < n, for i below the maximum length. The circuit asserts that the mask is n bits 1
+//followed by 0s, so the padding is linear in the mask:
+//  e_i = m_(i-1) - m_i         1 iff i = n, with m_(-1) = 1 and m_i = 0 from the maximum length
+//  P_i = x_i*m_i + sum s_j*e_(i-j) + e_(i-t) + f_k     f_k only for the last bit of the block k
+//where s is the suffix of t bits and f_k, 1 iff the block k is the last one of the padded
+//message, is a sum of consecutive e_i, which is m_(a-1) - m_b. Every block is absorbed, the
+//state after the block k > 0 is a mux of the permuted state and the state before the block,
+//selected by a_k = m_(kr-t-2), 1 iff the block k is part of the padded message.
+
+///Longest message the circuit of max_blocks blocks of r bits can hash with a suffix of
+///suffix_size bits, the padding needs at least 2 bits
+pub fn get_variable_length_max_size(max_blocks: u64, suffix_size: u64, r: u64) -> Option<u64> {
+    (max_blocks * r).checked_sub(suffix_size + 2)
+}
+
+///Keccak circuit for every message of at most get_variable_length_max_size bits, followed by
+///the domain suffix bits. The inputs "message", "length" and "length_mask" are 0 until they
+///are set, see get_variable_length_assignment, the output "digest" has l bits and the output
+///group ASSERTIONS checks the length and its mask.
+pub fn get_keccak_variable_length_circuit(
+    max_blocks: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+) -> Result<LAC<i64>, KeccakError> {
+    check_keccak_parameters(r, c, l)?;
+    let max_size = get_variable_length_max_size(max_blocks, suffix.len() as u64, r)
+        .ok_or(KeccakError::InvalidBlocksAmount { max_blocks, r })?;
+    assert!(
+        suffix.iter().all(|bit| *bit == 0 || *bit == 1),
+        "suffix bits must be 0 or 1"
+    );
+    let padded_size = max_blocks * r;
+
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer = get_keccak_basic_layer(vec![0; padded_size as usize]);
+    for id in (2 + padded_size)..(3 + padded_size + max_size) {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id, 0);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    lac.append_layers(get_variable_length_hash_layers(max_blocks, suffix, r, c, l));
+
+    let (message_ids, mask_ids) = get_variable_length_input_ids(max_blocks, max_size, r);
+    let length_id = 2 + padded_size;
+    lac.declare_input("message", Visibility::Private, message_ids)
+        .unwrap();
+    lac.declare_input("length", Visibility::Private, vec![length_id])
+        .unwrap();
+    lac.declare_input("length_mask", Visibility::Private, mask_ids.clone())
+        .unwrap();
+    lac.declare_output("digest", get_digest_ids(l)).unwrap();
+
+    //the mask is made of bits, m_(i+1)*(1-m_i) is 0 iff it is not a 1 after a 0, and it has
+    //length bits 1, the only check that can be negative is not folded with others
+    lac.assert_boolean(mask_ids.clone()).unwrap();
+    if max_size > 1 {
+        let first_id = lac.get_max_id() + 1;
+        let mut layer: Layer<i64> = Layer::new();
+        layer.set_degree(1);
+        for (i, ids) in mask_ids.windows(2).enumerate() {
+            let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+            gate.set_all(
+                Some(1),
+                Some(first_id + i as u64),
+                None,
+                Some([vec![ids[1]], vec![1, ids[0]]]),
+                Some([vec![1], vec![1, -1]]),
+            );
+            layer.append_gate(gate);
+        }
+        lac.assert_checks(layer).unwrap();
+    }
+    let check_id = lac.get_max_id() + 1;
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(1);
+    let mut weights = vec![1; mask_ids.len()];
+    weights.push(-1);
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+    gate.set_all(
+        Some(1),
+        Some(check_id),
+        None,
+        Some([[mask_ids, vec![length_id]].concat(), vec![1]]),
+        Some([weights, vec![1]]),
+    );
+    layer.append_gate(gate);
+    lac.assert_checks(layer).unwrap();
+
+    Ok(lac)
+}
+
+///Inputs of get_keccak_variable_length_circuit for the message, padded with 0s to max_blocks
+///blocks, its length and the length mask. Panics if the message is too long.
+pub fn get_variable_length_assignment(
+    message: &[i64],
+    max_blocks: u64,
+    suffix_size: u64,
+    r: u64,
+) -> Assignment<i64> {
+    let max_size = get_variable_length_max_size(max_blocks, suffix_size, r).unwrap_or(0);
+    let length = message.len() as u64;
+    assert!(
+        length <= max_size,
+        "the message has {} bits, the circuit hashes at most {}",
+        length,
+        max_size
+    );
+    let mut padded = message.to_vec();
+    padded.resize((max_blocks * r) as usize, 0);
+    let mask = (0..max_size).map(|i| (i < length) as i64).collect();
+
+    let mut assignment = Assignment::new();
+    assignment.set("message", padded);
+    assignment.set("length", vec![length as i64]);
+    assignment.set("length_mask", mask);
+    assignment
+}
+
+/// Layers of get_keccak_variable_length_circuit without the interface and the assertions,
+/// the digest is at the ids of get_digest_ids of the last layer
+pub(crate) fn get_variable_length_hash_layers(
+    max_blocks: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    c: u64,
+    l: u64,
+) -> Vec<Layer<i64>> {
+    let w = (r + c) / 25;
+    let rounds = get_keccak_f_rounds_amount(w);
+    let mut layers = get_variable_length_padding_layers(max_blocks, suffix, r, w);
+
+    let mut absorbing_phase_layers =
+        get_variable_length_absorbing_phase_layers(max_blocks, r, w, rounds);
+    let degree = 3 + absorbing_phase_layers.len() as u64;
+    layers.append(&mut absorbing_phase_layers);
+
+    let s_ids = get_state_ids(max_blocks, r, w);
+    layers.append(&mut get_keccak_squeezing_phase_layers(
+        s_ids, r, degree, w, l, rounds,
+    ));
+    layers
+}
+
+/// Ids of the message and of the length mask in the basic layer, the length is between them
+pub(crate) fn get_variable_length_input_ids(
+    max_blocks: u64,
+    max_size: u64,
+    r: u64,
+) -> (Vec<u64>, Vec<u64>) {
+    let padded_size = max_blocks * r;
+    (
+        (2..(2 + padded_size)).collect(),
+        ((3 + padded_size)..(3 + padded_size + max_size)).collect(),
+    )
+}
+
+/// Keccak-256 of every message of at most max_blocks blocks as a template, its input ports
+/// are the message and the length mask of get_variable_length_input_ids
+pub(crate) fn get_keccak256_variable_length_subcircuit(max_blocks: u64) -> SubCircuit<i64> {
+    let max_size = get_variable_length_max_size(max_blocks, 0, KECCAK256_RATE).unwrap();
+    let (message_ids, mask_ids) =
+        get_variable_length_input_ids(max_blocks, max_size, KECCAK256_RATE);
+    get_keccak_hash_subcircuit(
+        get_variable_length_hash_layers(
+            max_blocks,
+            Vec::new(),
+            KECCAK256_RATE,
+            KECCAK256_CAPACITY,
+            KECCAK256_OUTPUT_BITS,
+        ),
+        [message_ids, mask_ids].concat(),
+        KECCAK256_OUTPUT_BITS,
+    )
+}
+
+/// Wires bound to the ports of get_keccak256_variable_length_subcircuit for the message bits
+/// and its length mask, the bits after them are the constant 0
+pub(crate) fn get_keccak256_variable_length_inputs(
+    max_blocks: u64,
+    message: &[u64],
+    mask: &[u64],
+) -> Vec<u64> {
+    let max_size = get_variable_length_max_size(max_blocks, 0, KECCAK256_RATE).unwrap();
+    let mut message = message.to_vec();
+    message.resize((max_blocks * KECCAK256_RATE) as usize, 0);
+    let mut mask = mask.to_vec();
+    mask.resize(max_size as usize, 0);
+    [message, mask].concat()
+}
+
+/// Linear combination of the basic values, id to weight
+type Terms = BTreeMap<u64, i64>;
+
+/// Layers 1 and 2, the padded message in the layout of get_keccak_first_layer followed by the
+/// flags a_k of the blocks k > 0 at the ids after the state:
+/// layer1:    q_i=x_i*m_i at the id of x_i    l_i=(sum w_j*m_j)*1 the padding    a_k=0+m_(kr-t-2)
+/// layer2:    P_i=(q_i+l_i)*1 at the id of P_i, in the state for the block 0
+fn get_variable_length_padding_layers(
+    max_blocks: u64,
+    suffix: Vec<i64>,
+    r: u64,
+    w: u64,
+) -> Vec<Layer<i64>> {
+    let t = suffix.len() as i64;
+    let padded_size = max_blocks * r;
+    let max_size = get_variable_length_max_size(max_blocks, t as u64, r).unwrap() as i64;
+    let s_ids = get_state_ids(max_blocks, r, w);
+    let flag_ids = get_flag_ids(max_blocks, r, w);
+    let pad_start = *s_ids.last().unwrap() + 1 + max_blocks + 25 * w;
+
+    //m_j as a basic value, the constant 1 before the message and nothing from the maximum
+    let mask_id = |j: i64| -> Option<u64> {
+        if j < 0 {
+            Some(1)
+        } else if j < max_size {
+            Some(3 + padded_size + j as u64)
+        } else {
+            None
+        }
+    };
+    let add_mask = |terms: &mut Terms, j: i64, weight: i64| {
+        if let Some(id) = mask_id(j) {
+            *terms.entry(id).or_insert(0) += weight;
+        }
+    };
+    let add_e = |terms: &mut Terms, n: i64, weight: i64| {
+        if (0..=max_size).contains(&n) {
+            add_mask(terms, n - 1, weight);
+            add_mask(terms, n, -weight);
+        }
+    };
+
+    let mut layer1: Layer<i64> = Layer::new();
+    layer1.set_degree(1);
+    layer1.add_gate_0_and_1(1);
+    let mut layer2: Layer<i64> = Layer::new();
+    layer2.set_degree(2);
+    layer2.add_gate_0_and_1(2);
+
+    for i in 0..(padded_size as i64) {
+        let mut terms = Terms::new();
+        for (j, bit) in suffix.iter().enumerate() {
+            add_e(&mut terms, i - j as i64, *bit);
+        }
+        add_e(&mut terms, i - t, 1);
+        if (i + 1) % r as i64 == 0 {
+            //the block k is the last one for the lengths a..=b
+            let k = i / r as i64;
+            let a = (k * r as i64 - t - 1).max(0);
+            let b = ((k + 1) * r as i64 - t - 2).min(max_size);
+            if a <= b {
+                add_mask(&mut terms, a - 1, 1);
+                add_mask(&mut terms, b, -1);
+            }
+        }
+        terms.retain(|_, weight| *weight != 0);
+
+        let mut sum_ids: Vec<u64> = Vec::new();
+        if i < max_size {
+            let x_id = 2 + i as u64;
+            let mut gate: Gate<i64> = Gate::new_mult_gate();
+            gate.set_all(
+                Some(1),
+                Some(x_id),
+                Some([x_id, mask_id(i).unwrap()]),
+                None,
+                None,
+            );
+            layer1.append_gate(gate);
+            sum_ids.push(x_id);
+        }
+        if !terms.is_empty() {
+            let pad_id = pad_start + i as u64;
+            let (ids, weights): (Vec<u64>, Vec<i64>) = terms.into_iter().unzip();
+            let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+            gate.set_all(
+                Some(1),
+                Some(pad_id),
+                None,
+                Some([ids, vec![1]]),
+                Some([weights, vec![1]]),
+            );
+            layer1.append_gate(gate);
+            sum_ids.push(pad_id);
+        }
+
+        let out_id = if (i as u64) < r {
+            s_ids[i as usize]
+        } else {
+            2 + i as u64
+        };
+        let gate: Gate<i64> = if sum_ids.is_empty() {
+            let mut gate = Gate::new_add_gate();
+            gate.set_all(Some(2), Some(out_id), Some([0, 0]), None, None);
+            gate
+        } else {
+            let mut gate = Gate::new_R1CS_gate();
+            let weights = vec![1; sum_ids.len()];
+            gate.set_all(
+                Some(2),
+                Some(out_id),
+                None,
+                Some([sum_ids, vec![1]]),
+                Some([weights, vec![1]]),
+            );
+            gate
+        };
+        layer2.append_gate(gate);
+    }
+    for id in s_ids[(r as usize)..].iter() {
+        let mut gate: Gate<i64> = Gate::new_add_gate();
+        gate.set_all(Some(2), Some(*id), Some([0, 0]), None, None);
+        layer2.append_gate(gate);
+    }
+
+    for (k, flag_id) in (1..max_blocks).zip(flag_ids.clone()) {
+        let j = (k * r) as i64 - t - 2;
+        let mut gate: Gate<i64> = Gate::new_add_gate();
+        gate.set_all(
+            Some(1),
+            Some(flag_id),
+            Some([0, mask_id(j).unwrap()]),
+            None,
+            None,
+        );
+        layer1.append_gate(gate);
+    }
+    layer2.copy_gates_by_ids(flag_ids);
+
+    vec![layer1, layer2]
+}
+
+/// Absorbing phase of get_keccak_absorbing_phase_layers from the degree 3, every block k > 0
+/// saves the state S before the xor and ends with S = MUX(a_k, S_saved, f(S xor P_k))
+fn get_variable_length_absorbing_phase_layers(
+    max_blocks: u64,
+    r: u64,
+    w: u64,
+    rounds: usize,
+) -> Vec<Layer<i64>> {
+    let mut layers: Vec<Layer<i64>> = Vec::new();
+    let mut degree = 3;
+    let s_i = get_state_ids(max_blocks, r, w);
+    let flag_ids = get_flag_ids(max_blocks, r, w);
+    let saved_start = *s_i.last().unwrap() + max_blocks;
+    let saved_ids: Vec<u64> = (saved_start..(saved_start + 25 * w)).collect();
+    let keccak_f = get_keccak_p_subcircuit(s_i.clone(), w, rounds);
+    for i in 0..max_blocks {
+        let remaining_blocks: Vec<u64> = ((2 + (i + 1) * r)..(2 + (max_blocks * r))).collect();
+        //the flags of the blocks i..max_blocks, a_i is the first one from i = 1
+        let remaining_flags = flag_ids[(i.max(1) as usize - 1)..].to_vec();
+        let mut pass_through = [remaining_blocks.clone(), remaining_flags.clone()].concat();
+        if i > 0 {
+            let p_i: Vec<u64> = ((2 + i * r)..(2 + (i + 1) * r)).collect();
+            let s_i_subst: Vec<u64> = s_i[..(r as usize)].to_vec();
+            let mut layer = get_xor_bitstring_as_layer(s_i_subst.clone(), p_i, s_i_subst, degree);
+            layer.add_gate_0_and_1(degree);
+            layer.copy_gates_by_ids(s_i[(r as usize)..].to_vec());
+            layer.copy_gates_by_ids_set_out(s_i.clone(), saved_ids.clone());
+            layer.copy_gates_by_ids(pass_through.clone());
+            layers.push(layer);
+            degree += 1;
+            pass_through.extend(saved_ids.iter().copied());
+        }
+
+        let mut keccak_f_layers: Vec<Layer<i64>> =
+            keccak_f.instantiate(degree, &s_i, &IdRemap::new(0), &pass_through);
+        degree += keccak_f_layers.len() as u64;
+        layers.append(&mut keccak_f_layers);
+
+        if i > 0 {
+            let flag_id = remaining_flags[0];
+            let mux_layers = get_mux_bitstring_as_layers(
+                flag_id,
+                saved_ids.clone(),
+                s_i.clone(),
+                s_i.clone(),
+                degree,
+            );
+            for mut layer in mux_layers {
+                layer.add_gate_0_and_1(degree);
+                layer.copy_gates_by_ids(remaining_blocks.clone());
+                layer.copy_gates_by_ids(remaining_flags[1..].to_vec());
+                layers.push(layer);
+                degree += 1;
+            }
+        }
+    }
+
+    layers
+}
+
+/// Ids of the flags a_k of the blocks k > 0, placed after the state
+fn get_flag_ids(max_blocks: u64, r: u64, w: u64) -> Vec<u64> {
+    let start = *get_state_ids(max_blocks, r, w).last().unwrap() + 1;
+    (start..(start + max_blocks - 1)).collect()
+}
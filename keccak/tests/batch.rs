@@ -0,0 +1,74 @@
+use keccak::batch::*;
+use keccak::keccak256::*;
+use keccak::keccak_circuit::*;
+
+#[test]
+fn test_batch_against_single_circuits() {
+    let (r, c, l) = (72, 128, 100);
+    let messages: Vec<Vec<i64>> = (0..3)
+        .map(|k| (0..90).map(|i| (i * (k + 2) + i / 5) % 2).collect())
+        .collect();
+    let mut batch = KeccakBatch::new(3, 90, r, c, l).unwrap();
+    let assignment = batch.get_assignment(&messages);
+    let lac = batch.get_lac_mut();
+    assert_eq!(lac.validate(), Ok(()));
+    lac.assign_inputs(&assignment).unwrap();
+    lac.evaluate();
+
+    let digests = batch.get_digests();
+    for (message, digest) in messages.into_iter().zip(digests) {
+        let mut expected = get_keccak_lac_circuit(message, r, c, l);
+        expected.evaluate();
+        assert_eq!(digest, expected.get_named_outputs().unwrap()["digest"]);
+    }
+}
+
+#[test]
+fn test_batch_instance_structure() {
+    // every layer of the batch is the template moved to every instance, the constants 0 and
+    // 1 appear once
+    let mut batch = KeccakBatch::new(3, 20, 8, 17, 16).unwrap();
+    let mut template = batch.get_template().clone();
+    let layers_amount = template.get_layers_amount();
+    assert_eq!(batch.get_lac().get_layers_amount(), layers_amount);
+    assert_eq!(batch.get_lac().get_max_id(), template.get_max_id() + 2 * batch.get_stride());
+
+    for degree in 1..=(layers_amount as u64) {
+        let template_ids = template.get_layer_by_degree(degree).get_gate_ids();
+        let mut expected: Vec<u64> = (0..3)
+            .flat_map(|i| batch.get_instance_remap(i).map_ids(&template_ids))
+            .collect();
+        expected.sort();
+        expected.dedup();
+        let ids = batch.get_lac_mut().get_layer_by_degree(degree).get_gate_ids();
+        assert_eq!(ids, expected, "layer {}", degree);
+        let constants = template_ids.iter().filter(|id| **id < 2).count();
+        assert_eq!(ids.len(), 3 * template_ids.len() - 2 * constants);
+    }
+    let template_digest = template.get_output_group("digest").unwrap().get_ids().to_vec();
+    let remap = batch.get_instance_remap(2);
+    assert_eq!(
+        batch.get_lac().get_output_group("digest_2").unwrap().get_ids(),
+        remap.map_ids(&template_digest)
+    );
+}
+
+#[test]
+fn test_keccak256_batch() {
+    let messages: [&[u8]; 2] = [b"abc", b"xyz"];
+    let mut batch = keccak256_batch_circuit(2, 3);
+    let bits: Vec<Vec<i64>> = messages.iter().map(|m| bytes_to_bits(m)).collect();
+    let assignment = batch.get_assignment(&bits);
+    batch.get_lac_mut().assign_inputs(&assignment).unwrap();
+    batch.get_lac_mut().evaluate();
+    for (message, digest) in messages.iter().zip(batch.get_digests()) {
+        assert_eq!(bits_to_bytes(&digest), keccak256(message));
+    }
+}
+
+#[test]
+#[should_panic(expected = "the batch needs one message per instance")]
+fn test_batch_assignment_size() {
+    let batch = KeccakBatch::new(2, 8, 8, 17, 8).unwrap();
+    batch.get_assignment(&[vec![0; 8]]);
+}
@@ -0,0 +1,34 @@
+//Helpers shared by the tests of the Keccak circuits
+#![allow(dead_code)]
+use keccak::keccak256::get_digest_bytes;
+use lac::interface::Assignment;
+use lac::utils::LAC;
+use std::collections::HashMap;
+
+pub fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..(i + 2)], 16).unwrap())
+        .collect()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Checks that the circuit is valid and returns its digest as hex
+pub fn evaluate_digest(mut lac: LAC<i64>) -> String {
+    assert_eq!(lac.validate(), Ok(()));
+    lac.evaluate();
+    to_hex(&get_digest_bytes(&lac))
+}
+
+/// Evaluates lac on assignment and returns its named outputs
+pub fn evaluate_assignment(
+    lac: &mut LAC<i64>,
+    assignment: &Assignment<i64>,
+) -> HashMap<String, Vec<i64>> {
+    lac.assign_inputs(assignment).unwrap();
+    lac.evaluate();
+    lac.get_named_outputs().unwrap()
+}
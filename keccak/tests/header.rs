@@ -0,0 +1,248 @@
+mod common;
+
+use common::*;
+use keccak::header::*;
+use keccak::keccak256::*;
+use keccak::rlp::*;
+use lac::assertion::ASSERTIONS;
+use lac::interface::*;
+use std::collections::HashMap;
+use std::fs;
+
+//Headers read from tests/header/headers.txt, one block of lines Block, Number, Hash, StateRoot
+//and Header per header, in the order of the chain.
+//
+//A Frontier header takes 5 Keccak blocks and about 4 GB to evaluate, so the circuit chaining
+//the mainnet genesis and block 1 is ignored, run it with --ignored. test_header_chain_circuit
+//checks the chaining of 3 headers of a small shape of 1 block instead.
+
+#[derive(Debug, Clone)]
+struct Block {
+    number: u64,
+    hash: Vec<u8>,
+    state_root: Vec<u8>,
+    header: Vec<u8>,
+}
+
+fn load_blocks() -> Vec<Block> {
+    let path = format!("{}/tests/header/headers.txt", env!("CARGO_MANIFEST_DIR"));
+    let content = fs::read_to_string(&path).unwrap();
+    let mut blocks: Vec<Block> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').unwrap();
+        let value = value.trim();
+        if key.trim() == "Block" {
+            blocks.push(Block {
+                number: 0,
+                hash: Vec::new(),
+                state_root: Vec::new(),
+                header: Vec::new(),
+            });
+            continue;
+        }
+        let block = blocks.last_mut().unwrap();
+        match key.trim() {
+            "Number" => block.number = value.parse().unwrap(),
+            "Hash" => block.hash = from_hex(value),
+            "StateRoot" => block.state_root = from_hex(value),
+            "Header" => block.header = from_hex(value),
+            other => panic!("unknown line {}", other),
+        }
+    }
+    blocks
+}
+
+fn get_headers(blocks: &[Block]) -> Vec<Vec<u8>> {
+    blocks.iter().map(|block| block.header.clone()).collect()
+}
+
+/// Checks the block hash, the state root and the number of the header i against the block
+fn check_block_outputs(outputs: &HashMap<String, Vec<i64>>, i: usize, block: &Block) {
+    assert_eq!(
+        bits_to_bytes(&outputs[&format!("block_hash_{}", i)]),
+        block.hash
+    );
+    assert_eq!(
+        bits_to_bytes(&outputs[&format!("stateRoot_{}", i)]),
+        block.state_root
+    );
+    let mut number = get_integer_bytes(block.number);
+    let size = number.len() as i64;
+    number.resize(8, 0);
+    assert_eq!(bits_to_bytes(&outputs[&format!("number_{}", i)]), number);
+    assert_eq!(outputs[&format!("number_size_{}", i)], vec![size]);
+}
+
+#[test]
+fn test_header_shapes() {
+    let forks = [
+        (HeaderFork::Frontier, 15, "nonce"),
+        (HeaderFork::London, 16, "baseFeePerGas"),
+        (HeaderFork::Shanghai, 17, "withdrawalsRoot"),
+        (HeaderFork::Cancun, 20, "parentBeaconBlockRoot"),
+        (HeaderFork::Prague, 21, "requestsHash"),
+    ];
+    for (fork, fields_amount, last) in forks {
+        let shape = get_header_shape(fork);
+        assert_eq!(shape.get_fields().len(), fields_amount, "{:?}", fork);
+        assert_eq!(shape.get_fields().last().unwrap().0, last);
+        assert_eq!(shape.get_field_index(PARENT_HASH), Some(0));
+    }
+    let shape = get_header_shape(HeaderFork::Frontier);
+    assert_eq!(shape.get_max_size(), 592);
+    assert_eq!(get_header_max_blocks(&shape), 5);
+}
+
+#[test]
+fn test_native_header_chain() {
+    let blocks = load_blocks();
+    assert_eq!(blocks.len(), 3);
+    let shape = get_header_shape(HeaderFork::Frontier);
+    let headers = get_headers(&blocks);
+    let hashes = verify_header_chain(&shape, &headers).unwrap();
+    for (hash, block) in hashes.iter().zip(blocks.iter()) {
+        assert_eq!(hash.to_vec(), block.hash);
+        let values = shape.decode(&block.header).unwrap();
+        assert_eq!(
+            values[shape.get_field_index("stateRoot").unwrap()],
+            block.state_root
+        );
+        assert_eq!(
+            values[shape.get_field_index("number").unwrap()],
+            get_integer_bytes(block.number)
+        );
+    }
+
+    assert_eq!(
+        verify_header_chain(&shape, &[headers[0].clone(), headers[2].clone()]),
+        Err(HeaderError::ParentMismatch { index: 1 })
+    );
+    //a byte of the bloom of block 1 changes its hash, which block 2 no longer references
+    let mut tampered = headers.clone();
+    tampered[1][300] ^= 1;
+    assert_eq!(
+        verify_header_chain(&shape, &tampered),
+        Err(HeaderError::ParentMismatch { index: 2 })
+    );
+    //a Frontier header has no baseFeePerGas
+    assert_eq!(
+        verify_header_chain(&get_header_shape(HeaderFork::London), &headers[..1]),
+        Err(HeaderError::InvalidHeader {
+            index: 0,
+            error: RlpError::FieldMismatch { index: 15 }
+        })
+    );
+    assert_eq!(
+        verify_header_chain(&shape, &[]),
+        Err(HeaderError::EmptyChain)
+    );
+}
+
+#[test]
+fn test_header_circuit() {
+    let blocks = load_blocks();
+    let shape = get_header_shape(HeaderFork::Frontier);
+    let mut lac =
+        get_header_chain_circuit(&shape, 1, &["stateRoot", "number"], Visibility::Private);
+    assert_eq!(lac.validate(), Ok(()));
+    let assignment = get_header_chain_assignment(&shape, &[blocks[1].header.clone()]).unwrap();
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    check_block_outputs(&outputs, 0, &blocks[1]);
+    assert_eq!(outputs[ASSERTIONS], vec![0, 0]);
+
+    //the number 1 written as 2 bytes with a leading zero, with the hints of its size
+    let number = shape.get_field_index("number").unwrap();
+    let mut values = shape.decode(&blocks[1].header).unwrap();
+    values[number] = vec![0x02, 0x01];
+    let mut fields = shape.get_fields_values(&values).unwrap();
+    let start = shape.split_fields(&fields)[..number]
+        .iter()
+        .map(|wires| wires.len())
+        .sum::<usize>();
+    fields[start + 1] = 0;
+    let mut leading_zero = assignment.clone();
+    leading_zero.set("header_0", fields);
+    leading_zero.set("hints_0", shape.get_hints(&values).unwrap());
+    let outputs = evaluate_assignment(&mut lac, &leading_zero);
+    assert_ne!(outputs[ASSERTIONS][0], 0);
+}
+
+#[test]
+#[ignore = "needs ~8 GB"]
+fn test_mainnet_header_chain_circuit() {
+    let blocks = load_blocks();
+    let shape = get_header_shape(HeaderFork::Frontier);
+    let mut lac =
+        get_header_chain_circuit(&shape, 2, &["stateRoot", "number"], Visibility::Private);
+    assert_eq!(lac.validate(), Ok(()));
+    let assignment = get_header_chain_assignment(&shape, &get_headers(&blocks[..2])).unwrap();
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    for (i, block) in blocks[..2].iter().enumerate() {
+        check_block_outputs(&outputs, i, block);
+    }
+    assert_eq!(outputs[ASSERTIONS], vec![0, 0]);
+}
+
+/// Headers of a shape small enough for 1 Keccak block, a chain from a zero parent hash
+fn get_small_chain(shape: &RlpListShape) -> Vec<Vec<u8>> {
+    let mut headers: Vec<Vec<u8>> = Vec::new();
+    let mut parent = vec![0; 32];
+    for (number, extra) in [(0, &b""[..]), (1, b"light"), (300, b"client bridge")] {
+        let header = shape
+            .encode(&[parent, get_integer_bytes(number), extra.to_vec()])
+            .unwrap();
+        parent = keccak256(&header).to_vec();
+        headers.push(header);
+    }
+    headers
+}
+
+#[test]
+fn test_header_chain_circuit() {
+    let shape = RlpListShape::new(vec![
+        (PARENT_HASH, RlpField::Fixed(32)),
+        ("number", RlpField::Integer(8)),
+        ("extraData", RlpField::Bytes(32)),
+    ]);
+    assert_eq!(get_header_max_blocks(&shape), 1);
+    let headers = get_small_chain(&shape);
+    let hashes = verify_header_chain(&shape, &headers).unwrap();
+    let mut lac = get_header_chain_circuit(&shape, 3, &["number"], Visibility::Public);
+    assert_eq!(lac.validate(), Ok(()));
+    let assignment = get_header_chain_assignment(&shape, &headers).unwrap();
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    for (i, hash) in hashes.iter().enumerate() {
+        assert_eq!(
+            bits_to_bytes(&outputs[&format!("block_hash_{}", i)]),
+            hash.to_vec()
+        );
+    }
+    assert_eq!(outputs["number_size_2"], vec![2]);
+    assert_eq!(outputs[ASSERTIONS], vec![0, 0]);
+
+    //the header 2 in the place of the header 1 encodes fine but breaks the chain twice
+    let mut broken = assignment.clone();
+    for name in ["header", "hints"] {
+        let values = assignment.get(&format!("{}_2", name)).unwrap().to_vec();
+        broken.set(&format!("{}_1", name), values);
+    }
+    let outputs = evaluate_assignment(&mut lac, &broken);
+    assert_eq!(outputs[ASSERTIONS][0], 0);
+    assert_ne!(outputs[ASSERTIONS][1], 0);
+
+    let reversed = [headers[1].clone(), headers[0].clone()];
+    assert_eq!(
+        get_header_chain_assignment(&shape, &reversed).err(),
+        Some(HeaderError::ParentMismatch { index: 1 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "the shape needs a field parentHash of 32 bytes to chain the headers")]
+fn test_header_chain_circuit_without_parent() {
+    let shape = RlpListShape::new(vec![("number", RlpField::Integer(8))]);
+    get_header_chain_circuit(&shape, 2, &[], Visibility::Private);
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::keccak_circuit::*;
+use std::collections::HashMap;
+use std::fs;
+
+//Known answer tests read from the files of tests/kat, in the format of the NIST response files.
+//Lines [key = value] set the parameters r, c, suffix (bits, empty for plain Keccak) and
+//Outputlen of the vectors that follow them, each vector is a Len, Msg and MD or Output line.
+//Without Outputlen the output length is the length of MD.
+
+#[derive(Debug)]
+struct KatVector {
+    r: u64,
+    c: u64,
+    suffix: Vec<i64>,
+    output_bits: u64,
+    message: Vec<u8>,
+    digest: String,
+}
+
+fn load_kat_file(name: &str) -> Vec<KatVector> {
+    let path = format!("{}/tests/kat/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let content = fs::read_to_string(&path).unwrap();
+    let mut parameters: HashMap<String, String> = HashMap::new();
+    let mut vectors = Vec::new();
+    let mut len = 0;
+    let mut message = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(parameter) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let (key, value) = parameter.split_once('=').unwrap();
+            parameters.insert(key.trim().to_string(), value.trim().to_string());
+            continue;
+        }
+        let (key, value) = line.split_once('=').unwrap();
+        let value = value.trim();
+        match key.trim() {
+            "Len" => len = value.parse::<usize>().unwrap(),
+            "Msg" => {
+                // only byte oriented messages, the empty one is written 00
+                assert_eq!(
+                    len % 8,
+                    0,
+                    "{}: bit oriented messages are not supported",
+                    name
+                );
+                message = from_hex(value)[..(len / 8)].to_vec();
+            }
+            "MD" | "Output" => {
+                let parameter = |key: &str| parameters[key].parse::<u64>().unwrap();
+                let suffix = parameters["suffix"]
+                    .chars()
+                    .map(|bit| bit.to_digit(2).unwrap() as i64)
+                    .collect();
+                let output_bits = match parameters.get("Outputlen") {
+                    Some(bits) => bits.parse().unwrap(),
+                    None => 4 * value.len() as u64,
+                };
+                vectors.push(KatVector {
+                    r: parameter("r"),
+                    c: parameter("c"),
+                    suffix,
+                    output_bits,
+                    message: message.clone(),
+                    digest: value.to_string(),
+                });
+            }
+            key => panic!("{}: unknown key {}", name, key),
+        }
+    }
+    vectors
+}
+
+fn check_kat_file(name: &str) {
+    let vectors = load_kat_file(name);
+    assert!(!vectors.is_empty());
+    for vector in vectors {
+        let mut lac = get_keccak_lac_circuit_with_suffix(
+            bytes_to_bits(&vector.message),
+            vector.suffix.clone(),
+            vector.r,
+            vector.c,
+            vector.output_bits,
+        )
+        .unwrap();
+        assert_eq!(lac.validate(), Ok(()));
+        lac.evaluate();
+        assert_eq!(
+            to_hex(&get_digest_bytes(&lac)),
+            vector.digest,
+            "{}: r = {}, c = {}, Len = {}",
+            name,
+            vector.r,
+            vector.c,
+            8 * vector.message.len()
+        );
+    }
+}
+
+#[test]
+fn test_kat_files_cover_every_width() {
+    let files = [
+        "keccak256.txt",
+        "keccak_small_widths.txt",
+        "sha3_224.txt",
+        "sha3_256.txt",
+        "sha3_384.txt",
+        "sha3_512.txt",
+        "shake128.txt",
+        "shake256.txt",
+    ];
+    let vectors: Vec<KatVector> = files.iter().flat_map(|name| load_kat_file(name)).collect();
+    for w in [1, 2, 4, 8, 16, 32, 64] {
+        let width: Vec<&KatVector> = vectors.iter().filter(|v| v.r + v.c == 25 * w).collect();
+        assert!(!width.is_empty(), "no vectors for w = {}", w);
+        // an empty message, a message of several blocks and an output longer than the rate
+        assert!(width.iter().any(|v| v.message.is_empty()));
+        assert!(width
+            .iter()
+            .any(|v| get_blocks_amount(8 * v.message.len() as u64, v.r) > 2));
+        assert!(width.iter().any(|v| v.output_bits > v.r));
+    }
+}
+
+#[test]
+fn test_kat_keccak256() {
+    check_kat_file("keccak256.txt");
+}
+
+#[test]
+fn test_kat_keccak_small_widths() {
+    check_kat_file("keccak_small_widths.txt");
+}
+
+#[test]
+fn test_kat_sha3_224() {
+    check_kat_file("sha3_224.txt");
+}
+
+#[test]
+fn test_kat_sha3_256() {
+    check_kat_file("sha3_256.txt");
+}
+
+#[test]
+fn test_kat_sha3_384() {
+    check_kat_file("sha3_384.txt");
+}
+
+#[test]
+fn test_kat_sha3_512() {
+    check_kat_file("sha3_512.txt");
+}
+
+#[test]
+fn test_kat_shake128() {
+    check_kat_file("shake128.txt");
+}
+
+#[test]
+fn test_kat_shake256() {
+    check_kat_file("shake256.txt");
+}
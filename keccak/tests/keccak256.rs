@@ -0,0 +1,99 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+
+#[test]
+fn test_keccak256_known_vectors() {
+    let vectors: [(&[u8], &str); 2] = [
+        (
+            b"",
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        ),
+        (
+            b"abc",
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        ),
+    ];
+    for (message, digest) in vectors {
+        assert_eq!(to_hex(&keccak256(message)), digest);
+    }
+}
+
+#[test]
+fn test_keccak256_block_boundaries() {
+    // 135 bytes leave room for the single padding byte 0x81, 136 bytes need a second block
+    let a135 = [b'a'; 135];
+    let a136 = [b'a'; 136];
+    let counter: Vec<u8> = (0..200).collect();
+    let vectors: [(&[u8], &str); 3] = [
+        (
+            &a135,
+            "34367dc248bbd832f4e3e69dfaac2f92638bd0bbd18f2912ba4ef454919cf446",
+        ),
+        (
+            &a136,
+            "a6c4d403279fe3e0af03729caada8374b5ca54d8065329a3ebcaeb4b60aa386e",
+        ),
+        (
+            &counter,
+            "bfb0aa97863e797943cf7c33bb7e880bb4543f3d2703c0923c6901c2af57b890",
+        ),
+    ];
+    for (message, digest) in vectors {
+        let lac = keccak256_circuit(message);
+        assert_eq!(lac.validate(), Ok(()));
+        assert_eq!(to_hex(&keccak256(message)), digest);
+    }
+}
+
+#[test]
+fn test_keccak256_digest_check() {
+    let digest = keccak256(b"abc");
+    let mut lac = keccak256_digest_check_circuit(b"abc", &digest);
+    assert_eq!(lac.validate(), Ok(()));
+    lac.evaluate();
+    let outputs = lac.get_named_outputs().unwrap();
+    assert_eq!(outputs["digest_check"], [0]);
+    assert_eq!(bits_to_bytes(&outputs["digest"]), digest);
+
+    let mut wrong = digest;
+    wrong[0] ^= 0b101;
+    wrong[31] ^= 0x80;
+    let mut lac = keccak256_digest_check_circuit(b"abc", &wrong);
+    lac.evaluate();
+    assert_eq!(lac.get_named_outputs().unwrap()["digest_check"], [1]);
+}
+
+#[test]
+fn test_bytes_and_bits() {
+    assert_eq!(
+        bytes_to_bits(&[0x01, 0x80]),
+        [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]
+    );
+    let bytes: Vec<u8> = (0..=255).collect();
+    assert_eq!(bits_to_bytes(&bytes_to_bits(&bytes)), bytes);
+    assert_eq!(bits_to_bytes(&[1, 1, 0, 1]), [0b1011]);
+}
+
+#[test]
+fn test_keccak256_circuit_for_size() {
+    // one circuit evaluated for every 3 bytes message
+    let mut lac = keccak256_circuit_for_size(3);
+    assert_eq!(lac.validate(), Ok(()));
+    let messages: [&[u8]; 3] = [b"abc", b"xyz", &[0, 0, 0]];
+    for message in messages {
+        lac.assign_inputs(&get_message_assignment(message)).unwrap();
+        lac.evaluate();
+        assert_eq!(get_digest_bytes(&lac), keccak256(message));
+    }
+    assert!(lac.assign_inputs(&get_message_assignment(b"abcd")).is_err());
+
+    // the digest check circuit takes a new message and expected digest
+    let mut lac = keccak256_digest_check_circuit(&[0, 0, 0], &[0; 32]);
+    let mut assignment = get_message_assignment(b"xyz");
+    assignment.set("expected_digest", bytes_to_bits(&keccak256(b"xyz")));
+    lac.assign_inputs(&assignment).unwrap();
+    lac.evaluate();
+    assert_eq!(lac.get_named_outputs().unwrap()["digest_check"], [0]);
+}
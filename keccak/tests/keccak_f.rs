@@ -0,0 +1,236 @@
+use keccak::keccak256::{bits_to_bytes, bytes_to_bits};
+use keccak::keccak_f_circuit::*;
+use keccak::reference::*;
+use lac::utils::*;
+
+const WIDTHS: [u64; 7] = [1, 2, 4, 8, 16, 32, 64];
+
+/// splitmix64, the states are random but the same on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_state(&mut self, w: u64) -> KeccakState {
+        let mask = if w == 64 { u64::MAX } else { (1 << w) - 1 };
+        let mut state = [0; 25];
+        for lane in state.iter_mut() {
+            *lane = self.next() & mask;
+        }
+        state
+    }
+}
+
+fn get_state_ids(w: u64) -> Vec<u64> {
+    (2..(2 + 25 * w)).collect()
+}
+
+/// Circuit of the rounds `rounds` on a state held by the basic layer from id 2
+fn get_keccak_f_lac(w: u64, rounds: &[usize]) -> LAC<i64> {
+    let mut lac: LAC<i64> = LAC::new();
+    for (i, round) in rounds.iter().enumerate() {
+        let degree = 1 + i as u64 * ROUND_LAYERS;
+        lac.append_layers(get_keccak_f_round_layers(
+            get_state_ids(w),
+            degree,
+            w,
+            *round,
+        ));
+    }
+    lac
+}
+
+fn set_state(lac: &mut LAC<i64>, state: &KeccakState, w: u64) {
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    let bits = state_to_bits(state, w);
+    for (id, x) in [0, 1].iter().chain(&bits).enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id as u64, *x);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+}
+
+/// State held by the state ids of a trace layer, squaring the values when they are v in
+/// {-1, 0, 1} standing for the bit v^2
+fn read_state(layer: &std::collections::HashMap<u64, i64>, w: u64, squared: bool) -> KeccakState {
+    let bits: Vec<i64> = get_state_ids(w)
+        .iter()
+        .map(|id| {
+            if squared {
+                layer[id] * layer[id]
+            } else {
+                layer[id]
+            }
+        })
+        .collect();
+    assert!(bits.iter().all(|bit| *bit == 0 || *bit == 1));
+    bits_to_state(&bits, w)
+}
+
+/// Runs the circuit of get_keccak_f_lac(w, rounds) on state and compares it with the reference
+/// after every step, theta and the rho and pi steps are checked on the layers holding their
+/// result, chi on the lanes iota does not change. Returns the first step that differs.
+fn compare_with_reference(
+    lac: &mut LAC<i64>,
+    rounds: &[usize],
+    state: &KeccakState,
+    w: u64,
+) -> Result<(), String> {
+    set_state(lac, state, w);
+    let trace = lac.get_trace();
+    let mut state = *state;
+    for (i, round) in rounds.iter().enumerate() {
+        let steps = get_round_steps(&state, w, *round);
+        let degree = i * ROUND_LAYERS as usize;
+        let differs = |step: &str| Err(format!("round {} {} differs", i, step));
+
+        if read_state(&trace[degree + 4], w, true) != steps.theta {
+            return differs("theta");
+        }
+        let moved = read_state(&trace[degree + 5], w, false);
+        let mut unmoved = [0; 25];
+        for y in 0..5 {
+            for x in 0..5 {
+                unmoved[x + 5 * y] = moved[y + 5 * ((2 * x + 3 * y) % 5)];
+            }
+        }
+        if unmoved != steps.rho {
+            return differs("rho");
+        }
+        if moved != steps.pi {
+            return differs("pi");
+        }
+        let result = read_state(&trace[degree + 7], w, false);
+        if result[1..] != steps.chi[1..] {
+            return differs("chi");
+        }
+        if result != steps.iota {
+            return differs("iota");
+        }
+        state = steps.iota;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_reference_keccak_f_1600() {
+    // Keccak-f[1600] of the zero state, from the Keccak team's KeccakF-1600 test vectors
+    let state = keccak_f(&[0; 25], 64);
+    assert_eq!(state[0], 0xf1258f7940e1dde7);
+    assert_eq!(state[1], 0x84d5ccf933c0478a);
+    assert_eq!(state[24], 0xeaf1ff7b5ceca249);
+    let rc: Vec<u64> = (0..24).map(|round| get_round_constant(round, 64)).collect();
+    assert_eq!(rc[1], 0x8082);
+    assert_eq!(rc[23], 0x8000000080008008);
+    assert_eq!(get_rotation_offsets(64)[2], 62);
+}
+
+#[test]
+fn test_state_bits() {
+    let mut rng = Rng(7);
+    for w in WIDTHS {
+        let state = rng.next_state(w);
+        assert_eq!(bits_to_state(&state_to_bits(&state, w), w), state);
+    }
+}
+
+#[test]
+fn test_keccak_f_against_reference() {
+    let mut rng = Rng(0x4b656363616b);
+    for w in WIDTHS {
+        let rounds: Vec<usize> = (0..get_rounds_amount(w)).collect();
+        let mut lac = get_keccak_f_lac(w, &rounds);
+        set_state(&mut lac, &[0; 25], w);
+        assert_eq!(lac.validate(), Ok(()));
+        let states = [
+            [0; 25],
+            rng.next_state(w),
+            rng.next_state(w),
+            rng.next_state(w),
+        ];
+        for state in states {
+            assert_eq!(compare_with_reference(&mut lac, &rounds, &state, w), Ok(()));
+            let output = lac.get_trace().pop().unwrap();
+            assert_eq!(read_state(&output, w, false), keccak_f(&state, w));
+        }
+    }
+}
+
+#[test]
+fn test_keccak_f_layers_against_reference() {
+    // the template of the sponge is get_keccak_f_layers, with the rounds in one call
+    let w = 8;
+    let mut lac: LAC<i64> = LAC::new();
+    lac.append_layers(get_keccak_f_layers(get_state_ids(w), 1, w));
+    let rounds: Vec<usize> = (0..get_rounds_amount(w)).collect();
+    let state = Rng(1).next_state(w);
+    assert_eq!(compare_with_reference(&mut lac, &rounds, &state, w), Ok(()));
+}
+
+#[test]
+fn test_keccak_p_layers_against_reference() {
+    // Keccak-p of n rounds are the last n rounds of Keccak-f
+    let mut rng = Rng(0x6b656363616b70);
+    for w in [2, 8, 32] {
+        let rounds_amount = get_rounds_amount(w);
+        for n in [1, 5, rounds_amount] {
+            let mut lac: LAC<i64> = LAC::new();
+            lac.append_layers(get_keccak_p_layers(get_state_ids(w), 1, w, n));
+            let rounds: Vec<usize> = ((rounds_amount - n)..rounds_amount).collect();
+            let state = rng.next_state(w);
+            assert_eq!(compare_with_reference(&mut lac, &rounds, &state, w), Ok(()));
+            let output = lac.get_trace().pop().unwrap();
+            assert_eq!(read_state(&output, w, false), keccak_p(&state, w, n));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "Keccak-p needs between 1 and 14 rounds for w = 2, got 15")]
+fn test_keccak_p_too_many_rounds() {
+    get_keccak_p_layers(get_state_ids(2), 1, 2, 15);
+}
+
+#[test]
+#[should_panic(expected = "Keccak-p needs between 1 and 14 rounds for w = 2, got 15")]
+fn test_reference_keccak_p_too_many_rounds() {
+    keccak_p(&[0; 25], 2, 15);
+}
+
+#[test]
+fn test_reference_finds_wrong_round() {
+    // round 3 built with the constant of round 4 differs from the reference at its iota step
+    let w = 16;
+    let mut lac = get_keccak_f_lac(w, &[0, 1, 2, 4]);
+    let state = Rng(3).next_state(w);
+    assert_eq!(
+        compare_with_reference(&mut lac, &[0, 1, 2, 3], &state, w),
+        Err("round 3 iota differs".to_string())
+    );
+}
+
+#[test]
+fn test_reference_sponge() {
+    let to_hex = |bytes: &[u8]| -> String { bytes.iter().map(|b| format!("{:02x}", b)).collect() };
+    assert_eq!(
+        to_hex(&keccak256_reference(b"abc")),
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+    );
+    assert_eq!(
+        to_hex(&keccak256_reference(&[b'a'; 136])),
+        "a6c4d403279fe3e0af03729caada8374b5ca54d8065329a3ebcaeb4b60aa386e"
+    );
+    // SHA3-256 of "abc" with the suffix 01
+    let digest = keccak_sponge(&bytes_to_bits(b"abc"), &[0, 1], 1088, 512, 256);
+    assert_eq!(
+        to_hex(&bits_to_bytes(&digest)),
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+    );
+}
@@ -1,19 +1,226 @@
-use lac::utils::LAC;
+mod common;
+
+use common::*;
+use lac::assertion::ASSERTIONS;
+use lac::interface::Visibility;
+use lac::utils::*;
+use keccak::keccak256::*;
 use keccak::keccak_circuit::*;
+use keccak::keccak_f_circuit::*;
+use keccak::sha3::*;
 
 #[test]
 fn test_keccak_circuit() {
     let input: Vec<i64> = vec![1,0,0,1,0,1,1,0,1,1,0,0,0,1,0,1];
     let keccak: LAC<i64> = get_keccak_lac_circuit(input, 1152, 448, 256);
-    println!("layers: {:?}, gates: {:?}", keccak.get_layers_amount(), keccak.get_gates_amount())
+    assert_eq!(keccak.get_input_size(), 16);
+    assert_eq!(keccak.get_input_group("message").unwrap().get_ids().len(), 16);
+    assert_eq!(keccak.get_output_group("digest").unwrap().get_ids().len(), 256);
 }
 
 #[test]
+#[allow(non_snake_case)]
 fn test_keccak_circuit_OK() {
-    //"OK" = 0100111101001011
-    let input: Vec<i64> = vec![0,1,0,0,1,1,1,1,0,1,0,0,1,0,1,1];
+    let input: Vec<i64> = bytes_to_bits(b"OK");
     let mut keccak: LAC<i64> = get_keccak_lac_circuit(input, 1344, 256, 256);
-    println!("layers: {:?}, gates: {:?}", keccak.get_layers_amount(), keccak.get_gates_amount());
-    let result = keccak.evaluate();
-    println!("result: {:?}", result);
+    assert_eq!(keccak.validate(), Ok(()));
+    keccak.evaluate();
+    let digest = get_digest_bytes(&keccak);
+    assert_eq!(digest[..4], [0xf8, 0x1e, 0x01, 0xd8]);
+    assert_eq!(digest[28..], [0x4c, 0xd9, 0x34, 0xba]);
+}
+
+#[test]
+fn test_keccak_circuit_small_state() {
+    // Keccak-f[200], the 200 bits output needs 3 squeezes of 72 bits
+    let input: Vec<i64> = vec![1,0,0,1,0,1,1,0,1,1,0,0,0,1,0,1];
+    let mut keccak: LAC<i64> = get_keccak_lac_circuit(input, 72, 128, 16);
+    assert_eq!(keccak.validate(), Ok(()));
+    // the last layer holds the constants 0 and 1 followed by the digest
+    assert_eq!(keccak.evaluate(), vec![0,1,1,0,1,0,1,1,1,0,0,1,1,1,1,1,1,1]);
+
+    let mut keccak: LAC<i64> = get_keccak_lac_circuit(bytes_to_bits(b"OK"), 72, 128, 200);
+    assert_eq!(keccak.validate(), Ok(()));
+    keccak.evaluate();
+    let digest = get_digest_bytes(&keccak);
+    assert_eq!(digest.len(), 25);
+    assert_eq!(digest[..4], [0xff, 0xcf, 0x9a, 0x0a]);
+    assert_eq!(digest[21..], [0xaf, 0x6c, 0xcb, 0x76]);
+}
+
+#[test]
+fn test_keccak_circuit_merged_with_deeper_circuit() {
+    // the Keccak circuit is padded with relays of its last layer, they read its constant 0
+    let input: Vec<i64> = vec![1,0,0,1,0,1,1,0,1,1,0,0,0,1,0,1];
+    let mut keccak: LAC<i64> = get_keccak_lac_circuit(input, 72, 128, 16);
+    let layers_amount = keccak.get_layers_amount() as u64;
+    let mut deep: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for (id, x) in [0, 1, 5].into_iter().enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id as u64, x);
+        basic_layer.append_value(value);
+    }
+    deep.set_basic_layer(basic_layer);
+    for degree in 1..=(layers_amount + 3) {
+        let mut layer: Layer<i64> = Layer::new();
+        layer.set_degree(degree);
+        layer.add_gate_0_and_1(degree);
+        layer.copy_gates_by_ids(vec![2]);
+        deep.append_layer(layer);
+    }
+    deep.declare_output("x", vec![2]).unwrap();
+
+    deep.merge_lac_remapped(keccak.clone()).unwrap();
+    assert_eq!(deep.validate(), Ok(()));
+    deep.evaluate();
+    keccak.evaluate();
+    let outputs = deep.get_named_outputs().unwrap();
+    assert_eq!(outputs["digest"], keccak.get_named_outputs().unwrap()["digest"]);
+    assert_eq!(outputs["digest"], vec![1,0,1,0,1,1,1,0,0,1,1,1,1,1,1,1]);
+    assert_eq!(outputs["x"], vec![5]);
+}
+
+#[test]
+fn test_keccak_circuit_boolean_message() {
+    let input: Vec<i64> = vec![1,0,0,1,0,1,1,0,1,1,0,0,0,1,0,1];
+    let mut keccak: LAC<i64> = get_keccak_lac_circuit(input, 1152, 448, 256);
+    let layers_amount = keccak.get_layers_amount();
+    let message_ids = keccak.get_input_group("message").unwrap().get_ids().to_vec();
+    let id = keccak.assert_boolean(message_ids).unwrap();
+    assert_eq!(keccak.get_layers_amount(), layers_amount);
+    assert_eq!(keccak.get_output_group(ASSERTIONS).unwrap().get_ids(), [id]);
+    assert_eq!(keccak.get_output_group("digest").unwrap().get_ids().len(), 256);
+}
+
+#[test]
+fn test_keccak_digest_check_circuit() {
+    let keccak: LAC<i64> = get_keccak_lac_circuit_for_size(16, 1152, 448, 256).unwrap();
+    let check: LAC<i64> = get_keccak_digest_check_circuit(16, 1152, 448, 256).unwrap();
+    assert_eq!(check.validate(), Ok(()));
+    // the check adds the XORs, 8 halving layers of the OR reduction and its output
+    assert_eq!(check.get_layers_amount(), keccak.get_layers_amount() + 10);
+    assert_eq!(check.get_input_group("message").unwrap().get_ids().len(), 16);
+    let expected_group = check.get_input_group("expected_digest").unwrap();
+    assert_eq!(expected_group.get_visibility(), Visibility::Public);
+    assert_eq!(expected_group.get_ids().len(), 256);
+    let check_ids = check.get_output_group("digest_check").unwrap().get_ids();
+    assert_eq!(check_ids.len(), 1);
+    assert!(check_ids[0] >= RESERVED_IDS);
+    assert_eq!(check.get_output_group("digest").unwrap().get_ids(), get_digest_ids(256));
+
+    assert_eq!(
+        get_keccak_digest_check_circuit(16, 150, 100, 256).err(),
+        Some(KeccakError::InvalidWidth { r: 150, c: 100 })
+    );
+    assert_eq!(
+        get_keccak_digest_check_circuit(16, 1152, 448, 0).err(),
+        Some(KeccakError::InvalidOutputLength)
+    );
+}
+
+#[test]
+fn test_digest_check_layers() {
+    // a "digest" moved to the ids 0..n by one layer, compared with carried expected bits
+    let cases: [(&[i64], &[i64], i64); 7] = [
+        (&[1, 0, 1, 1], &[1, 0, 1, 1], 0),
+        (&[1, 0, 1, 1], &[1, 1, 1, 1], 1),
+        (&[1, 0, 1, 1], &[1, 0, 1, 0], 1),
+        (&[1, 0, 1, 1], &[0, 1, 0, 0], 1),
+        (&[0, 1, 1], &[0, 1, 1], 0),
+        (&[0, 1, 1], &[0, 1, 0], 1),
+        (&[1], &[0], 1),
+    ];
+    for (digest, expected, check) in cases {
+        let n = digest.len() as u64;
+        let mut lac: LAC<i64> = LAC::new();
+        let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+        let ids = (0..(2 + n)).chain(10..(10 + n));
+        for (id, x) in ids.zip([0, 1].iter().chain(digest).chain(expected)) {
+            let mut value: Value<i64> = Value::new();
+            value.set_all(id, *x);
+            basic_layer.append_value(value);
+        }
+        lac.set_basic_layer(basic_layer);
+        let mut layer: Layer<i64> = Layer::new();
+        layer.set_degree(1);
+        layer.copy_gates_by_ids_set_out((2..(2 + n)).collect(), (0..n).collect());
+        lac.append_layer(layer);
+
+        let expected_ids: Vec<u64> = (10..(10 + n)).collect();
+        lac.add_layers(get_carried_layers(expected_ids.clone(), 9, 1)).unwrap();
+        lac.append_layers(get_digest_check_layers((0..n).collect(), expected_ids, 9, 0, 2));
+        assert_eq!(lac.validate(), Ok(()));
+        assert_eq!(lac.evaluate(), vec![check]);
+    }
+}
+
+#[test]
+fn test_keccak_f_zero_state() {
+    // Keccak-f[1600] of the zero state, lanes (0, 0) and (1, 0) of the Keccak team's values
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for id in 0..(2 + 1600) {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id, (id == 1) as i64);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    lac.append_layers(get_keccak_f_layers((2..(2 + 1600)).collect(), 1, 64));
+    assert_eq!(lac.get_layers_amount(), 24 * ROUND_LAYERS as usize);
+    assert_eq!(lac.validate(), Ok(()));
+    let state = lac.evaluate();
+    let lane = |x: usize| -> u64 {
+        (0..64).map(|z| (state[2 + 64 * x + z] as u64) << z).sum()
+    };
+    assert_eq!(lane(0), 0xf1258f7940e1dde7);
+    assert_eq!(lane(1), 0x84d5ccf933c0478a);
+}
+
+#[test]
+fn test_keccak_sponge_blocks_and_squeezes() {
+    // Keccak-f[100] with r = 40, 288 output bits need 8 squeezes
+    let (r, c, l) = (40, 60, 288);
+    let vectors = [
+        (20, 1, "fd756c5a6504ffeb76b0acd026bfe1c85b77d7be875118a363f0c6487a48534410b25767"),
+        (60, 2, "6dbb73dc469a5b9258e16e0bddb7d58147a7eccb403e2361baffc24b0378cb4f5ff2c68b"),
+        (170, 5, "d0a6e1303f7c8239e2756eb4cca600eed7953b133fcb295b2b928520e8e1dfaa559f9d3f"),
+    ];
+    let f_layers = ROUND_LAYERS as usize * 16;
+    for (input_size, blocks, expected) in vectors {
+        let input: Vec<i64> = (0..input_size).map(|i| (i * 7 + i / 3) % 2).collect();
+        assert_eq!(get_blocks_amount(input_size as u64, r), blocks);
+        let mut keccak: LAC<i64> = get_keccak_lac_circuit(input, r, c, l);
+        // first layer, an xor layer for every block but the first, keccak-f for every block
+        // and every squeeze but the last, a copy layer before every squeeze and the last layer
+        let squeezes = 8;
+        let layers = 1 + (blocks as usize - 1) + blocks as usize * f_layers
+            + (squeezes - 1) * (1 + f_layers) + 1;
+        assert_eq!(keccak.get_layers_amount(), layers);
+        assert_eq!(keccak.validate(), Ok(()));
+        keccak.evaluate();
+        assert_eq!(to_hex(&get_digest_bytes(&keccak)), expected);
+    }
+}
+
+#[test]
+fn test_keccak256_five_blocks() {
+    let message: Vec<u8> = (0..(4 * 136 + 10)).map(|i| ((i * 31 + 7) % 256) as u8).collect();
+    assert_eq!(get_blocks_amount(8 * message.len() as u64, 1088), 5);
+    assert_eq!(
+        to_hex(&keccak256(&message)),
+        "04c3dd636239552d434ca5da678187f91d071251d079257a7bbaf6f81789e782"
+    );
+}
+
+#[test]
+fn test_shake128_four_squeezes() {
+    // 5376 bits are 4 times the rate of 1344 bits
+    let mut shake: LAC<i64> = shake128_circuit(b"abc", 5376).unwrap();
+    assert_eq!(shake.validate(), Ok(()));
+    shake.evaluate();
+    let digest = to_hex(&get_digest_bytes(&shake));
+    assert_eq!(digest.len(), 1344);
+    assert_eq!(digest[..16], *"5881092dd818bf5c");
+    assert_eq!(digest[1328..], *"6f4c72a10d6ac4c0");
 }
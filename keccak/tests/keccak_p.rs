@@ -0,0 +1,57 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::keccak_circuit::*;
+use keccak::sha3::*;
+
+//TurboSHAKE128 and KangarooTwelve are sponges on Keccak-p[1600, 12], the last 12 rounds of
+//Keccak-f[1600], with the capacity 256. Their vectors are the ones of RFC 9861.
+
+#[test]
+fn test_turboshake128() {
+    // the domain byte 0x1f is the suffix 1111 followed by the first bit of the padding
+    let lac = get_keccak_p_lac_circuit(Vec::new(), SHAKE_SUFFIX.to_vec(), 1344, 256, 512, 12);
+    assert_eq!(
+        evaluate_digest(lac.unwrap()),
+        "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c\
+         3e8ccae2a4dae56c84a04c2385c03c15e8193bdf58737363321691c05462c8df"
+    );
+}
+
+#[test]
+fn test_kangaroo_twelve_empty_message() {
+    // the empty message is the single chunk 0x00, its length encoding, hashed by
+    // TurboSHAKE128 with the domain byte 0x07, the suffix 11
+    let lac = get_keccak_p_lac_circuit(bytes_to_bits(&[0]), vec![1, 1], 1344, 256, 256, 12);
+    assert_eq!(
+        evaluate_digest(lac.unwrap()),
+        "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+    );
+}
+
+#[test]
+fn test_keccak_p_all_rounds() {
+    // Keccak-p with the rounds of Keccak-f is Keccak-f
+    let lac = get_keccak_p_lac_circuit(bytes_to_bits(b"abc"), Vec::new(), 72, 128, 64, 18);
+    let expected = get_keccak_lac_circuit(bytes_to_bits(b"abc"), 72, 128, 64);
+    assert_eq!(evaluate_digest(lac.unwrap()), evaluate_digest(expected));
+}
+
+#[test]
+fn test_keccak_p_invalid_rounds() {
+    for rounds in [0, 25] {
+        let error = get_keccak_p_lac_circuit(Vec::new(), Vec::new(), 1088, 512, 256, rounds);
+        assert_eq!(
+            error.err(),
+            Some(KeccakError::InvalidRoundsAmount { rounds, w: 64 })
+        );
+    }
+    assert_eq!(
+        check_keccak_p_parameters(8, 17, 8, 13)
+            .unwrap_err()
+            .to_string(),
+        "rounds must be between 1 and 12 for w = 1, got 13"
+    );
+    assert_eq!(check_keccak_p_parameters(8, 17, 8, 12), Ok(()));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::merkle::*;
+use keccak::reference::keccak256_reference;
+use lac::assertion::ASSERTIONS;
+use lac::interface::*;
+use lac::utils::RESERVED_IDS;
+
+/// Leaves of 32 bytes, the leaf i is 7i, 7i + 1, ...
+fn get_leaves(amount: usize) -> Vec<[u8; 32]> {
+    (0..amount)
+        .map(|i| std::array::from_fn(|j| (7 * i + j) as u8))
+        .collect()
+}
+
+#[test]
+fn test_merkle_root_circuit() {
+    let leaves = get_leaves(4);
+    let mut lac = get_merkle_root_circuit(4);
+    assert_eq!(lac.validate(), Ok(()));
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("leaves", bytes_to_bits(&leaves.concat()));
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_eq!(bits_to_bytes(&outputs["root"]), merkle_root(&leaves));
+}
+
+#[test]
+fn test_merkle_root_reference() {
+    let leaves = get_leaves(2);
+    assert_eq!(
+        merkle_root(&leaves),
+        keccak256_reference(&[leaves[0], leaves[1]].concat())
+    );
+    assert_eq!(merkle_root(&leaves[..1]), leaves[0]);
+}
+
+#[test]
+fn test_merkle_path_circuit() {
+    // the paths of a left and of a right leaf of a tree of depth 2 reach the same root
+    let leaves = get_leaves(4);
+    let root = merkle_root(&leaves);
+    let mut lac = get_merkle_path_circuit(2);
+    assert_eq!(lac.validate(), Ok(()));
+    for index in [0, 3] {
+        let (siblings, directions) = get_merkle_path(&leaves, index);
+        let assignment = get_merkle_path_assignment(&leaves[index], &siblings, &directions);
+        let outputs = evaluate_assignment(&mut lac, &assignment);
+        assert_eq!(bits_to_bytes(&outputs["root"]), root, "leaf {}", index);
+        assert_eq!(outputs[ASSERTIONS], vec![0]);
+    }
+}
+
+#[test]
+fn test_merkle_path_check_circuit() {
+    let leaves = get_leaves(4);
+    let root = merkle_root(&leaves);
+    let mut lac = get_merkle_path_check_circuit(2);
+    assert_eq!(lac.validate(), Ok(()));
+    assert!(lac.get_output_group("root_check").unwrap().get_ids()[0] >= RESERVED_IDS);
+    let (siblings, directions) = get_merkle_path(&leaves, 2);
+    let mut assignment = get_merkle_path_assignment(&leaves[2], &siblings, &directions);
+    assignment.set("expected_root", bytes_to_bits(&root));
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_eq!(outputs["root_check"], vec![0]);
+    assert!(lac.get_failed_assertions().is_empty());
+
+    // a wrong direction orders the children the other way and reaches another root
+    let mut wrong = directions.clone();
+    wrong[0] ^= 1;
+    let mut assignment = get_merkle_path_assignment(&leaves[2], &siblings, &wrong);
+    assignment.set("expected_root", bytes_to_bits(&root));
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_ne!(outputs["root_check"], vec![0]);
+}
+
+#[test]
+#[should_panic(expected = "the tree needs a power of 2 of leaves from 2 on, got 3")]
+fn test_merkle_root_circuit_leaves_amount() {
+    get_merkle_root_circuit(3);
+}
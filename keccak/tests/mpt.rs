@@ -0,0 +1,231 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::mpt::*;
+use keccak::reference::keccak256_reference;
+use keccak::rlp::*;
+use lac::assertion::ASSERTIONS;
+use lac::utils::*;
+use std::collections::HashMap;
+use std::fs;
+
+//Proofs read from tests/mpt/proofs.txt, one block of lines Proof, Key, Root, Node for every node
+//from the root and Value per proof.
+
+#[derive(Debug, Clone)]
+struct Proof {
+    key: Vec<u8>,
+    root: [u8; 32],
+    nodes: Vec<Vec<u8>>,
+    value: Vec<u8>,
+}
+
+fn load_proofs() -> HashMap<String, Proof> {
+    let path = format!("{}/tests/mpt/proofs.txt", env!("CARGO_MANIFEST_DIR"));
+    let content = fs::read_to_string(&path).unwrap();
+    let mut proofs: HashMap<String, Proof> = HashMap::new();
+    let mut name = String::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=').unwrap();
+        let value = value.trim();
+        if key.trim() == "Proof" {
+            name = value.to_string();
+            proofs.insert(
+                name.clone(),
+                Proof {
+                    key: Vec::new(),
+                    root: [0; 32],
+                    nodes: Vec::new(),
+                    value: Vec::new(),
+                },
+            );
+            continue;
+        }
+        let proof = proofs.get_mut(&name).unwrap();
+        match key.trim() {
+            "Key" => proof.key = from_hex(value),
+            "Root" => proof.root = from_hex(value).try_into().unwrap(),
+            "Node" => proof.nodes.push(from_hex(value)),
+            "Value" => proof.value = from_hex(value),
+            other => panic!("unknown line {}", other),
+        }
+    }
+    proofs
+}
+
+/// Evaluates the proof and checks the value outputs and the assertions
+fn check_proof(lac: &mut LAC<i64>, proof: &Proof, max_blocks: u64, max_value_size: usize) {
+    let assignment = get_mpt_proof_assignment(
+        &proof.root,
+        &proof.key,
+        &proof.nodes,
+        max_blocks,
+        max_value_size,
+    )
+    .unwrap();
+    let outputs = evaluate_assignment(lac, &assignment);
+    let mut value: Vec<i64> = proof.value.iter().map(|byte| *byte as i64).collect();
+    value.resize(max_value_size, 0);
+    assert_eq!(outputs["value"], value);
+    assert_eq!(outputs["value_size"], vec![proof.value.len() as i64]);
+    assert_eq!(outputs[ASSERTIONS], vec![0, 0]);
+    assert!(lac.get_failed_assertions().is_empty());
+}
+
+#[test]
+fn test_native_proofs() {
+    let proofs = load_proofs();
+    assert_eq!(proofs.len(), 5);
+    for (name, proof) in proofs.iter() {
+        assert_eq!(
+            verify_mpt_proof(&proof.root, &proof.key, &proof.nodes),
+            Ok(proof.value.clone()),
+            "{}",
+            name
+        );
+    }
+
+    let proof = &proofs["account_extension"];
+    let mut nodes = proof.nodes.clone();
+    nodes[3][40] ^= 1;
+    assert_eq!(
+        verify_mpt_proof(&proof.root, &proof.key, &nodes),
+        Err(MptError::HashMismatch { index: 3 })
+    );
+    //without its leaf the proof ends at a branch
+    assert_eq!(
+        verify_mpt_proof(&proof.root, &proof.key, &proof.nodes[..3]),
+        Err(MptError::UnexpectedNode { index: 2 })
+    );
+    //the key of another account leaves the first branch for another child
+    let other = &proofs["account_leaf"];
+    assert_eq!(
+        verify_mpt_proof(&proof.root, &other.key, &proof.nodes),
+        Err(MptError::HashMismatch { index: 1 })
+    );
+    assert_eq!(
+        verify_mpt_proof(&proof.root, &proof.key, &[]),
+        Err(MptError::EmptyProof)
+    );
+}
+
+#[test]
+fn test_mpt_assignment_errors() {
+    let proofs = load_proofs();
+    let proof = &proofs["account_leaf"];
+    assert_eq!(
+        get_mpt_proof_assignment(&proof.root, &proof.key, &proof.nodes, 1, 80).err(),
+        Some(MptError::NodeTooLong {
+            index: 0,
+            size: 147,
+            max_size: 135
+        })
+    );
+    assert_eq!(
+        get_mpt_proof_assignment(&proof.root, &proof.key, &proof.nodes, 2, 60).err(),
+        Some(MptError::ValueTooLong {
+            size: proof.value.len(),
+            max_size: 60
+        })
+    );
+    assert_eq!(get_mpt_max_node_size(2), 271);
+
+    //the branches of the mainnet proof need 4 blocks
+    let proof = &proofs["account_mainnet"];
+    assert_eq!(
+        get_mpt_proof_assignment(&proof.root, &proof.key, &proof.nodes, 3, 80).err(),
+        Some(MptError::NodeTooLong {
+            index: 0,
+            size: 532,
+            max_size: 407
+        })
+    );
+    assert!(get_mpt_proof_assignment(&proof.root, &proof.key, &proof.nodes, 4, 80).is_ok());
+}
+
+#[test]
+fn test_mpt_inline_node() {
+    //a leaf of 4 bytes inlined as a child of a branch, and as the child of an extension on the
+    //path of the key
+    let key = [0; 20];
+    let key_hash = keccak256_reference(&key);
+    let inline = encode_rlp_list(&[encode_rlp_string(&[0x20]), encode_rlp_string(&[1])]);
+    let mut items = vec![encode_rlp_string(&[]); 17];
+    items[3] = inline.clone();
+    let branch = encode_rlp_list(&items);
+    let extension = encode_rlp_list(&[encode_rlp_string(&[0x00, key_hash[0]]), inline]);
+    for node in [branch, extension] {
+        let root = keccak256_reference(&node);
+        let nodes = vec![node.clone(), node];
+        assert_eq!(
+            get_mpt_proof_assignment(&root, &key, &nodes, 2, 32).err(),
+            Some(MptError::InlineNode { index: 0 })
+        );
+    }
+}
+
+#[test]
+fn test_mpt_account_proof_circuit() {
+    //branch, extension, branch and leaf
+    let proof = &load_proofs()["account_extension"];
+    let mut lac = get_mpt_proof_circuit(4, 2, 20, 80);
+    assert_eq!(lac.validate(), Ok(()));
+    check_proof(&mut lac, proof, 2, 80);
+}
+
+#[test]
+fn test_mpt_account_leaf_circuit() {
+    let proofs = load_proofs();
+    let proof = &proofs["account_leaf"];
+    let mut lac = get_mpt_proof_circuit(2, 2, 20, 80);
+    assert_eq!(lac.validate(), Ok(()));
+    check_proof(&mut lac, proof, 2, 80);
+
+    //the hints of a valid proof under another root or for another key
+    let assignment =
+        get_mpt_proof_assignment(&proof.root, &proof.key, &proof.nodes, 2, 80).unwrap();
+    let mut wrong_root = assignment.clone();
+    let mut root = proof.root;
+    root[31] ^= 1;
+    wrong_root.set("root", bytes_to_bits(&root));
+    let outputs = evaluate_assignment(&mut lac, &wrong_root);
+    assert_eq!(outputs[ASSERTIONS][0], 0);
+    assert_ne!(outputs[ASSERTIONS][1], 0);
+
+    let mut wrong_key = assignment.clone();
+    wrong_key.set("key", bytes_to_bits(&proofs["account_extension"].key));
+    let outputs = evaluate_assignment(&mut lac, &wrong_key);
+    assert_ne!(outputs[ASSERTIONS][1], 0);
+}
+
+#[test]
+fn test_mpt_storage_proof_circuit() {
+    let proofs = load_proofs();
+    let mut lac = get_mpt_proof_circuit(2, 1, 32, 32);
+    assert_eq!(lac.validate(), Ok(()));
+    //a value of a single byte and a value of 31 bytes
+    for name in ["storage_0", "storage_2"] {
+        check_proof(&mut lac, &proofs[name], 1, 32);
+    }
+}
+
+#[test]
+#[ignore = "needs ~18 GB"]
+fn test_mpt_mainnet_account_proof_circuit() {
+    //8 branches of up to 532 bytes and a leaf, 36 Keccak blocks that do not fit the memory of
+    //the CI, run it with --ignored
+    let proof = &load_proofs()["account_mainnet"];
+    let mut lac = get_mpt_proof_circuit(9, 4, 20, 80);
+    assert_eq!(lac.validate(), Ok(()));
+    check_proof(&mut lac, proof, 4, 80);
+}
+
+#[test]
+#[should_panic(expected = "the value size must be between 1 and 255, got 256")]
+fn test_mpt_proof_circuit_value_size() {
+    get_mpt_proof_circuit(2, 1, 32, 256);
+}
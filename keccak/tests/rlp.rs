@@ -0,0 +1,210 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::rlp::*;
+use lac::assertion::ASSERTIONS;
+
+/// A hash, a string whose payload crosses 55 bytes with it, an integer and a flag
+fn get_shape() -> RlpListShape {
+    RlpListShape::new(vec![
+        ("hash", RlpField::Fixed(32)),
+        ("name", RlpField::Bytes(40)),
+        ("nonce", RlpField::Integer(8)),
+        ("flag", RlpField::Bytes(1)),
+    ])
+}
+
+/// Values of the shape with the payload of the list short and long, empty fields and single
+/// bytes below and above 0x80
+fn get_values() -> Vec<Vec<Vec<u8>>> {
+    let hash = keccak256(b"rlp").to_vec();
+    vec![
+        vec![hash.clone(), b"dog".to_vec(), vec![], vec![]],
+        vec![hash.clone(), vec![], get_integer_bytes(15), vec![0x7f]],
+        vec![
+            hash.clone(),
+            vec![0x80],
+            get_integer_bytes(1024),
+            vec![0x80],
+        ],
+        vec![hash.clone(), vec![0xab; 40], vec![0xff; 8], vec![0x00]],
+        vec![
+            hash,
+            b"Lorem ipsum dolor sit".to_vec(),
+            get_integer_bytes(0x0102030405),
+            vec![1],
+        ],
+    ]
+}
+
+#[test]
+fn test_native_rlp() {
+    //the examples of the Ethereum documentation
+    let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+    let strings: [(&[u8], &str); 5] = [
+        (b"dog", "83646f67"),
+        (b"", "80"),
+        (&[0x0f], "0f"),
+        (&[0x04, 0x00], "820400"),
+        (lorem, "b8384c6f72656d20697073756d20646f6c6f722073697420616d65742c20636f6e7365637465747572206164697069736963696e6720656c6974"),
+    ];
+    for (bytes, hex) in strings {
+        assert_eq!(encode_rlp_string(bytes), from_hex(hex));
+    }
+    let cat_dog = encode_rlp_list(&[encode_rlp_string(b"cat"), encode_rlp_string(b"dog")]);
+    assert_eq!(cat_dog, from_hex("c88363617483646f67"));
+    assert_eq!(encode_rlp_list(&[]), from_hex("c0"));
+    assert_eq!(get_integer_bytes(0), Vec::<u8>::new());
+    assert_eq!(get_integer_bytes(1024), vec![0x04, 0x00]);
+
+    let items = decode_rlp_list(&cat_dog).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1].get_payload(&cat_dog), b"dog");
+    let errors = [
+        ("c28100", RlpError::NonCanonical { offset: 1 }),
+        ("c3b80100", RlpError::NonCanonical { offset: 1 }),
+        ("c483646f", RlpError::Truncated { offset: 0 }),
+        ("c0c0", RlpError::TrailingBytes { offset: 1 }),
+        ("83646f67", RlpError::NotAList { offset: 0 }),
+    ];
+    for (hex, error) in errors {
+        assert_eq!(decode_rlp_list(&from_hex(hex)), Err(error), "{}", hex);
+    }
+}
+
+#[test]
+fn test_rlp_shape() {
+    let shape = get_shape();
+    for values in get_values() {
+        let encoding = shape.encode(&values).unwrap();
+        let items: Vec<Vec<u8>> = values.iter().map(|v| encode_rlp_string(v)).collect();
+        assert_eq!(encoding, encode_rlp_list(&items));
+        assert_eq!(shape.decode(&encoding), Ok(values));
+    }
+    assert_eq!(shape.get_max_size(), 2 + 33 + 41 + 9 + 2);
+
+    let hash = keccak256(b"rlp").to_vec();
+    let mismatches = [
+        vec![hash[1..].to_vec(), vec![], vec![], vec![]],
+        vec![hash.clone(), vec![0; 41], vec![], vec![]],
+        vec![hash.clone(), vec![], vec![0, 1], vec![]],
+        vec![hash.clone(), vec![], vec![]],
+    ];
+    for (index, values) in [0, 1, 2, 3].into_iter().zip(mismatches) {
+        assert_eq!(
+            shape.encode(&values),
+            Err(RlpError::FieldMismatch { index })
+        );
+    }
+    //a list as a field and an integer with a leading zero
+    let nested = encode_rlp_list(&[
+        encode_rlp_string(&hash),
+        encode_rlp_list(&[]),
+        encode_rlp_string(&[]),
+        encode_rlp_string(&[]),
+    ]);
+    assert_eq!(
+        shape.decode(&nested),
+        Err(RlpError::FieldMismatch { index: 1 })
+    );
+    let leading_zero = encode_rlp_list(&[
+        encode_rlp_string(&hash),
+        encode_rlp_string(&[]),
+        encode_rlp_string(&[0, 1]),
+        encode_rlp_string(&[]),
+    ]);
+    assert_eq!(
+        shape.decode(&leading_zero),
+        Err(RlpError::FieldMismatch { index: 2 })
+    );
+}
+
+#[test]
+fn test_rlp_encoding_circuit() {
+    let shape = get_shape();
+    let mut lac = get_rlp_encoding_circuit(&shape);
+    assert_eq!(lac.validate(), Ok(()));
+    for values in get_values() {
+        let assignment = get_rlp_encoding_assignment(&shape, &values).unwrap();
+        let outputs = evaluate_assignment(&mut lac, &assignment);
+        let mut encoding = shape.encode(&values).unwrap();
+        assert_eq!(outputs["length"], vec![encoding.len() as i64]);
+        encoding.resize(shape.get_max_size(), 0);
+        assert_eq!(bits_to_bytes(&outputs["encoding"]), encoding);
+        assert_eq!(outputs[ASSERTIONS], vec![0]);
+    }
+
+    //a byte after the size of the field and a size selector that is not one-hot
+    let values = &get_values()[0];
+    let mut assignment = get_rlp_encoding_assignment(&shape, values).unwrap();
+    let mut name = bytes_to_bits(b"dogs");
+    name.resize(8 * 40, 0);
+    assignment.set("name", name);
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_ne!(outputs[ASSERTIONS], vec![0]);
+
+    let mut assignment = get_rlp_encoding_assignment(&shape, values).unwrap();
+    let mut size = vec![0; 41];
+    size[3] = 1;
+    size[4] = 1;
+    assignment.set("name_size", size);
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_ne!(outputs[ASSERTIONS], vec![0]);
+}
+
+#[test]
+fn test_rlp_decoding_circuit() {
+    let shape = get_shape();
+    let mut lac = get_rlp_decoding_circuit(&shape);
+    assert_eq!(lac.validate(), Ok(()));
+    for values in get_values() {
+        let encoding = shape.encode(&values).unwrap();
+        let assignment = get_rlp_decoding_assignment(&shape, &encoding).unwrap();
+        let outputs = evaluate_assignment(&mut lac, &assignment);
+        for ((name, field), value) in shape.get_fields().iter().zip(values.iter()) {
+            let mut bytes = value.clone();
+            bytes.resize(field.get_max_size(), 0);
+            assert_eq!(bits_to_bytes(&outputs[*name]), bytes);
+            if field.is_variable() {
+                let size_name = format!("{}_size", name);
+                assert_eq!(outputs[&size_name], vec![value.len() as i64]);
+            }
+        }
+        assert_eq!(outputs["length"], vec![encoding.len() as i64]);
+        assert_eq!(outputs[ASSERTIONS], vec![0]);
+    }
+
+    //the single byte 0x7f written with a header, with the hints of the canonical encoding
+    let values = &get_values()[1];
+    let encoding = shape.encode(values).unwrap();
+    let mut assignment = get_rlp_decoding_assignment(&shape, &encoding).unwrap();
+    let mut non_canonical = encoding.clone();
+    non_canonical[0] += 1;
+    non_canonical.insert(non_canonical.len() - 1, 0x81);
+    assert_eq!(
+        decode_rlp_list(&non_canonical),
+        Err(RlpError::NonCanonical { offset: 36 })
+    );
+    let mut bits = bytes_to_bits(&non_canonical);
+    bits.resize(8 * shape.get_max_size(), 0);
+    assignment.set("encoding", bits);
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_ne!(outputs[ASSERTIONS], vec![0]);
+
+    //a byte changed in the hash
+    let mut assignment = get_rlp_decoding_assignment(&shape, &encoding).unwrap();
+    let mut tampered = encoding.clone();
+    tampered[5] ^= 0x10;
+    let mut bits = bytes_to_bits(&tampered);
+    bits.resize(8 * shape.get_max_size(), 0);
+    assignment.set("encoding", bits);
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_ne!(outputs[ASSERTIONS], vec![0]);
+}
+
+#[test]
+#[should_panic(expected = "the field name needs a size between 1 and 55, got 56")]
+fn test_rlp_shape_field_size() {
+    RlpListShape::new(vec![("name", RlpField::Bytes(56))]);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::*;
+use keccak::keccak_circuit::*;
+use keccak::sha3::*;
+
+#[test]
+fn test_sha3_abc() {
+    assert_eq!(
+        evaluate_digest(sha3_224_circuit(b"abc")),
+        "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf"
+    );
+    assert_eq!(
+        evaluate_digest(sha3_256_circuit(b"abc")),
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+    );
+    assert_eq!(
+        evaluate_digest(sha3_384_circuit(b"abc")),
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2\
+         98d88cea927ac7f539f1edf228376d25"
+    );
+    assert_eq!(
+        evaluate_digest(sha3_512_circuit(b"abc")),
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+         10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+    );
+}
+
+#[test]
+fn test_sha3_256_1600_bits() {
+    // NIST example of a 1600 bits message of 0xa3 bytes, absorbed in 2 blocks
+    let message = [0xa3; 200];
+    assert_eq!(
+        evaluate_digest(sha3_256_circuit(&message)),
+        "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787"
+    );
+}
+
+#[test]
+fn test_shake() {
+    assert_eq!(
+        evaluate_digest(shake128_circuit(b"", 256).unwrap()),
+        "7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26"
+    );
+    assert_eq!(
+        evaluate_digest(shake256_circuit(b"abc", 512).unwrap()),
+        "483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739\
+         d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"
+    );
+
+    // more output bits than the rate needs a second squeeze
+    let output = evaluate_digest(shake128_circuit(&[0xa3; 200], 1600).unwrap());
+    assert_eq!(output.len(), 400);
+    assert_eq!(&output[..8], "131ab8d2");
+    assert_eq!(&output[392..], "85e0493f");
+}
+
+#[test]
+fn test_invalid_parameters() {
+    assert_eq!(
+        check_keccak_parameters(150, 100, 16),
+        Err(KeccakError::InvalidWidth { r: 150, c: 100 })
+    );
+    assert_eq!(
+        check_keccak_parameters(3200, 0, 16),
+        Err(KeccakError::InvalidWidth { r: 3200, c: 0 })
+    );
+    assert_eq!(
+        check_keccak_parameters(1600, 0, 256),
+        Err(KeccakError::InvalidRate { r: 1600, c: 0 })
+    );
+    assert_eq!(
+        check_keccak_parameters(1088, 512, 0),
+        Err(KeccakError::InvalidOutputLength)
+    );
+    assert_eq!(check_keccak_parameters(72, 128, 16), Ok(()));
+
+    let error = get_keccak_lac_circuit_with_suffix(vec![1, 0], SHA3_SUFFIX.to_vec(), 150, 100, 16);
+    assert!(error
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with("rate + capacity must be 25 * w"));
+    assert!(shake128_circuit(b"abc", 0).is_err());
+}
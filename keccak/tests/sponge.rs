@@ -0,0 +1,136 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::keccak_circuit::*;
+use keccak::sha3::*;
+use keccak::sponge::*;
+use lac::utils::*;
+use lac::xor::*;
+
+/// Values of the wires `ids` after evaluating lac
+fn evaluate_wires(lac: &mut LAC<i64>, ids: &[u64]) -> Vec<i64> {
+    assert_eq!(lac.validate(), Ok(()));
+    let output = lac.get_trace().pop().unwrap();
+    ids.iter().map(|id| output[id]).collect()
+}
+
+fn get_message_lac(message: &[i64]) -> (LAC<i64>, Vec<u64>) {
+    let mut lac: LAC<i64> = LAC::new();
+    lac.set_basic_layer(get_keccak_basic_layer(message.to_vec()));
+    let ids = (2..(2 + message.len() as u64)).collect();
+    (lac, ids)
+}
+
+#[test]
+fn test_sponge_blocks_and_squeezes() {
+    // the vectors of the sponge circuit with r = 40 and 1, 2 and 5 blocks, absorbed and
+    // squeezed in pieces that do not follow the blocks
+    let vectors = [
+        (
+            20,
+            "fd756c5a6504ffeb76b0acd026bfe1c85b77d7be875118a363f0c6487a48534410b25767",
+        ),
+        (
+            60,
+            "6dbb73dc469a5b9258e16e0bddb7d58147a7eccb403e2361baffc24b0378cb4f5ff2c68b",
+        ),
+        (
+            170,
+            "d0a6e1303f7c8239e2756eb4cca600eed7953b133fcb295b2b928520e8e1dfaa559f9d3f",
+        ),
+    ];
+    for (input_size, expected) in vectors {
+        let message: Vec<i64> = (0..input_size).map(|i| (i * 7 + i / 3) % 2).collect();
+        let (mut lac, ids) = get_message_lac(&message);
+        let mut sponge = KeccakSpongeGadget::new(40, 60).unwrap();
+        sponge.absorb(&mut lac, &ids[..7]);
+        sponge.absorb(&mut lac, &[]);
+        sponge.absorb(&mut lac, &ids[7..]);
+        let mut out_ids = sponge.squeeze(&mut lac, 100);
+        out_ids.append(&mut sponge.squeeze(&mut lac, 188));
+        let digest = bits_to_bytes(&evaluate_wires(&mut lac, &out_ids));
+        assert_eq!(to_hex(&digest), expected);
+    }
+}
+
+#[test]
+fn test_sponge_in_larger_circuit() {
+    // absorbs the output of an xor layer followed by wires of the basic layer, the wires of
+    // the caller are still readable after the sponge
+    let (a, b): (Vec<i64>, Vec<i64>) = (0..50).map(|i| (i % 2, (i / 3) % 2)).unzip();
+    let (mut lac, ids) = get_message_lac(&[a.clone(), b.clone()].concat());
+    let (a_ids, b_ids) = (ids[..50].to_vec(), ids[50..].to_vec());
+    let xor_ids: Vec<u64> = (200..250).collect();
+    let mut layer = get_xor_bitstring_as_layer(a_ids.clone(), b_ids, xor_ids.clone(), 1);
+    layer.add_gate_0_and_1(1);
+    layer.copy_gates_by_ids(a_ids.clone());
+    lac.append_layer(layer);
+
+    let mut sponge = KeccakSpongeGadget::new(72, 128).unwrap();
+    sponge.absorb(&mut lac, &xor_ids);
+    sponge.absorb(&mut lac, &a_ids);
+    let out_ids = sponge.squeeze(&mut lac, 100);
+    let last_ids = lac.get_last_layer_ids();
+    assert!(xor_ids.iter().chain(&a_ids).all(|id| last_ids.contains(id)));
+
+    let xor: Vec<i64> = a.iter().zip(&b).map(|(x, y)| x ^ y).collect();
+    let mut expected = get_keccak_lac_circuit([xor.clone(), a.clone()].concat(), 72, 128, 100);
+    assert_eq!(expected.validate(), Ok(()));
+    expected.evaluate();
+    let expected_digest = &expected.get_named_outputs().unwrap()["digest"];
+
+    let wires = evaluate_wires(&mut lac, &[out_ids, xor_ids, a_ids].concat());
+    assert_eq!(wires[..100], expected_digest[..]);
+    assert_eq!(wires[100..150], xor[..]);
+    assert_eq!(wires[150..], a[..]);
+}
+
+#[test]
+fn test_sponge_sha3_256() {
+    let (mut lac, ids) = get_message_lac(&bytes_to_bits(b"abc"));
+    let mut sponge = KeccakSpongeGadget::new(1088, 512).unwrap();
+    sponge.set_suffix(SHA3_SUFFIX.to_vec());
+    sponge.absorb(&mut lac, &ids);
+    let out_ids = sponge.squeeze(&mut lac, 256);
+    assert_eq!(
+        to_hex(&bits_to_bytes(&evaluate_wires(&mut lac, &out_ids))),
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+    );
+}
+
+#[test]
+fn test_sponge_turboshake128() {
+    let (mut lac, _) = get_message_lac(&[]);
+    let mut sponge = KeccakSpongeGadget::new(1344, 256).unwrap();
+    sponge.set_suffix(SHAKE_SUFFIX.to_vec());
+    sponge.set_rounds(12).unwrap();
+    let out_ids = sponge.squeeze(&mut lac, 256);
+    assert_eq!(
+        to_hex(&bits_to_bytes(&evaluate_wires(&mut lac, &out_ids))),
+        "1e415f1c5983aff2169217277d17bb538cd945a397ddec541f1ce41af2c1b74c"
+    );
+}
+
+#[test]
+fn test_sponge_invalid_parameters() {
+    assert_eq!(
+        KeccakSpongeGadget::new(1000, 500).err(),
+        Some(KeccakError::InvalidWidth { r: 1000, c: 500 })
+    );
+    let mut sponge = KeccakSpongeGadget::new(8, 17).unwrap();
+    assert_eq!(
+        sponge.set_rounds(13),
+        Err(KeccakError::InvalidRoundsAmount { rounds: 13, w: 1 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "can not absorb after squeezing")]
+fn test_sponge_absorb_after_squeeze() {
+    let (mut lac, ids) = get_message_lac(&[1, 0, 1]);
+    let mut sponge = KeccakSpongeGadget::new(8, 17).unwrap();
+    sponge.absorb(&mut lac, &ids[..2]);
+    sponge.squeeze(&mut lac, 8);
+    sponge.absorb(&mut lac, &ids[2..]);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use common::*;
+use keccak::keccak256::*;
+use keccak::keccak_circuit::*;
+use keccak::sha3::*;
+use keccak::variable_length::*;
+use lac::assertion::ASSERTIONS;
+
+/// Digest of the fixed length circuit for the message
+fn get_expected_digest(message: &[i64], suffix: &[i64], r: u64, c: u64, l: u64) -> Vec<i64> {
+    let mut lac =
+        get_keccak_lac_circuit_with_suffix(message.to_vec(), suffix.to_vec(), r, c, l).unwrap();
+    lac.evaluate();
+    lac.get_named_outputs().unwrap()["digest"].clone()
+}
+
+#[test]
+fn test_variable_length_lengths() {
+    // one circuit of 3 blocks of r = 40 for lengths around the block boundaries, with the
+    // suffix of SHA3 the maximum length is 116
+    let (r, c, l) = (40, 60, 64);
+    let mut lac = get_keccak_variable_length_circuit(3, SHA3_SUFFIX.to_vec(), r, c, l).unwrap();
+    assert_eq!(lac.validate(), Ok(()));
+    assert_eq!(get_variable_length_max_size(3, 2, r), Some(116));
+    let shape = (lac.get_layers_amount(), lac.get_gates_amount());
+    for length in [0, 1, 35, 36, 37, 39, 40, 75, 77, 116] {
+        let message: Vec<i64> = (0..length).map(|i| (i * 5 + i / 7) % 2).collect();
+        let assignment = get_variable_length_assignment(&message, 3, 2, r);
+        let outputs = evaluate_assignment(&mut lac, &assignment);
+        let expected = get_expected_digest(&message, &SHA3_SUFFIX, r, c, l);
+        assert_eq!(outputs["digest"], expected, "length {}", length);
+        assert_eq!(lac.get_failed_assertions(), Vec::<u64>::new());
+        assert_eq!((lac.get_layers_amount(), lac.get_gates_amount()), shape);
+    }
+}
+
+#[test]
+fn test_variable_length_ignores_bits_after_length() {
+    let (r, c, l) = (72, 128, 40);
+    let mut lac = get_keccak_variable_length_circuit(2, Vec::new(), r, c, l).unwrap();
+    let message: Vec<i64> = (0..50).map(|i| i % 3 % 2).collect();
+    let mut assignment = get_variable_length_assignment(&message, 2, 0, r);
+    let mut dirty = assignment.get("message").unwrap().to_vec();
+    for bit in dirty[50..].iter_mut() {
+        *bit = 1;
+    }
+    assignment.set("message", dirty);
+    let outputs = evaluate_assignment(&mut lac, &assignment);
+    assert_eq!(outputs["digest"], get_expected_digest(&message, &[], r, c, l));
+    assert_eq!(outputs[ASSERTIONS], vec![0, 0, 0]);
+}
+
+#[test]
+fn test_variable_length_invalid_length() {
+    let r = 40;
+    let mut lac = get_keccak_variable_length_circuit(2, Vec::new(), r, 60, 8).unwrap();
+    let message = vec![1; 10];
+    let assignment = get_variable_length_assignment(&message, 2, 0, r);
+
+    // a length that does not match the mask
+    let mut wrong_length = assignment.clone();
+    wrong_length.set("length", vec![11]);
+    // a mask with a 1 after a 0
+    let mut holed_mask = assignment.clone();
+    let mut mask = assignment.get("length_mask").unwrap().to_vec();
+    mask[4] = 0;
+    mask[10] = 1;
+    holed_mask.set("length_mask", mask);
+
+    evaluate_assignment(&mut lac, &assignment);
+    assert!(lac.get_failed_assertions().is_empty());
+    for invalid in [wrong_length, holed_mask] {
+        evaluate_assignment(&mut lac, &invalid);
+        assert!(!lac.get_failed_assertions().is_empty());
+    }
+}
+
+#[test]
+fn test_variable_length_keccak256() {
+    let mut lac = get_keccak_variable_length_circuit(
+        2,
+        Vec::new(),
+        KECCAK256_RATE,
+        KECCAK256_CAPACITY,
+        KECCAK256_OUTPUT_BITS,
+    )
+    .unwrap();
+    assert_eq!(lac.validate(), Ok(()));
+    for message in [&b"abc"[..], &[b'a'; 136][..]] {
+        let assignment = get_variable_length_assignment(&bytes_to_bits(message), 2, 0, 1088);
+        let outputs = evaluate_assignment(&mut lac, &assignment);
+        assert_eq!(bits_to_bytes(&outputs["digest"]), keccak256(message));
+    }
+}
+
+#[test]
+fn test_variable_length_invalid_parameters() {
+    assert_eq!(
+        get_keccak_variable_length_circuit(1, SHAKE_SUFFIX.to_vec(), 5, 20, 8).err(),
+        Some(KeccakError::InvalidBlocksAmount { max_blocks: 1, r: 5 })
+    );
+    assert_eq!(
+        get_keccak_variable_length_circuit(0, Vec::new(), 8, 17, 8).err(),
+        Some(KeccakError::InvalidBlocksAmount { max_blocks: 0, r: 8 })
+    );
+}
@@ -0,0 +1,129 @@
+use crate::majority::*;
+use crate::utils::*;
+use crate::xor::*;
+
+//Half adder of the bits x0 and x1, with the sum XOR(x0,x1) = (x0-x1)^2 of get_xor_as_layer and
+//carry = x0*x1
+//Uses 1 layer and 2 gates:
+//  layer1:    g_sum=(x0-x1)*(x0-x1)    g_carry=x0*x1
+
+pub fn get_half_adder_as_layer(in_ids: Vec<u64>, out_ids: Vec<u64>, degree: u64) -> Layer<i64> {
+    let mut layer = get_xor_as_layer(in_ids.clone(), out_ids[0], degree);
+
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(
+        Some(degree),
+        Some(out_ids[1]),
+        Some([in_ids[0], in_ids[1]]),
+        None,
+        None,
+    );
+    layer.append_gate(gate);
+
+    layer
+}
+
+///Half adder for bit_string, uses 1 layer
+pub fn get_half_adder_bitstring_as_layer(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    sum_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let size = in_ids0.len();
+    let mut layer: Layer<i64> = Layer::new();
+    for i in 0..size {
+        let layer_half_adder_bit_i = get_half_adder_as_layer(
+            vec![in_ids0[i], in_ids1[i]],
+            vec![sum_ids[i], carry_ids[i]],
+            degree,
+        );
+        layer.merge_layer(layer_half_adder_bit_i);
+    }
+    layer
+}
+
+//Full adder of the bits x0, x1 and the carry in c, the sum uses
+//XOR(x0,x1,c) = ((x0+x1+c-2)*(c-x0-x1))^2 and the carry out is MAJ(x0,x1,c).
+//Both have degree 3 so it needs 2 layers, it uses 5 gates, reads the constant 1 from the
+//previous layer and the first layer overwrites the id of c with a temporary value:
+//  layer1:    g_sum=(x0+x1+c-2)*(c-x0-x1)    g_carry=x0*(x1+c)    g_c=x1*c
+//  layer2:    g_sum=g_sum*g_sum              g_carry=(g_carry-g_c)*(g_carry-g_c)
+
+#[allow(non_snake_case)]
+fn get_full_adder_first_layer(in_ids: Vec<u64>, out_ids: Vec<u64>, degree: u64) -> Layer<i64> {
+    let mut layer = get_majority_first_layer(in_ids.clone(), out_ids[1], degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([
+        vec![1, in_ids[0], in_ids[1], in_ids[2]],
+        vec![in_ids[0], in_ids[1], in_ids[2]],
+    ]);
+    let weights_R1CS = Some([vec![-2, 1, 1, 1], vec![-1, -1, 1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_ids[0]),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+#[allow(non_snake_case)]
+fn get_full_adder_second_layer(in_id: u64, out_ids: Vec<u64>, degree: u64) -> Layer<i64> {
+    let mut layer = get_majority_second_layer(in_id, out_ids[1], degree);
+
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(
+        Some(degree),
+        Some(out_ids[0]),
+        Some([out_ids[0], out_ids[0]]),
+        None,
+        None,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+/// Adds the bits `in_ids = [x0, x1, c]` into `out_ids = [sum, carry]`
+pub fn get_full_adder_as_layers(
+    in_ids: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let layer1 = get_full_adder_first_layer(in_ids.clone(), out_ids.clone(), degree);
+    let layer2 = get_full_adder_second_layer(in_ids[2], out_ids, degree + 1);
+    vec![layer1, layer2]
+}
+
+///Full adder for bit_string without carry propagation (carry-save), uses 2 layers
+pub fn get_full_adder_bitstring_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    in_ids2: Vec<u64>,
+    sum_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let mut layers = vec![Layer::new(), Layer::new()];
+    for i in 0..size {
+        let layers_full_adder_bit_i = get_full_adder_as_layers(
+            vec![in_ids0[i], in_ids1[i], in_ids2[i]],
+            vec![sum_ids[i], carry_ids[i]],
+            degree,
+        );
+        for (layer, layer_full_adder_bit_i) in layers.iter_mut().zip(layers_full_adder_bit_i) {
+            layer.merge_layer(layer_full_adder_bit_i);
+        }
+    }
+    layers
+}
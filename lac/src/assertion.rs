@@ -0,0 +1,148 @@
+use crate::utils::*;
+
+//LAC circuits have no constraints, an assertion is a wire that is 0 iff the condition holds.
+//The assertion wires of a circuit are the output group ASSERTIONS, a verifier checks that
+//they are all 0 (see LAC::get_failed_assertions).
+//
+//A fold adds several checks into one wire, sum r_i*c_i. With every r_i = 1 it is only sound
+//when the checks are never negative, as the booleanity check x*(x-1) on integers. Checks that
+//can be negative, as the equality check a-b, need random coefficients chosen by the verifier
+//after the inputs are fixed.
+
+/// Name of the output group holding the assertion wires
+pub const ASSERTIONS: &str = "assertions";
+
+///Booleanity check x_i*(x_i-1) of every value of in_ids, reads the constant 1 from the
+///previous layer, uses 1 layer
+pub fn get_boolean_assertion_as_layer(
+    in_ids: Vec<u64>,
+    check_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+    for (x, check_id) in in_ids.into_iter().zip(check_ids) {
+        layer.append_R1CS_gate(check_id, [vec![x], vec![x, 1]], [vec![1], vec![1, -1]]);
+    }
+    layer
+}
+
+///Equality check a_i-b_i of the values of in_ids0 and in_ids1, reads the constant 1 from the
+///previous layer, uses 1 layer
+pub fn get_equality_assertion_as_layer(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    check_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+    for i in 0..in_ids0.len() {
+        layer.append_R1CS_gate(
+            check_ids[i],
+            [vec![in_ids0[i], in_ids1[i]], vec![1]],
+            [vec![1, -1], vec![1]],
+        );
+    }
+    layer
+}
+
+///Folds the checks check_ids into out_id as sum coefficients_i*check_i, reads the constant 1
+///from the previous layer, uses 1 layer and 1 gate
+pub fn get_assertion_fold_as_layer(
+    check_ids: Vec<u64>,
+    coefficients: Vec<i64>,
+    out_id: u64,
+    degree: u64,
+) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+    layer.append_R1CS_gate(out_id, [check_ids, vec![1]], [coefficients, vec![1]]);
+    layer
+}
+
+impl LAC<i64> {
+    /// Asserts that the basic values `ids` are bits. Their booleanity checks are folded into
+    /// one wire that is relayed to the last layer and added to the output group `ASSERTIONS`.
+    /// Call it once the circuit is built, a circuit of 1 layer is padded to 2. Returns the id
+    /// of the assertion wire.
+    pub fn assert_boolean(&mut self, ids: Vec<u64>) -> Result<u64, MergeError> {
+        let first_id = self.get_max_id() + 1;
+        let check_ids: Vec<u64> = (first_id..(first_id + ids.len() as u64)).collect();
+        self.assert_checks(get_boolean_assertion_as_layer(ids, check_ids, 1))
+    }
+
+    /// Asserts that the checks computed from the basic values by the layer `checks` are 0, as
+    /// get_boolean_assertion_as_layer at degree 1 with ids above get_max_id. The checks are
+    /// folded with coefficients 1, so they must never be negative unless there is only one.
+    /// The assertion wire is relayed to the last layer and added to the output group
+    /// `ASSERTIONS`, it uses new ids and carries its own constant 1, computed as 1*1 from the
+    /// basic value 1, so the layers of the circuit need no constants. Call it once the circuit
+    /// is built, a circuit of 1 layer is padded to 2. Returns the id of the assertion wire.
+    pub fn assert_checks(&mut self, checks: Layer<i64>) -> Result<u64, MergeError> {
+        let check_ids = checks.get_gate_ids();
+        let first_id = self
+            .get_max_id()
+            .max(check_ids.iter().copied().max().unwrap_or(0))
+            + 1;
+        let (one_id, out_id) = (first_id, first_id + 1);
+        let layers_amount = self.get_layers_amount().max(2);
+
+        let mut layer = checks;
+        layer.set_gates_degree(1);
+        layer.append_mult_gate(one_id, [1, 1]);
+        let mut layers = vec![layer];
+
+        //the fold reads the carried 1 instead of the constant 1 of the previous layer
+        for degree in 2..=(layers_amount as u64) {
+            let mut layer: Layer<i64> = Layer::new();
+            layer.set_degree(degree);
+            if degree == 2 {
+                let weights = vec![1; check_ids.len()];
+                layer.append_R1CS_gate(
+                    out_id,
+                    [check_ids.clone(), vec![one_id]],
+                    [weights, vec![1]],
+                );
+            } else {
+                layer.append_mult_gate(out_id, [out_id, one_id]);
+            }
+            if degree < layers_amount as u64 {
+                layer.append_mult_gate(one_id, [one_id, one_id]);
+            }
+            layers.push(layer);
+        }
+        self.add_layers(layers)?;
+
+        match self
+            .outputs
+            .iter_mut()
+            .find(|group| group.get_name() == ASSERTIONS)
+        {
+            Some(group) => {
+                let mut group_ids = group.get_ids().to_vec();
+                group_ids.push(out_id);
+                group.set_ids(group_ids);
+            }
+            None => self.declare_output(ASSERTIONS, vec![out_id]).unwrap(),
+        }
+        Ok(out_id)
+    }
+
+    /// Ids of the assertion wires that are not 0 after the last evaluation
+    pub fn get_failed_assertions(&self) -> Vec<u64> {
+        let last_output = match self.layers.last() {
+            Some(layer) => &layer.output,
+            None => return Vec::new(),
+        };
+        match self.get_output_group(ASSERTIONS) {
+            Some(group) => group
+                .get_ids()
+                .iter()
+                .filter(|id| last_output.get(id) != Some(&0))
+                .copied()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::utils::*;
+use std::collections::HashMap;
+
+//Circuits written as wires instead of layers. Every wire is computed by one R1CS gate
+//(sum a_i*x_i)*(sum b_i*y_i) placed in the layer after the last of its inputs, and the wires a
+//gate reads are relayed, with their ids, up to the layer before it. Every layer of the builder
+//holds the constants 0 and 1, so the layer before the builder must hold them too.
+//
+//The builder also keeps checks, wires that are never negative and are 0 iff a condition
+//holds: booleanity checks x*(x-1) and the squares of the equality checks. fold_checks adds
+//them into one assertion wire, none of them can cancel another so the coefficients are 1.
+//
+//Selecting the byte at a position p that the circuit computes needs a one-hot selector s,
+//s_i = 1 iff i = p, given as a hint and checked by assert_one_hot. The byte is then
+//sum s_i*x_i, a product per position and a sum, 2 layers.
+
+/// Linear combination of wires as pairs of id and weight, the id 1 is the constant 1
+pub type Combination = Vec<(u64, i64)>;
+
+/// Combination plus the terms
+pub fn with(combination: &Combination, terms: &[(u64, i64)]) -> Combination {
+    [combination.clone(), terms.to_vec()].concat()
+}
+
+/// Combination with every weight times factor
+pub fn scale(combination: &Combination, factor: i64) -> Combination {
+    combination
+        .iter()
+        .map(|(id, weight)| (*id, factor * weight))
+        .collect()
+}
+
+/// One-hot selector of position among size positions, the hint read by assert_one_hot
+pub fn get_one_hot(position: usize, size: usize) -> Vec<i64> {
+    (0..size).map(|i| (i == position) as i64).collect()
+}
+
+pub struct CircuitBuilder {
+    degree: u64,
+    next_id: u64,
+    layers: Vec<Layer<i64>>,
+    //level of every wire computed by the builder, the layer before it is the level 0
+    levels: HashMap<u64, usize>,
+    //last level every wire is relayed to
+    relayed: HashMap<u64, usize>,
+    checks: Vec<u64>,
+}
+
+impl CircuitBuilder {
+    /// Builder whose first layer has degree `degree` and reads the layer before it, the
+    /// wires it computes take the ids from first_id on
+    pub fn new(degree: u64, first_id: u64) -> Self {
+        assert!(
+            first_id >= RESERVED_IDS,
+            "the ids below {} are the constants",
+            RESERVED_IDS
+        );
+        CircuitBuilder {
+            degree,
+            next_id: first_id,
+            layers: Vec::new(),
+            levels: HashMap::new(),
+            relayed: HashMap::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn get_layers_amount(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Level of the layer computing the wire, 0 for the wires of the layer before the builder
+    pub fn get_level(&self, id: u64) -> usize {
+        *self.levels.get(&id).unwrap_or(&0)
+    }
+
+    /// Wire (sum a_i*x_i)*(sum b_i*y_i), in the layer after the last wire it reads
+    pub fn r1cs(&mut self, a: &[(u64, i64)], b: &[(u64, i64)]) -> u64 {
+        let level = a
+            .iter()
+            .chain(b.iter())
+            .map(|(id, _)| self.get_level(*id))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        for (id, _) in a.iter().chain(b.iter()) {
+            self.relay(*id, level - 1);
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let (ids0, weights0): (Vec<u64>, Vec<i64>) = a.iter().copied().unzip();
+        let (ids1, weights1): (Vec<u64>, Vec<i64>) = b.iter().copied().unzip();
+        self.get_layer_mut(level)
+            .append_R1CS_gate(id, [ids0, ids1], [weights0, weights1]);
+        self.levels.insert(id, level);
+        self.relayed.insert(id, level);
+        id
+    }
+
+    /// Wire sum a_i*x_i
+    pub fn linear(&mut self, a: &[(u64, i64)]) -> u64 {
+        self.r1cs(a, &[(1, 1)])
+    }
+
+    /// Wire x*y
+    pub fn mult(&mut self, x: u64, y: u64) -> u64 {
+        self.r1cs(&[(x, 1)], &[(y, 1)])
+    }
+
+    /// Relays the wire up to the layer of level `level`, the constants are in every layer
+    pub fn relay(&mut self, id: u64, level: usize) {
+        if id < RESERVED_IDS {
+            return;
+        }
+        let from = *self.relayed.get(&id).unwrap_or(&self.get_level(id));
+        for relay_level in (from + 1)..=level {
+            self.get_layer_mut(relay_level).copy_gates_by_ids(vec![id]);
+        }
+        if level > from {
+            self.relayed.insert(id, level);
+        }
+    }
+
+    /// Wire sum s_i*v_i of the one-hot selector s, the value v_i at its position
+    pub fn select(&mut self, selector: &[u64], values: &[Combination]) -> u64 {
+        assert_eq!(
+            selector.len(),
+            values.len(),
+            "the selector needs one bit per value"
+        );
+        let products: Combination = selector
+            .iter()
+            .zip(values)
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(s, value)| (self.r1cs(&[(*s, 1)], value), 1))
+            .collect();
+        self.linear(&products)
+    }
+
+    /// Wires sum s_i*v_(i+k) for k in 0..width, the width values from the position of the
+    /// one-hot selector s, the values after the last one are 0
+    pub fn select_window(
+        &mut self,
+        selector: &[u64],
+        values: &[Combination],
+        width: usize,
+    ) -> Vec<u64> {
+        (0..width)
+            .map(|k| {
+                let shifted: Vec<Combination> = (0..selector.len())
+                    .map(|i| values.get(i + k).cloned().unwrap_or_default())
+                    .collect();
+                self.select(selector, &shifted)
+            })
+            .collect()
+    }
+
+    /// Checks that the wire a is 0, with its square
+    pub fn assert_zero(&mut self, a: &[(u64, i64)]) {
+        if !a.is_empty() {
+            let check = self.r1cs(a, a);
+            self.checks.push(check);
+        }
+    }
+
+    /// Checks that the product of the wires a and b is 0, with its square
+    pub fn assert_product_zero(&mut self, a: &[(u64, i64)], b: &[(u64, i64)]) {
+        let product = self.r1cs(a, b);
+        self.assert_zero(&[(product, 1)]);
+    }
+
+    /// Checks that the wires are bits with x*(x-1), never negative on integers
+    pub fn assert_bits(&mut self, ids: &[u64]) {
+        for id in ids {
+            let check = self.r1cs(&[(*id, 1)], &[(*id, 1), (1, -1)]);
+            self.checks.push(check);
+        }
+    }
+
+    /// Checks that the selector is made of bits, exactly one of them 1, at the position index
+    pub fn assert_one_hot(&mut self, selector: &[u64], index: &[(u64, i64)]) {
+        self.assert_bits(selector);
+        let mut sum: Combination = selector.iter().map(|s| (*s, 1)).collect();
+        sum.push((1, -1));
+        self.assert_zero(&sum);
+        let mut position: Combination = selector
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, s)| (*s, i as i64))
+            .collect();
+        position.extend(index.iter().map(|(id, weight)| (*id, -weight)));
+        self.assert_zero(&position);
+    }
+
+    /// Checks that the mask is length bits 1 followed by 0s
+    pub fn assert_prefix_mask(&mut self, mask: &[u64], length: &[(u64, i64)]) {
+        self.assert_bits(mask);
+        //m_(i+1)*(1-m_i) is 0 iff it is not a 1 after a 0
+        for ids in mask.windows(2) {
+            self.assert_product_zero(&[(ids[1], 1)], &[(1, 1), (ids[0], -1)]);
+        }
+        let mut sum: Combination = mask.iter().map(|m| (*m, 1)).collect();
+        sum.extend(length.iter().map(|(id, weight)| (*id, -weight)));
+        self.assert_zero(&sum);
+    }
+
+    /// Wire sum of the checks made since the last fold, 0 iff all of them hold
+    pub fn fold_checks(&mut self) -> u64 {
+        let checks: Combination = self.checks.drain(..).map(|id| (id, 1)).collect();
+        if checks.is_empty() {
+            self.r1cs(&[(0, 1)], &[(1, 1)])
+        } else {
+            self.linear(&checks)
+        }
+    }
+
+    /// Layers of the builder, with the outputs relayed to the last one. Panics if checks were
+    /// not folded.
+    pub fn finish(mut self, outputs: &[u64]) -> Vec<Layer<i64>> {
+        assert!(
+            self.checks.is_empty(),
+            "the checks must be folded into an assertion wire"
+        );
+        let last = self.layers.len().max(1);
+        for id in outputs {
+            self.relay(*id, last);
+        }
+        self.get_layer_mut(last);
+        self.layers
+    }
+
+    fn get_layer_mut(&mut self, level: usize) -> &mut Layer<i64> {
+        while self.layers.len() < level {
+            let degree = self.degree + self.layers.len() as u64;
+            self.layers.push(Layer::new_with_constants(degree));
+        }
+        &mut self.layers[level - 1]
+    }
+}
@@ -0,0 +1,255 @@
+use crate::interface::*;
+use crate::utils::*;
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+impl LAC<i64> {
+    /// Whole circuit as JSON: basic layer, layers with their gates sorted by id, and the
+    /// declared input and output groups
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"basic_layer\":[");
+        let values = self.basic_layer.values.keys().sorted().map(|id| {
+            let value = self.basic_layer.values[id].value;
+            format!("{{\"id\":{},\"value\":{}}}", id, json_option(value))
+        });
+        json.push_str(&values.format(",").to_string());
+
+        json.push_str("],\"layers\":[");
+        let layers = self.layers.iter().map(|layer| {
+            let gates = layer.gates.keys().sorted().map(|id| {
+                let gate = layer.gates[id].borrow();
+                match gate.gate_type {
+                    GateType::Add | GateType::Mult => format!(
+                        "{{\"id\":{},\"type\":\"{}\",\"inputs\":{:?}}}",
+                        id,
+                        if gate.gate_type == GateType::Add {
+                            "add"
+                        } else {
+                            "mult"
+                        },
+                        gate.input_id.unwrap_or([0, 0])
+                    ),
+                    GateType::R1CS => format!(
+                        "{{\"id\":{},\"type\":\"r1cs\",\"inputs\":{:?},\"weights\":{:?}}}",
+                        id,
+                        gate.input_id_R1CS.clone().unwrap_or_default(),
+                        gate.R1CS_weights.clone().unwrap_or_default()
+                    ),
+                }
+            });
+            format!(
+                "{{\"degree\":{},\"gates\":[{}]}}",
+                json_option(layer.degree),
+                gates.format(",")
+            )
+        });
+        json.push_str(&layers.format(",").to_string());
+
+        json.push_str("],\"inputs\":[");
+        let inputs = self.inputs.iter().map(|group| {
+            format!(
+                "{{\"name\":{},\"visibility\":\"{}\",\"ids\":{:?}}}",
+                json_string(group.get_name()),
+                match group.get_visibility() {
+                    Visibility::Public => "public",
+                    Visibility::Private => "private",
+                },
+                group.get_ids()
+            )
+        });
+        json.push_str(&inputs.format(",").to_string());
+
+        json.push_str("],\"outputs\":[");
+        let outputs = self.outputs.iter().map(|group| {
+            format!(
+                "{{\"name\":{},\"ids\":{:?}}}",
+                json_string(group.get_name()),
+                group.get_ids()
+            )
+        });
+        json.push_str(&outputs.format(",").to_string());
+        json.push_str("]}");
+        json
+    }
+
+    /// Graphviz digraph with one node per basic value and gate, grouped by layer.
+    /// Node `L{d}_{id}` is the gate `id` of degree `d`, degree 0 is the basic layer.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph lac {\n  rankdir=BT;\n");
+        dot.push_str("  subgraph layer_0 {\n    rank=same;\n");
+        for id in self.basic_layer.values.keys().sorted() {
+            writeln!(dot, "    L0_{} [label=\"in {}\", shape=box];", id, id).unwrap();
+        }
+        dot.push_str("  }\n");
+
+        for (position, layer) in self.layers.iter().enumerate() {
+            let degree = position + 1;
+            writeln!(dot, "  subgraph layer_{} {{\n    rank=same;", degree).unwrap();
+            for id in layer.gates.keys().sorted() {
+                let label = match layer.gates[id].borrow().gate_type {
+                    GateType::Add => "+",
+                    GateType::Mult => "*",
+                    GateType::R1CS => "r1cs",
+                };
+                writeln!(dot, "    L{}_{} [label=\"{} {}\"];", degree, id, label, id).unwrap();
+            }
+            dot.push_str("  }\n");
+            for id in layer.gates.keys().sorted() {
+                let gate = layer.gates[id].borrow();
+                let input_ids: Vec<u64> = match gate.gate_type {
+                    GateType::Add | GateType::Mult => gate.input_id.unwrap_or([0, 0]).to_vec(),
+                    GateType::R1CS => gate.input_id_R1CS.clone().unwrap_or_default().concat(),
+                };
+                for input_id in input_ids {
+                    writeln!(dot, "  L{}_{} -> L{}_{};", degree - 1, input_id, degree, id).unwrap();
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Rank-1 constraint system with one constraint A * B = C per gate, as JSON.
+    /// Variable 0 is the constant one, followed by the output wires, the public inputs,
+    /// the private inputs and then every other wire. Basic values 0 and 1 that are not part
+    /// of an input group are treated as constants.
+    pub fn to_r1cs_json(&self) -> String {
+        let mut variables: HashMap<(usize, u64), usize> = HashMap::new();
+        let mut n_variables = 1;
+        let depth = self.layers.len();
+
+        let output_ids: Vec<u64> = self
+            .outputs
+            .iter()
+            .flat_map(|group| group.get_ids().iter().copied())
+            .collect();
+        let public_ids = self.get_input_ids(Visibility::Public);
+        let private_ids = self.get_input_ids(Visibility::Private);
+        for id in &output_ids {
+            variables.entry((depth, *id)).or_insert_with(|| {
+                n_variables += 1;
+                n_variables - 1
+            });
+        }
+        let n_outputs = n_variables - 1;
+        for id in public_ids.iter().chain(private_ids.iter()) {
+            variables.entry((0, *id)).or_insert_with(|| {
+                n_variables += 1;
+                n_variables - 1
+            });
+        }
+        let n_public_inputs = public_ids.len();
+        let n_private_inputs = private_ids.len();
+
+        let mut constants: HashMap<u64, i64> = HashMap::new();
+        for id in self.basic_layer.values.keys().sorted() {
+            if *id < RESERVED_IDS && !variables.contains_key(&(0, *id)) {
+                constants.insert(*id, self.basic_layer.values[id].value.unwrap_or(0));
+            } else {
+                variables.entry((0, *id)).or_insert_with(|| {
+                    n_variables += 1;
+                    n_variables - 1
+                });
+            }
+        }
+        for (position, layer) in self.layers.iter().enumerate() {
+            for id in layer.gates.keys().sorted() {
+                variables.entry((position + 1, *id)).or_insert_with(|| {
+                    n_variables += 1;
+                    n_variables - 1
+                });
+            }
+        }
+
+        let term = |position: usize, id: u64, weight: i64| -> (usize, i64) {
+            if position == 0 {
+                if let Some(constant) = constants.get(&id) {
+                    return (0, weight * constant);
+                }
+            }
+            (variables[&(position, id)], weight)
+        };
+        let mut constraints: Vec<String> = Vec::new();
+        for (position, layer) in self.layers.iter().enumerate() {
+            for id in layer.gates.keys().sorted() {
+                let gate = layer.gates[id].borrow();
+                let (a, b) = match gate.gate_type {
+                    GateType::Add => {
+                        let [in0, in1] = gate.input_id.unwrap();
+                        (
+                            vec![term(position, in0, 1), term(position, in1, 1)],
+                            vec![(0, 1)],
+                        )
+                    }
+                    GateType::Mult => {
+                        let [in0, in1] = gate.input_id.unwrap();
+                        (vec![term(position, in0, 1)], vec![term(position, in1, 1)])
+                    }
+                    GateType::R1CS => {
+                        let input_id = gate.input_id_R1CS.as_ref().unwrap();
+                        let weights = gate.R1CS_weights.as_ref().unwrap();
+                        let side = |i: usize| -> Vec<(usize, i64)> {
+                            input_id[i]
+                                .iter()
+                                .zip(&weights[i])
+                                .map(|(id, weight)| term(position, *id, *weight))
+                                .collect()
+                        };
+                        (side(0), side(1))
+                    }
+                };
+                let c = vec![(variables[&(position + 1, *id)], 1)];
+                constraints.push(format!(
+                    "[{},{},{}]",
+                    linear_combination_json(a),
+                    linear_combination_json(b),
+                    linear_combination_json(c)
+                ));
+            }
+        }
+
+        format!(
+            "{{\"n_variables\":{},\"n_outputs\":{},\"n_public_inputs\":{},\"n_private_inputs\":{},\"constraints\":[{}]}}",
+            n_variables,
+            n_outputs,
+            n_public_inputs,
+            n_private_inputs,
+            constraints.join(",")
+        )
+    }
+}
+
+/// Sums the weights of repeated variables and drops zero weights
+fn linear_combination_json(terms: Vec<(usize, i64)>) -> String {
+    let mut combination: BTreeMap<usize, i64> = BTreeMap::new();
+    for (variable, weight) in terms {
+        *combination.entry(variable).or_insert(0) += weight;
+    }
+    let terms = combination
+        .iter()
+        .filter(|(_, weight)| **weight != 0)
+        .map(|(variable, weight)| format!("\"{}\":{}", variable, weight));
+    format!("{{{}}}", terms.format(","))
+}
+
+fn json_option<T: std::fmt::Display>(x: Option<T>) -> String {
+    match x {
+        Some(x) => x.to_string(),
+        None => "null".to_string(),
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(res, "\\u{:04x}", c as u32).unwrap(),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
@@ -0,0 +1,338 @@
+use crate::adder::*;
+use crate::packing::*;
+use crate::utils::*;
+use crate::xnor::*;
+use std::collections::HashMap;
+
+//n-bit unsigned integers are bit_strings with the least significant bit first. The gadgets
+//overwrite the ids of their inputs with temporary values and every layer they return holds the
+//constants 0 and 1, so they can be chained. Costs for n = 32 include those constants:
+//  ripple-carry adder:       63 layers, 4189 gates, most of them relays
+//  carry-lookahead adder:     7 layers,  720 gates
+//  field adder with hints:    1 layer,    69 gates, plus 33 hint inputs
+//  subtraction:               7 layers,  721 gates
+//  less-than:                 7 layers,  172 gates
+//  equality:                  6 layers,   75 gates
+
+/// Number of levels of a prefix network over `size` bits
+fn get_levels_amount(size: usize) -> u32 {
+    let mut levels = 0;
+    while (1 << levels) < size {
+        levels += 1;
+    }
+    levels
+}
+
+//Ripple-carry adder, bit 0 goes through a half adder and every other bit through a full adder
+//fed by the carry of the previous bit, so it uses 2n-1 layers. The carry out of bit i is
+//written to carry_ids[i].
+
+///Adds the bit_strings in_ids0 and in_ids1 modulo 2^n, uses 2n-1 layers
+pub fn get_ripple_carry_adder_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+
+    let mut layer = Layer::new_with_constants(degree);
+    layer.merge_layer(get_half_adder_as_layer(
+        vec![in_ids0[0], in_ids1[0]],
+        vec![out_ids[0], carry_ids[0]],
+        degree,
+    ));
+    layer.copy_gates_by_ids([&in_ids0[1..], &in_ids1[1..]].concat());
+    let mut layers = vec![layer];
+
+    for i in 1..size {
+        let stage_degree = degree + 2 * i as u64 - 1;
+        let layers_full_adder = get_full_adder_as_layers(
+            vec![carry_ids[i - 1], in_ids1[i], in_ids0[i]],
+            vec![out_ids[i], carry_ids[i]],
+            stage_degree,
+        );
+        for (j, layer_full_adder) in layers_full_adder.into_iter().enumerate() {
+            let mut layer = Layer::new_with_constants(stage_degree + j as u64);
+            layer.merge_layer(layer_full_adder);
+            layer.copy_gates_by_ids([&in_ids0[(i + 1)..], &in_ids1[(i + 1)..]].concat());
+            layer.copy_gates_by_ids([&out_ids[..i], &carry_ids[..i]].concat());
+            layers.push(layer);
+        }
+    }
+    layers
+}
+
+//Carry-lookahead adder using a Kogge-Stone prefix network. Every bit starts with its generate
+//g = x0*x1 and propagate p = (x0-x1)^2, and the generate of a group of bits is kept as the sum
+//G+t of two wires, G in the id of x0 and t in the carry id, which lets a level combine the
+//group of bit i with the group of bit i-2^k in a single layer:
+//  t_i=P_i*(G_j+t_j)    G_i=G_i+t_i    P_i=P_i*P_j
+//This is correct because a group never generates and propagates at the same time. With
+//L = ceil(log2(n)) it uses L+2 layers, the last one adds G+t into the carries and computes
+//sum_i = (p_i-c_i)^2 with p_i kept in out_ids.
+
+#[allow(non_snake_case)]
+fn get_prefix_adder_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    subtract: bool,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let levels = get_levels_amount(size);
+    let (G, P, t) = (&in_ids0, &in_ids1, &carry_ids);
+
+    //subtraction adds the complement of x1 and a carry in of 1, which is a t for bit 0
+    let mut layer = Layer::new_with_constants(degree);
+    let mut has_t = vec![false; size];
+    for i in 0..size {
+        let (x0, x1) = (in_ids0[i], in_ids1[i]);
+        let mut propagate_ids = vec![out_ids[i]];
+        if i > 0 {
+            propagate_ids.push(P[i]);
+        }
+        if subtract {
+            layer.append_R1CS_gate(G[i], [vec![x0], vec![1, x1]], [vec![1], vec![1, -1]]);
+            if i == 0 {
+                propagate_ids.push(t[0]);
+                has_t[0] = true;
+            }
+            for id in propagate_ids {
+                layer.merge_layer(get_xnor_as_layer(vec![x0, x1], id, degree));
+            }
+        } else {
+            layer.append_mult_gate(G[i], [x0, x1]);
+            for id in propagate_ids {
+                let ids = vec![x0, x1];
+                layer.append_R1CS_gate(id, [ids.clone(), ids], [vec![1, -1], vec![1, -1]]);
+            }
+        }
+    }
+    let mut layers = vec![layer];
+
+    for k in 0..levels {
+        let span = 1 << k;
+        let mut layer = Layer::new_with_constants(degree + 1 + k as u64);
+        layer.copy_gates_by_ids(out_ids.clone());
+        let mut next_has_t = vec![false; size];
+        for i in 0..size {
+            if has_t[i] {
+                layer.append_add_gate(G[i], [G[i], t[i]]);
+            } else {
+                layer.copy_gates_by_ids(vec![G[i]]);
+            }
+            if i < span {
+                continue;
+            }
+            let j = i - span;
+            let mut ids = vec![G[j]];
+            if has_t[j] {
+                ids.push(t[j]);
+            }
+            let weights = vec![1; ids.len()];
+            layer.append_R1CS_gate(t[i], [vec![P[i]], ids], [vec![1], weights]);
+            next_has_t[i] = true;
+            if i >= 2 * span && k + 1 < levels {
+                layer.append_mult_gate(P[i], [P[i], P[j]]);
+            }
+        }
+        has_t = next_has_t;
+        layers.push(layer);
+    }
+
+    let mut layer = Layer::new_with_constants(degree + 1 + levels as u64);
+    for i in 0..size {
+        if has_t[i] {
+            layer.append_add_gate(t[i], [G[i], t[i]]);
+        } else {
+            layer.copy_gates_by_ids_set_out(vec![G[i]], vec![t[i]]);
+        }
+        let mut ids = vec![out_ids[i]];
+        if i > 0 {
+            ids.push(G[i - 1]);
+            if has_t[i - 1] {
+                ids.push(t[i - 1]);
+            }
+        } else if subtract {
+            ids.push(1);
+        }
+        let mut weights = vec![-1; ids.len()];
+        weights[0] = 1;
+        layer.append_R1CS_gate(out_ids[i], [ids.clone(), ids], [weights.clone(), weights]);
+    }
+    layers.push(layer);
+    layers
+}
+
+///Adds the bit_strings in_ids0 and in_ids1 modulo 2^n, the carry out of bit i is written to
+///carry_ids[i], uses ceil(log2(n))+2 layers
+pub fn get_carry_lookahead_adder_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    get_prefix_adder_layers(in_ids0, in_ids1, out_ids, carry_ids, false, degree)
+}
+
+///Subtracts the bit_string in_ids1 from in_ids0 modulo 2^n as in_ids0 + NOT(in_ids1) + 1,
+///carry_ids[n-1] is 1 iff in_ids0 >= in_ids1, uses ceil(log2(n))+2 layers
+pub fn get_subtraction_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    carry_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    get_prefix_adder_layers(in_ids0, in_ids1, out_ids, carry_ids, true, degree)
+}
+
+//Field adder: the words are added as integers, a = sum 2^i*x0_i + sum 2^i*x1_i, and a is
+//unpacked into n+1 bits given as hints (see get_unpack_as_layer and get_field_adder_hints).
+//The sum is hint_ids[..n], the carry out is hint_ids[n] and the n+2 check wires are 0 iff the
+//hints are correct. Uses 1 layer and 2n+3 gates.
+
+///Adds the bit_strings in_ids0 and in_ids1 with the hints hint_ids, uses 1 layer
+pub fn get_field_adder_as_layer(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    hint_ids: Vec<u64>,
+    check_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let mut ids: Vec<u64> = Vec::new();
+    let mut weights: Vec<i64> = Vec::new();
+    for i in 0..in_ids0.len() {
+        ids.extend([in_ids0[i], in_ids1[i]]);
+        weights.extend([1 << i, 1 << i]);
+    }
+    get_unpack_checks_layer(ids, weights, hint_ids, check_ids, degree)
+}
+
+///Hints of get_field_adder_as_layer: the n+1 bits of the sum of the bit_strings x0 and x1
+pub fn get_field_adder_hints(x0: &[i64], x1: &[i64]) -> Vec<i64> {
+    let sum: i64 = x0
+        .iter()
+        .zip(x1)
+        .enumerate()
+        .map(|(i, (a, b))| (a + b) << i)
+        .sum();
+    (0..=x0.len()).map(|i| (sum >> i) & 1).collect()
+}
+
+//Less-than computes the carry out of x1 + NOT(x0), which is 1 iff x0 < x1, with a prefix
+//tree that only keeps the group of the top bit. Groups are combined in pairs from the top as
+//in the carry-lookahead adder, the t of a group is kept in the id of the x0 bit of the group it
+//absorbed. Uses ceil(log2(n))+2 layers.
+
+///Writes 1 to out_id iff the bit_string in_ids0 is less than in_ids1, uses ceil(log2(n))+2 layers
+#[allow(non_snake_case)]
+pub fn get_less_than_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_id: u64,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let levels = get_levels_amount(size);
+    let (G, P) = (&in_ids0, &in_ids1);
+
+    let mut layer = Layer::new_with_constants(degree);
+    for i in 0..size {
+        let (x0, x1) = (in_ids0[i], in_ids1[i]);
+        layer.append_R1CS_gate(G[i], [vec![x1], vec![1, x0]], [vec![1], vec![1, -1]]);
+        layer.merge_layer(get_xnor_as_layer(vec![x0, x1], P[i], degree));
+    }
+    let mut layers = vec![layer];
+
+    //id holding the t of the group whose top bit is the key
+    let mut t: HashMap<usize, u64> = HashMap::new();
+    for k in 0..levels {
+        let span = 1 << k;
+        let mut layer = Layer::new_with_constants(degree + 1 + k as u64);
+        let mut next_t: HashMap<usize, u64> = HashMap::new();
+        for i in (0..size).rev().step_by(span) {
+            //the group of i is absorbed by the group of i+span
+            if (size - 1 - i) % (2 * span) == span {
+                continue;
+            }
+            match t.get(&i) {
+                Some(t_id) => layer.append_add_gate(G[i], [G[i], *t_id]),
+                None => layer.copy_gates_by_ids(vec![G[i]]),
+            }
+            if !(size - 1 - i).is_multiple_of(2 * span) {
+                continue;
+            }
+            if i < span {
+                layer.copy_gates_by_ids(vec![P[i]]);
+                continue;
+            }
+            let j = i - span;
+            let mut ids = vec![G[j]];
+            ids.extend(t.get(&j));
+            let weights = vec![1; ids.len()];
+            layer.append_R1CS_gate(G[j], [vec![P[i]], ids], [vec![1], weights]);
+            layer.append_mult_gate(P[i], [P[i], P[j]]);
+            next_t.insert(i, G[j]);
+        }
+        t = next_t;
+        layers.push(layer);
+    }
+
+    let mut layer = Layer::new_with_constants(degree + 1 + levels as u64);
+    match t.get(&(size - 1)) {
+        Some(t_id) => layer.append_add_gate(out_id, [G[size - 1], *t_id]),
+        None => layer.copy_gates_by_ids_set_out(vec![G[size - 1]], vec![out_id]),
+    }
+    layers.push(layer);
+    layers
+}
+
+//Equality multiplies the XNOR of every pair of bits with a product tree, the products are kept
+//in the ids of in_ids0. Uses ceil(log2(n))+1 layers.
+
+///Writes 1 to out_id iff the bit_strings in_ids0 and in_ids1 are equal, uses ceil(log2(n))+1
+///layers
+pub fn get_equality_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_id: u64,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let levels = get_levels_amount(size);
+    let mut ids = in_ids0.clone();
+    if levels == 0 {
+        ids[0] = out_id;
+    }
+
+    let mut layer = Layer::new_with_constants(degree);
+    for i in 0..size {
+        layer.merge_layer(get_xnor_as_layer(
+            vec![in_ids0[i], in_ids1[i]],
+            ids[i],
+            degree,
+        ));
+    }
+    let mut layers = vec![layer];
+
+    for k in 0..levels {
+        let span = 1 << k;
+        let mut layer = Layer::new_with_constants(degree + 1 + k as u64);
+        for i in (0..size).step_by(2 * span) {
+            let id = if k + 1 == levels { out_id } else { ids[i] };
+            if i + span < size {
+                layer.append_mult_gate(id, [ids[i], ids[i + span]]);
+            } else {
+                layer.copy_gates_by_ids_set_out(vec![ids[i]], vec![id]);
+            }
+        }
+        layers.push(layer);
+    }
+    layers
+}
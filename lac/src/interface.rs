@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+/// Named group of basic layer values, e.g. `message` or `expected_digest`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputGroup {
+    name: String,
+    visibility: Visibility,
+    ids: Vec<u64>,
+}
+
+impl InputGroup {
+    pub fn new(name: &str, visibility: Visibility, ids: Vec<u64>) -> Self {
+        InputGroup {
+            name: name.to_string(),
+            visibility,
+            ids,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_visibility(&self) -> Visibility {
+        self.visibility
+    }
+
+    pub fn get_ids(&self) -> &[u64] {
+        &self.ids
+    }
+
+    pub fn set_ids(&mut self, ids: Vec<u64>) {
+        self.ids = ids;
+    }
+}
+
+/// Named group of gates of the last layer, e.g. `digest`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputGroup {
+    name: String,
+    ids: Vec<u64>,
+}
+
+impl OutputGroup {
+    pub fn new(name: &str, ids: Vec<u64>) -> Self {
+        OutputGroup {
+            name: name.to_string(),
+            ids,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_ids(&self) -> &[u64] {
+        &self.ids
+    }
+
+    pub fn set_ids(&mut self, ids: Vec<u64>) {
+        self.ids = ids;
+    }
+}
+
+/// Values of the input groups of a circuit by group name, a witness for a circuit built
+/// once, see `LAC::assign_inputs`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Assignment<T> {
+    values: HashMap<String, Vec<T>>,
+}
+
+impl<T> Assignment<T> {
+    pub fn new() -> Self {
+        Assignment {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Sets the values of the input group `name`, replacing any previous ones
+    pub fn set(&mut self, name: &str, values: Vec<T>) {
+        self.values.insert(name.to_string(), values);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[T]> {
+        self.values.get(name).map(|values| values.as_slice())
+    }
+
+    /// Names of the assigned groups, sorted
+    pub fn get_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.values.keys().map(|name| name.as_str()).collect();
+        names.sort();
+        names
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InterfaceError {
+    /// An input or output group with this name is already declared
+    DuplicateName(String),
+    /// No input group with this name is declared
+    UnknownInput(String),
+    /// A declared input group got no values
+    MissingInput(String),
+    /// An input group got a different amount of values than it has ids
+    WrongLength {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    /// An output group refers to an id the last layer does not compute
+    MissingOutputWire { name: String, id: u64 },
+}
+
+impl fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterfaceError::DuplicateName(name) => write!(f, "group {} declared twice", name),
+            InterfaceError::UnknownInput(name) => write!(f, "unknown input group {}", name),
+            InterfaceError::MissingInput(name) => write!(f, "no values for input group {}", name),
+            InterfaceError::WrongLength {
+                name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "input group {} expects {} values, got {}",
+                name, expected, got
+            ),
+            InterfaceError::MissingOutputWire { name, id } => write!(
+                f,
+                "output group {} refers to id {} which the last layer does not compute",
+                name, id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InterfaceError {}
@@ -1,5 +1,22 @@
+pub mod adder;
 pub mod and;
+pub mod assertion;
+pub mod builder;
+pub mod export;
+pub mod integer;
+pub mod interface;
+pub mod majority;
+pub mod mux;
+pub mod nand;
+pub mod nor;
 pub mod not;
 pub mod or;
+pub mod packing;
+pub mod serialize;
+pub mod subcircuit;
+pub mod trace;
 pub mod utils;
+pub mod validate;
+pub mod word;
+pub mod xnor;
 pub mod xor;
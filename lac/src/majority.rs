@@ -0,0 +1,86 @@
+use crate::utils::*;
+
+//3-input majority implemented using MAJ(x0,x1,x2) = (x0*(x1+x2)-x1*x2)^2, on bits the squared
+//term is 0 when at most one input is set and 1 or -1 otherwise.
+//MAJ has degree 3 so it needs 2 layers, it uses 3 gates and the first layer overwrites the
+//id of x2 with a temporary value:
+//  layer1:    g_out=x0*(x1+x2)    g_x2=x1*x2
+//  layer2:    g_out=(g_out-g_x2)*(g_out-g_x2)
+
+#[allow(non_snake_case)]
+pub(crate) fn get_majority_first_layer(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate0: Gate<i64> = Gate::new_R1CS_gate();
+    let mut gate1: Gate<i64> = Gate::new_mult_gate();
+
+    let input_id_R1CS = Some([vec![in_ids[0]], vec![in_ids[1], in_ids[2]]]);
+    let weights_R1CS = Some([vec![1], vec![1, 1]]);
+    gate0.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+    gate1.set_all(
+        Some(degree),
+        Some(in_ids[2]),
+        Some([in_ids[1], in_ids[2]]),
+        None,
+        None,
+    );
+
+    layer.append_gates(vec![gate0, gate1]);
+
+    layer
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn get_majority_second_layer(in_id: u64, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([vec![out_id, in_id], vec![out_id, in_id]]);
+    let weights_R1CS = Some([vec![1, -1], vec![1, -1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+pub fn get_majority_as_layers(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Vec<Layer<i64>> {
+    let layer1 = get_majority_first_layer(in_ids.clone(), out_id, degree);
+    let layer2 = get_majority_second_layer(in_ids[2], out_id, degree + 1);
+    vec![layer1, layer2]
+}
+
+///Majority for bit_string, uses 2 layers
+pub fn get_majority_bitstring_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    in_ids2: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let mut layers = vec![Layer::new(), Layer::new()];
+    for i in 0..size {
+        let layers_majority_bit_i =
+            get_majority_as_layers(vec![in_ids0[i], in_ids1[i], in_ids2[i]], out_ids[i], degree);
+        for (layer, layer_majority_bit_i) in layers.iter_mut().zip(layers_majority_bit_i) {
+            layer.merge_layer(layer_majority_bit_i);
+        }
+    }
+    layers
+}
@@ -0,0 +1,100 @@
+use crate::utils::*;
+
+//2:1 MUX implemented using MUX(s,x0,x1) = x0+s*(x1-x0), which is x0 if s = 0 and x1 if s = 1
+//No single R1CS gate computes it over the integers, so it uses 2 layers and 3 gates, the first
+//layer relays x0 and reads the constant 0 from the previous layer:
+//  layer1:    g_out=s*(x1-x0)    g_x0=0+x0
+//  layer2:    g_out=g_out+g_x0
+//When the output is written over x0, the relay of x0 would overwrite g_out, so it uses
+//MUX(s,x0,x1) = x1+(1-s)*(x0-x1) instead, which relays x1 and also reads the constant 1:
+//  layer1:    g_out=(1-s)*(x0-x1)    g_x1=0+x1
+//  layer2:    g_out=g_out+g_x1
+
+#[allow(non_snake_case)]
+fn get_mux_first_layer(sel_id: u64, in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let (input_id_R1CS, weights_R1CS, relay_id) = if out_id == in_ids[0] {
+        (
+            Some([vec![1, sel_id], vec![in_ids[0], in_ids[1]]]),
+            Some([vec![1, -1], vec![1, -1]]),
+            in_ids[1],
+        )
+    } else {
+        (
+            Some([vec![sel_id], vec![in_ids[1], in_ids[0]]]),
+            Some([vec![1], vec![1, -1]]),
+            in_ids[0],
+        )
+    };
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+    layer.copy_gates_by_ids(vec![relay_id]);
+
+    layer
+}
+
+fn get_mux_second_layer(in_id: u64, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_add_gate();
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        Some([out_id, in_id]),
+        None,
+        None,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+/// Selects `in_ids[0]` if the bit `sel_id` is 0 and `in_ids[1]` if it is 1
+pub fn get_mux_as_layers(
+    sel_id: u64,
+    in_ids: Vec<u64>,
+    out_id: u64,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let relay_id = if out_id == in_ids[0] {
+        in_ids[1]
+    } else {
+        in_ids[0]
+    };
+    let layer1 = get_mux_first_layer(sel_id, in_ids, out_id, degree);
+    let layer2 = get_mux_second_layer(relay_id, out_id, degree + 1);
+    vec![layer1, layer2]
+}
+
+///MUX for bit_string with a single selector bit, uses 2 layers
+pub fn get_mux_bitstring_as_layers(
+    sel_id: u64,
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let mut layers = vec![Layer::new(), Layer::new()];
+    for i in 0..size {
+        let layers_mux_bit_i =
+            get_mux_as_layers(sel_id, vec![in_ids0[i], in_ids1[i]], out_ids[i], degree);
+        for (layer, layer_mux_bit_i) in layers.iter_mut().zip(layers_mux_bit_i) {
+            layer.merge_layer(layer_mux_bit_i);
+        }
+    }
+    layers
+}
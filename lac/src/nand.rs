@@ -0,0 +1,73 @@
+use crate::utils::*;
+
+//NAND gate implemented using NAND(x0,x1) = 1-x0*x1
+//No single R1CS gate computes it over the integers, so it uses 2 layers and 2 gates plus
+//the constants 0 and 1 in the first layer:
+//  layer1:    g_out=x0*x1
+//  layer2:    g_out=(1-g_out)*1
+
+fn get_nand_first_layer(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+    layer.add_gate_0_and_1(degree);
+
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        Some([in_ids[0], in_ids[1]]),
+        None,
+        None,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+#[allow(non_snake_case)]
+fn get_nand_second_layer(out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([vec![1, out_id], vec![1]]);
+    let weights_R1CS = Some([vec![1, -1], vec![1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+pub fn get_nand_as_layers(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Vec<Layer<i64>> {
+    let layer1 = get_nand_first_layer(in_ids, out_id, degree);
+    let layer2 = get_nand_second_layer(out_id, degree + 1);
+    vec![layer1, layer2]
+}
+
+///NAND for bit_string, uses 2 layers
+pub fn get_nand_bitstring_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let mut layers = vec![Layer::new(), Layer::new()];
+    for i in 0..size {
+        let layers_nand_bit_i =
+            get_nand_as_layers(vec![in_ids0[i], in_ids1[i]], out_ids[i], degree);
+        for (layer, layer_nand_bit_i) in layers.iter_mut().zip(layers_nand_bit_i) {
+            layer.merge_layer(layer_nand_bit_i);
+        }
+    }
+    layers
+}
@@ -0,0 +1,43 @@
+use crate::utils::*;
+
+//NOR gate implemented using NOR(x0,x1) = (1-x0)*(1-x1)
+//Uses 1 layer and 1 R1CS gate, reads the constant 1 from the previous layer:
+//  layer1:    g_out=(1-x0)*(1-x1)
+
+#[allow(non_snake_case)]
+pub fn get_nor_as_layer(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([vec![1, in_ids[0]], vec![1, in_ids[1]]]);
+    let weights_R1CS = Some([vec![1, -1], vec![1, -1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+///NOR for bit_string, uses 1 layer
+pub fn get_nor_bitstring_as_layer(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let size = in_ids0.len();
+    let mut layer: Layer<i64> = Layer::new();
+    for i in 0..size {
+        let layer_nor_bit_i = get_nor_as_layer(vec![in_ids0[i], in_ids1[i]], out_ids[i], degree);
+        layer.merge_layer(layer_nor_bit_i);
+    }
+    layer
+}
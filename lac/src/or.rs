@@ -1,3 +1,5 @@
+use crate::interface::*;
+use crate::nor::*;
 use crate::utils::*;
 
 //OR gate implemented using OR(x0,x1) = 1-(1-x0)*(1-x1)
@@ -7,6 +9,7 @@ use crate::utils::*;
 //  layer2:       g_0=g_0*g_1
 //  layer3:      g_0=(1-g_9)*1
 
+///x0 and x1 are the input groups "x0" and "x1", other values are set with LAC::assign_inputs
 pub fn get_or_lac_circuit(x0: i64, x1: i64) -> LAC<i64> {
     let mut lac = LAC::new();
 
@@ -18,6 +21,12 @@ pub fn get_or_lac_circuit(x0: i64, x1: i64) -> LAC<i64> {
     let layers = vec![layer1, layer2, layer3];
     lac.append_layers(layers);
 
+    lac.declare_input("x0", Visibility::Private, vec![2])
+        .unwrap();
+    lac.declare_input("x1", Visibility::Private, vec![3])
+        .unwrap();
+    lac.declare_output("out", vec![0]).unwrap();
+
     lac
 }
 
@@ -90,3 +99,55 @@ fn get_or_third_layer() -> Layer<i64> {
 
     layer
 }
+
+//Composable OR using OR(x0,x1) = 1-NOR(x0,x1), no single R1CS gate computes it over the
+//integers so it uses 2 layers and 2 R1CS gates plus the constants 0 and 1 in the first layer:
+//  layer1:    g_out=(1-x0)*(1-x1)
+//  layer2:    g_out=(1-g_out)*1
+
+#[allow(non_snake_case)]
+fn get_or_composable_second_layer(out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([vec![1, out_id], vec![1]]);
+    let weights_R1CS = Some([vec![1, -1], vec![1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+pub fn get_or_as_layers(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Vec<Layer<i64>> {
+    let mut layer1 = get_nor_as_layer(in_ids, out_id, degree);
+    layer1.add_gate_0_and_1(degree);
+    let layer2 = get_or_composable_second_layer(out_id, degree + 1);
+    vec![layer1, layer2]
+}
+
+///OR for bit_string, uses 2 layers
+pub fn get_or_bitstring_as_layers(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Vec<Layer<i64>> {
+    let size = in_ids0.len();
+    let mut layers = vec![Layer::new(), Layer::new()];
+    for i in 0..size {
+        let layers_or_bit_i = get_or_as_layers(vec![in_ids0[i], in_ids1[i]], out_ids[i], degree);
+        for (layer, layer_or_bit_i) in layers.iter_mut().zip(layers_or_bit_i) {
+            layer.merge_layer(layer_or_bit_i);
+        }
+    }
+    layers
+}
@@ -0,0 +1,100 @@
+use crate::utils::*;
+
+//Values are i64 and not field elements, so a packed value holds at most MAX_PACKED_BITS bits
+//and unpacking only accepts values in 0..2^k.
+
+/// Most bits packed into one value
+pub const MAX_PACKED_BITS: usize = 62;
+
+//Pack of the bits b_0..b_k-1, least significant bit first. Uses 1 layer and 1 R1CS gate and
+//reads the constant 1 from the previous layer:
+//  layer1:    g_out=(sum 2^i*b_i)*1
+
+#[allow(non_snake_case)]
+pub fn get_pack_as_layer(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    assert!(
+        in_ids.len() <= MAX_PACKED_BITS,
+        "can not pack {} bits into one value",
+        in_ids.len()
+    );
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let weights: Vec<i64> = (0..in_ids.len()).map(|i| 1 << i).collect();
+    layer.append_R1CS_gate(out_id, [in_ids, vec![1]], [weights, vec![1]]);
+
+    layer
+}
+
+///Packs the bit_string in_ids into values of k bits, the last one may be shorter, uses 1 layer
+pub fn get_pack_bitstring_as_layer(
+    in_ids: Vec<u64>,
+    out_ids: Vec<u64>,
+    k: usize,
+    degree: u64,
+) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    for (chunk, out_id) in in_ids.chunks(k).zip(out_ids) {
+        layer.merge_layer(get_pack_as_layer(chunk.to_vec(), out_id, degree));
+    }
+    layer
+}
+
+//Unpack of a value x into k bits given as hint inputs h_i by the prover (see
+//get_unpack_hints). The first layer must be able to read the hints, so they are basic values
+//or relayed by the caller. The layer relays the hints and checks them, the k+1 check wires are
+//0 iff the hints are the bits of x:
+//  g_check_i=h_i*(h_i-1)                  for i in 0..k
+//  g_check_k=(x - sum 2^i*h_i)*1
+//Uses 1 layer and 2k+1 gates plus the constants 0 and 1.
+
+/// Layer relaying `hint_ids` and checking that they are the bits of the linear combination
+/// of `value_ids` with `value_weights`
+#[allow(non_snake_case)]
+pub(crate) fn get_unpack_checks_layer(
+    value_ids: Vec<u64>,
+    value_weights: Vec<i64>,
+    hint_ids: Vec<u64>,
+    check_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    assert!(
+        hint_ids.len() <= MAX_PACKED_BITS,
+        "can not unpack a value into {} bits",
+        hint_ids.len()
+    );
+    let mut layer: Layer<i64> = Layer::new_with_constants(degree);
+    layer.copy_gates_by_ids(hint_ids.clone());
+
+    let (mut ids, mut weights) = (value_ids, value_weights);
+    for (i, h) in hint_ids.iter().enumerate() {
+        layer.append_R1CS_gate(
+            check_ids[i],
+            [vec![*h], vec![*h, 1]],
+            [vec![1], vec![1, -1]],
+        );
+        ids.push(*h);
+        weights.push(-(1 << i));
+    }
+    layer.append_R1CS_gate(
+        check_ids[hint_ids.len()],
+        [ids, vec![1]],
+        [weights, vec![1]],
+    );
+    layer
+}
+
+///Unpacks the value in_id into the bits hint_ids, checked by check_ids, uses 1 layer
+pub fn get_unpack_as_layer(
+    in_id: u64,
+    hint_ids: Vec<u64>,
+    check_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    get_unpack_checks_layer(vec![in_id], vec![1], hint_ids, check_ids, degree)
+}
+
+///Hints of get_unpack_as_layer: the k bits of x, least significant bit first
+pub fn get_unpack_hints(x: i64, k: usize) -> Vec<i64> {
+    (0..k).map(|i| (x >> i) & 1).collect()
+}
@@ -0,0 +1,264 @@
+use crate::interface::*;
+use crate::utils::*;
+use itertools::Itertools;
+use std::fmt;
+
+//Binary format, every integer little endian:
+//  magic "LAC\0", version: u32
+//  basic layer: amount: u64, then (id: u64, value: i64) sorted by id
+//  layers: amount: u64, then per layer
+//      degree: u64 (0 if not set), gates amount: u64, then per gate sorted by id
+//      id: u64, type: u8 (0 add, 1 mult, 2 R1CS)
+//      add/mult: input ids: 2 x u64
+//      R1CS: twice (length: u64, ids: length x u64, weights: length x i64)
+//  input groups: amount: u64, then (name, visibility: u8 (0 public, 1 private), ids)
+//  output groups: amount: u64, then (name, ids)
+//  names are (length: u64, utf8 bytes), ids are (length: u64, length x u64)
+
+const MAGIC: &[u8; 4] = b"LAC\0";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    UnexpectedEnd,
+    InvalidGateType(u8),
+    InvalidVisibility(u8),
+    InvalidName,
+    TrailingBytes,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidMagic => write!(f, "not a serialized LAC"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported LAC format version {}", version)
+            }
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            DecodeError::InvalidGateType(t) => write!(f, "invalid gate type {}", t),
+            DecodeError::InvalidVisibility(v) => write!(f, "invalid visibility {}", v),
+            DecodeError::InvalidName => write!(f, "group name is not valid utf8"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after the circuit"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl LAC<i64> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer { bytes: Vec::new() };
+        writer.bytes.extend_from_slice(MAGIC);
+        writer.bytes.extend_from_slice(&VERSION.to_le_bytes());
+
+        writer.u64(self.basic_layer.values.len() as u64);
+        for id in self.basic_layer.values.keys().sorted() {
+            writer.u64(*id);
+            writer.i64(self.basic_layer.values[id].value.unwrap_or(0));
+        }
+
+        writer.u64(self.layers.len() as u64);
+        for layer in &self.layers {
+            writer.u64(layer.degree.unwrap_or(0));
+            writer.u64(layer.gates.len() as u64);
+            for id in layer.gates.keys().sorted() {
+                let gate = layer.gates[id].borrow();
+                writer.u64(*id);
+                match gate.gate_type {
+                    GateType::Add | GateType::Mult => {
+                        writer.u8(if gate.gate_type == GateType::Add {
+                            0
+                        } else {
+                            1
+                        });
+                        let input_id = gate.input_id.unwrap_or([0, 0]);
+                        writer.u64(input_id[0]);
+                        writer.u64(input_id[1]);
+                    }
+                    GateType::R1CS => {
+                        writer.u8(2);
+                        let no_ids: [Vec<u64>; 2] = [Vec::new(), Vec::new()];
+                        let no_weights: [Vec<i64>; 2] = [Vec::new(), Vec::new()];
+                        let input_id = gate.input_id_R1CS.as_ref().unwrap_or(&no_ids);
+                        let weights = gate.R1CS_weights.as_ref().unwrap_or(&no_weights);
+                        for side in 0..2 {
+                            writer.u64(input_id[side].len() as u64);
+                            for id in &input_id[side] {
+                                writer.u64(*id);
+                            }
+                            for i in 0..input_id[side].len() {
+                                writer.i64(*weights[side].get(i).unwrap_or(&0));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        writer.u64(self.inputs.len() as u64);
+        for group in &self.inputs {
+            writer.name(group.get_name());
+            writer.u8(match group.get_visibility() {
+                Visibility::Public => 0,
+                Visibility::Private => 1,
+            });
+            writer.ids(group.get_ids());
+        }
+        writer.u64(self.outputs.len() as u64);
+        for group in &self.outputs {
+            writer.name(group.get_name());
+            writer.ids(group.get_ids());
+        }
+
+        writer.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<LAC<i64>, DecodeError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(DecodeError::InvalidMagic);
+        }
+        let version = u32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+
+        let mut lac: LAC<i64> = LAC::new();
+        let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+        for _ in 0..reader.u64()? {
+            let mut value: Value<i64> = Value::new();
+            let id = reader.u64()?;
+            value.set_all(id, reader.i64()?);
+            basic_layer.append_value(value);
+        }
+        lac.set_basic_layer(basic_layer);
+
+        for _ in 0..reader.u64()? {
+            let mut layer: Layer<i64> = Layer::new();
+            let degree = reader.u64()?;
+            let degree = if degree == 0 { None } else { Some(degree) };
+            layer.degree = degree;
+            for _ in 0..reader.u64()? {
+                let id = reader.u64()?;
+                let mut gate: Gate<i64> = match reader.u8()? {
+                    0 => Gate::new_add_gate(),
+                    1 => Gate::new_mult_gate(),
+                    2 => Gate::new_R1CS_gate(),
+                    t => return Err(DecodeError::InvalidGateType(t)),
+                };
+                if gate.gate_type == GateType::R1CS {
+                    let mut input_id: [Vec<u64>; 2] = [Vec::new(), Vec::new()];
+                    let mut weights: [Vec<i64>; 2] = [Vec::new(), Vec::new()];
+                    for side in 0..2 {
+                        let len = reader.u64()?;
+                        for _ in 0..len {
+                            input_id[side].push(reader.u64()?);
+                        }
+                        for _ in 0..len {
+                            weights[side].push(reader.i64()?);
+                        }
+                    }
+                    gate.set_all(degree, Some(id), None, Some(input_id), Some(weights));
+                } else {
+                    let input_id = [reader.u64()?, reader.u64()?];
+                    gate.set_all(degree, Some(id), Some(input_id), None, None);
+                }
+                layer.append_gate(gate);
+            }
+            lac.append_layer(layer);
+        }
+
+        for _ in 0..reader.u64()? {
+            let name = reader.name()?;
+            let visibility = match reader.u8()? {
+                0 => Visibility::Public,
+                1 => Visibility::Private,
+                v => return Err(DecodeError::InvalidVisibility(v)),
+            };
+            lac.inputs
+                .push(InputGroup::new(&name, visibility, reader.ids()?));
+        }
+        for _ in 0..reader.u64()? {
+            let name = reader.name()?;
+            lac.outputs.push(OutputGroup::new(&name, reader.ids()?));
+        }
+
+        if reader.pos != bytes.len() {
+            return Err(DecodeError::TrailingBytes);
+        }
+        Ok(lac)
+    }
+}
+
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, x: u8) {
+        self.bytes.push(x);
+    }
+
+    fn u64(&mut self, x: u64) {
+        self.bytes.extend_from_slice(&x.to_le_bytes());
+    }
+
+    fn i64(&mut self, x: i64) {
+        self.bytes.extend_from_slice(&x.to_le_bytes());
+    }
+
+    fn name(&mut self, name: &str) {
+        self.u64(name.len() as u64);
+        self.bytes.extend_from_slice(name.as_bytes());
+    }
+
+    fn ids(&mut self, ids: &[u64]) {
+        self.u64(ids.len() as u64);
+        for id in ids {
+            self.u64(*id);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], DecodeError> {
+        if self.bytes.len() - self.pos < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        self.pos += len;
+        Ok(&self.bytes[(self.pos - len)..self.pos])
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, DecodeError> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn name(&mut self) -> Result<String, DecodeError> {
+        let len = self.u64()?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::UnexpectedEnd)?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| DecodeError::InvalidName)
+    }
+
+    fn ids(&mut self) -> Result<Vec<u64>, DecodeError> {
+        let mut ids: Vec<u64> = Vec::new();
+        for _ in 0..self.u64()? {
+            ids.push(self.u64()?);
+        }
+        Ok(ids)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::utils::*;
+use std::collections::HashMap;
+use std::ops::{Add, Mul};
+
+/// Reusable template: layers built once with degrees starting at 1, the ids read by its
+/// first layer (input ports) and the ids of its last layer holding the result (output ports).
+/// Every instance binds the input ports to existing wires and moves all other ids by an
+/// `IdRemap`, so several instances never share internal ids.
+#[derive(Clone)]
+pub struct SubCircuit<T> {
+    layers: Vec<Layer<T>>,
+    input_ids: Vec<u64>,
+    output_ids: Vec<u64>,
+    constant_ids: Vec<u64>,
+}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> SubCircuit<T> {
+    /// `layers` must be consecutive, the first one is moved to degree 1. Panics if the first
+    /// layer reads an id that is neither an input port nor a constant or if an output port is
+    /// not computed by the last layer.
+    pub fn new(layers: Vec<Layer<T>>, input_ids: Vec<u64>, output_ids: Vec<u64>) -> Self {
+        assert!(!layers.is_empty(), "subcircuit needs at least one layer");
+        let mut layers = layers;
+        for (i, layer) in layers.iter_mut().enumerate() {
+            layer.set_gates_degree(i as u64 + 1);
+        }
+
+        let mut constant_ids: Vec<u64> = Vec::new();
+        for id in layers[0].get_read_ids() {
+            if id < RESERVED_IDS {
+                constant_ids.push(id);
+            } else {
+                assert!(
+                    input_ids.contains(&id),
+                    "subcircuit reads id {} which is not an input port",
+                    id
+                );
+            }
+        }
+        let last_ids = layers.last().unwrap().get_gate_ids();
+        for id in &output_ids {
+            assert!(
+                last_ids.binary_search(id).is_ok(),
+                "output port {} is not computed by the last layer",
+                id
+            );
+        }
+
+        SubCircuit {
+            layers,
+            input_ids,
+            output_ids,
+            constant_ids,
+        }
+    }
+
+    pub fn get_depth(&self) -> u64 {
+        self.layers.len() as u64
+    }
+
+    pub fn get_input_ids(&self) -> Vec<u64> {
+        self.input_ids.clone()
+    }
+
+    pub fn get_output_ids(&self) -> Vec<u64> {
+        self.output_ids.clone()
+    }
+
+    /// Constants the first layer reads, they must be present in the layer before any instance
+    pub fn get_constant_ids(&self) -> Vec<u64> {
+        self.constant_ids.clone()
+    }
+
+    /// Largest id used inside the template, an `IdRemap` with a larger offset than any id in
+    /// use keeps instances disjoint from the surrounding circuit
+    pub fn get_max_id(&self) -> u64 {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.get_gate_ids())
+            .chain(self.input_ids.iter().copied())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Ids of the output ports of the instance created with `remap`
+    pub fn get_instance_output_ids(&self, remap: &IdRemap) -> Vec<u64> {
+        remap.map_ids(&self.output_ids)
+    }
+
+    /// Layers of an instance whose first layer has degree `degree` and reads `inputs` in
+    /// place of the input ports. Every `pass_through` wire of the layer before the instance is
+    /// relayed unchanged, with the same id, through all its layers, together with the
+    /// constant 0 the relays read.
+    pub fn instantiate(
+        &self,
+        degree: u64,
+        inputs: &[u64],
+        remap: &IdRemap,
+        pass_through: &[u64],
+    ) -> Vec<Layer<T>> {
+        assert_eq!(
+            inputs.len(),
+            self.input_ids.len(),
+            "every input port must be bound"
+        );
+        let bindings: HashMap<u64, u64> = self
+            .input_ids
+            .iter()
+            .copied()
+            .zip(inputs.iter().copied())
+            .collect();
+
+        let mut relays: Vec<u64> = pass_through.to_vec();
+        if !relays.is_empty() && !relays.contains(&0) {
+            relays.push(0);
+        }
+
+        let mut layers: Vec<Layer<T>> = Vec::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let map_out = |id: u64| remap.map(id);
+            let mut layer = if i == 0 {
+                layer.remapped(&map_out, &|id: u64| *bindings.get(&id).unwrap_or(&id))
+            } else {
+                layer.remapped(&map_out, &map_out)
+            };
+            layer.set_gates_degree(degree + i as u64);
+            layer.copy_gates_by_ids(relays.clone());
+            layers.push(layer);
+        }
+        layers
+    }
+
+    /// Instantiates the template into `lac` at `degree`, with internal ids above every id
+    /// already used by `lac`. Returns the ids of the instance's output ports.
+    pub fn instantiate_into(
+        &self,
+        lac: &mut LAC<T>,
+        degree: u64,
+        inputs: &[u64],
+        pass_through: &[u64],
+    ) -> Result<Vec<u64>, MergeError>
+    where
+        T: PartialEq,
+    {
+        let remap = IdRemap::new(lac.get_max_id().max(RESERVED_IDS - 1) + 1 - RESERVED_IDS);
+        let layers = self.instantiate(degree, inputs, &remap, pass_through);
+        lac.add_layers(layers)?;
+        Ok(self.get_instance_output_ids(&remap))
+    }
+}
@@ -0,0 +1,160 @@
+use crate::utils::*;
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Values of every wire: index 0 holds the basic layer values and index `d` the outputs of the
+/// layer of degree `d`, both indexed by id
+pub type Trace<T> = Vec<HashMap<u64, T>>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceError<T> {
+    /// The trace does not have one entry per layer plus one for the basic layer
+    WrongLayersAmount { expected: usize, got: usize },
+    /// The trace has no value for the gate `id` of degree `degree`
+    MissingValue { degree: u64, id: u64 },
+    /// The gate `id` of degree `degree` reads `input_id`, which has no value in the trace
+    MissingInput { degree: u64, id: u64, input_id: u64 },
+    /// The trace has a value for an id the layer does not compute
+    UnknownGate { degree: u64, id: u64 },
+    /// The claimed value differs from the gate applied to the claimed previous layer
+    Inconsistent {
+        degree: u64,
+        id: u64,
+        expected: T,
+        claimed: T,
+    },
+}
+
+impl<T: fmt::Debug> fmt::Display for TraceError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::WrongLayersAmount { expected, got } => {
+                write!(f, "trace has {} layers, expected {}", got, expected)
+            }
+            TraceError::MissingValue { degree, id } => {
+                write!(f, "no value for gate {} of degree {}", id, degree)
+            }
+            TraceError::MissingInput {
+                degree,
+                id,
+                input_id,
+            } => write!(
+                f,
+                "gate {} of degree {} reads {} which has no value",
+                id, degree, input_id
+            ),
+            TraceError::UnknownGate { degree, id } => {
+                write!(f, "value for unknown gate {} of degree {}", id, degree)
+            }
+            TraceError::Inconsistent {
+                degree,
+                id,
+                expected,
+                claimed,
+            } => write!(
+                f,
+                "gate {} of degree {} should be {:?}, trace claims {:?}",
+                id, degree, expected, claimed
+            ),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for TraceError<T> {}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> LAC<T> {
+    /// Evaluates the circuit and returns the value of every wire
+    pub fn get_trace(&mut self) -> Trace<T> {
+        self.evaluate();
+        let basic: HashMap<u64, T> = self
+            .basic_layer
+            .values
+            .iter()
+            .filter_map(|(id, value)| value.value.map(|x| (*id, x)))
+            .collect();
+        let mut trace: Trace<T> = vec![basic];
+        for layer in &self.layers {
+            trace.push(layer.output.clone());
+        }
+        trace
+    }
+
+    /// Checks that every claimed gate value equals the gate applied to the claimed values of
+    /// the previous layer. The basic layer values of the trace are taken as given. Returns
+    /// the first problem, by degree and then id.
+    pub fn check_trace(&self, trace: &Trace<T>) -> Result<(), TraceError<T>>
+    where
+        T: PartialEq,
+    {
+        match self.check_trace_up_to(trace, Some(1)) {
+            Ok(()) => Ok(()),
+            Err(mut errors) => Err(errors.remove(0)),
+        }
+    }
+
+    /// Same as `check_trace` but returns every problem found
+    pub fn check_trace_all(&self, trace: &Trace<T>) -> Result<(), Vec<TraceError<T>>>
+    where
+        T: PartialEq,
+    {
+        self.check_trace_up_to(trace, None)
+    }
+
+    fn check_trace_up_to(
+        &self,
+        trace: &Trace<T>,
+        max_errors: Option<usize>,
+    ) -> Result<(), Vec<TraceError<T>>>
+    where
+        T: PartialEq,
+    {
+        if trace.len() != self.layers.len() + 1 {
+            return Err(vec![TraceError::WrongLayersAmount {
+                expected: self.layers.len() + 1,
+                got: trace.len(),
+            }]);
+        }
+
+        let mut errors: Vec<TraceError<T>> = Vec::new();
+        for (position, layer) in self.layers.iter().enumerate() {
+            let degree = position as u64 + 1;
+            let previous = &trace[position];
+            let claimed = &trace[position + 1];
+            for id in layer.gates.keys().chain(claimed.keys()).sorted().dedup() {
+                let id = *id;
+                let error = match (layer.gates.get(&id), claimed.get(&id)) {
+                    (None, _) => Some(TraceError::UnknownGate { degree, id }),
+                    (Some(_), None) => Some(TraceError::MissingValue { degree, id }),
+                    (Some(gate), Some(claimed)) => match gate.borrow().compute(previous) {
+                        Err(input_id) => Some(TraceError::MissingInput {
+                            degree,
+                            id,
+                            input_id,
+                        }),
+                        Ok(expected) if expected != *claimed => Some(TraceError::Inconsistent {
+                            degree,
+                            id,
+                            expected,
+                            claimed: *claimed,
+                        }),
+                        Ok(_) => None,
+                    },
+                };
+                if let Some(error) = error {
+                    errors.push(error);
+                    if Some(errors.len()) == max_errors {
+                        return Err(errors);
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
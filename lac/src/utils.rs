@@ -1,12 +1,84 @@
+use crate::interface::*;
 use itertools::Itertools;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
 use std::ops::{Add, Mul};
 
+/// Ids 0 and 1 are reserved for the constant gates/values 0 and 1 and are shared when merging.
+pub const RESERVED_IDS: u64 = 2;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeError {
+    /// Two different gates with the same id at the same degree
+    ConflictingGate { degree: u64, id: u64 },
+    /// Two different basic values with the same id
+    ConflictingValue { id: u64 },
+    /// Layer has no degree set, so it can not be placed
+    MissingDegree,
+    /// Both circuits declare an input or output group with this name
+    ConflictingGroup { name: String },
+    /// The layer of this degree, 0 for the basic layer, has no constant 0 for the relays that
+    /// pad the circuit to read, or holds another gate or value at the id 0
+    MissingConstant { degree: u64 },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::ConflictingGate { degree, id } => {
+                write!(f, "conflicting gate with id {} at degree {}", id, degree)
+            }
+            MergeError::ConflictingValue { id } => {
+                write!(f, "conflicting basic value with id {}", id)
+            }
+            MergeError::MissingDegree => write!(f, "layer has no degree"),
+            MergeError::ConflictingGroup { name } => {
+                write!(f, "group {} declared by both circuits", name)
+            }
+            MergeError::MissingConstant { degree } => {
+                write!(f, "layer of degree {} has no constant 0 to relay from", degree)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// Moves every non reserved id by a fixed offset, ids 0 and 1 stay in place.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IdRemap {
+    offset: u64,
+}
+
+impl IdRemap {
+    pub fn new(offset: u64) -> Self {
+        IdRemap { offset }
+    }
+
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn map(&self, id: u64) -> u64 {
+        if id < RESERVED_IDS {
+            id
+        } else {
+            id + self.offset
+        }
+    }
+
+    pub fn map_ids(&self, ids: &[u64]) -> Vec<u64> {
+        ids.iter().map(|id| self.map(*id)).collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct LAC<T> {
-    basic_layer: BasicLayer<T>,
-    layers: Vec<Layer<T>>,
+    pub(crate) basic_layer: BasicLayer<T>,
+    pub(crate) layers: Vec<Layer<T>>,
+    pub(crate) inputs: Vec<InputGroup>,
+    pub(crate) outputs: Vec<OutputGroup>,
 }
 
 impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> LAC<T> {
@@ -14,13 +86,15 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         LAC {
             basic_layer: BasicLayer::new(),
             layers: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
         }
     }
 
     pub fn get_gates_amount(&self) -> usize {
         let mut res = 0;
         for layer in &self.layers {
-            for (_, gate) in &layer.gates {
+            for gate in layer.gates.values() {
                 match gate.borrow().gate_type {
                     GateType::R1CS => {
                         res += 3;
@@ -38,8 +112,164 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         self.layers.len()
     }
 
+    /// Amount of declared input values, or of basic values other than the constants 0 and 1
+    /// when no input group is declared
     pub fn get_input_size(&self) -> usize {
-        self.basic_layer.values.len() - 2
+        if self.inputs.is_empty() {
+            self.basic_layer
+                .values
+                .keys()
+                .filter(|id| **id >= RESERVED_IDS)
+                .count()
+        } else {
+            self.inputs.iter().map(|group| group.get_ids().len()).sum()
+        }
+    }
+
+    /// Declares the basic values with ids `ids` as the input group `name`
+    pub fn declare_input(
+        &mut self,
+        name: &str,
+        visibility: Visibility,
+        ids: Vec<u64>,
+    ) -> Result<(), InterfaceError> {
+        self.check_group_name(name)?;
+        self.inputs.push(InputGroup::new(name, visibility, ids));
+        Ok(())
+    }
+
+    /// Declares the gates of the last layer with ids `ids` as the output group `name`
+    pub fn declare_output(&mut self, name: &str, ids: Vec<u64>) -> Result<(), InterfaceError> {
+        self.check_group_name(name)?;
+        self.outputs.push(OutputGroup::new(name, ids));
+        Ok(())
+    }
+
+    fn check_group_name(&self, name: &str) -> Result<(), InterfaceError> {
+        let inputs = self.inputs.iter().map(|group| group.get_name());
+        let mut names = inputs.chain(self.outputs.iter().map(|group| group.get_name()));
+        if names.any(|group_name| group_name == name) {
+            return Err(InterfaceError::DuplicateName(name.to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn get_input_groups(&self) -> &[InputGroup] {
+        &self.inputs
+    }
+
+    pub fn get_output_groups(&self) -> &[OutputGroup] {
+        &self.outputs
+    }
+
+    pub fn get_input_group(&self, name: &str) -> Option<&InputGroup> {
+        self.inputs.iter().find(|group| group.get_name() == name)
+    }
+
+    pub fn get_output_group(&self, name: &str) -> Option<&OutputGroup> {
+        self.outputs.iter().find(|group| group.get_name() == name)
+    }
+
+    /// Ids of every input group with the given visibility, in declaration order
+    pub fn get_input_ids(&self, visibility: Visibility) -> Vec<u64> {
+        self.inputs
+            .iter()
+            .filter(|group| group.get_visibility() == visibility)
+            .flat_map(|group| group.get_ids().iter().copied())
+            .collect()
+    }
+
+    /// Sets the basic values of the input group `name`
+    pub fn set_input(&mut self, name: &str, values: &[T]) -> Result<(), InterfaceError> {
+        let group = self
+            .get_input_group(name)
+            .ok_or_else(|| InterfaceError::UnknownInput(name.to_string()))?;
+        if group.get_ids().len() != values.len() {
+            return Err(InterfaceError::WrongLength {
+                name: name.to_string(),
+                expected: group.get_ids().len(),
+                got: values.len(),
+            });
+        }
+        let mut new_values: Vec<Value<T>> = Vec::new();
+        for (id, x) in group.get_ids().iter().zip(values) {
+            let mut value: Value<T> = Value::new();
+            value.set_all(*id, *x);
+            new_values.push(value);
+        }
+        self.basic_layer.append_values(new_values);
+        Ok(())
+    }
+
+    /// Sets the basic values of every declared input group from `assignment`. Fails without
+    /// changing any value when a group has no values, a wrong amount of values or is not
+    /// declared, so a circuit built once can be evaluated with many witnesses.
+    pub fn assign_inputs(&mut self, assignment: &Assignment<T>) -> Result<(), InterfaceError> {
+        for name in assignment.get_names() {
+            if self.get_input_group(name).is_none() {
+                return Err(InterfaceError::UnknownInput(name.to_string()));
+            }
+        }
+        for group in &self.inputs {
+            let values = assignment
+                .get(group.get_name())
+                .ok_or_else(|| InterfaceError::MissingInput(group.get_name().to_string()))?;
+            if group.get_ids().len() != values.len() {
+                return Err(InterfaceError::WrongLength {
+                    name: group.get_name().to_string(),
+                    expected: group.get_ids().len(),
+                    got: values.len(),
+                });
+            }
+        }
+        for group in self.inputs.clone() {
+            self.set_input(group.get_name(), assignment.get(group.get_name()).unwrap())?;
+        }
+        Ok(())
+    }
+
+    /// Sets every declared input group from `inputs`, evaluates the circuit and returns the
+    /// values of every declared output group
+    pub fn evaluate_named(
+        &mut self,
+        inputs: &HashMap<&str, Vec<T>>,
+    ) -> Result<HashMap<String, Vec<T>>, InterfaceError> {
+        let mut assignment: Assignment<T> = Assignment::new();
+        for (name, values) in inputs {
+            assignment.set(name, values.clone());
+        }
+        self.assign_inputs(&assignment)?;
+        self.evaluate();
+        self.get_named_outputs()
+    }
+
+    /// Values of every declared output group after `evaluate`
+    pub fn get_named_outputs(&self) -> Result<HashMap<String, Vec<T>>, InterfaceError> {
+        let last_output = match self.layers.last() {
+            Some(layer) => layer.output.clone(),
+            None => self
+                .basic_layer
+                .values
+                .iter()
+                .map(|(id, value)| (*id, value.value.unwrap()))
+                .collect(),
+        };
+        let mut res: HashMap<String, Vec<T>> = HashMap::new();
+        for group in &self.outputs {
+            let mut values: Vec<T> = Vec::new();
+            for id in group.get_ids() {
+                let value =
+                    last_output
+                        .get(id)
+                        .ok_or_else(|| InterfaceError::MissingOutputWire {
+                            name: group.get_name().to_string(),
+                            id: *id,
+                        })?;
+                values.push(*value);
+            }
+            res.insert(group.get_name().to_string(), values);
+        }
+        Ok(res)
     }
 
     pub fn set_basic_layer(&mut self, basic_layer: BasicLayer<T>) {
@@ -65,54 +295,198 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         }
     }
 
-    pub fn merge_lac(&mut self, lac: LAC<T>) {
+    /// Merges `lac` side by side into `self`, keeping its ids.
+    /// Gates and values with the same id must be identical, otherwise nothing is merged and
+    /// the conflict is returned. The shallower circuit is padded with relays of its last layer.
+    pub fn merge_lac(&mut self, lac: LAC<T>) -> Result<(), MergeError>
+    where
+        T: PartialEq,
+    {
+        let mut merged = self.clone();
+        let mut lac = lac;
+        merged.pad_to_layers_amount(lac.get_layers_amount())?;
+        lac.pad_to_layers_amount(merged.get_layers_amount())?;
+
         for (id, value) in lac.basic_layer.values {
-            self.basic_layer.values.insert(id, value);
+            merged.basic_layer.try_append_value(id, value)?;
+        }
+        for (i, layer) in lac.layers.into_iter().enumerate() {
+            merged.layers[i].try_merge_layer(layer)?;
+        }
+        for group in lac.inputs {
+            merged
+                .declare_input(
+                    group.get_name(),
+                    group.get_visibility(),
+                    group.get_ids().to_vec(),
+                )
+                .map_err(|_| MergeError::ConflictingGroup {
+                    name: group.get_name().to_string(),
+                })?;
+        }
+        for group in lac.outputs {
+            merged
+                .declare_output(group.get_name(), group.get_ids().to_vec())
+                .map_err(|_| MergeError::ConflictingGroup {
+                    name: group.get_name().to_string(),
+                })?;
+        }
+
+        *self = merged;
+        Ok(())
+    }
+
+    /// Merges `lac` side by side into `self` after moving all its ids, except the constants
+    /// 0 and 1, above the largest id used by `self`. Returns the remap so the caller can find
+    /// the inputs and outputs of `lac` in the merged circuit.
+    pub fn merge_lac_remapped(&mut self, lac: LAC<T>) -> Result<IdRemap, MergeError>
+    where
+        T: PartialEq,
+    {
+        let remap = IdRemap::new(self.get_max_id().max(RESERVED_IDS - 1) + 1 - RESERVED_IDS);
+        self.merge_lac(lac.remapped(&remap))?;
+        Ok(remap)
+    }
+
+    /// Returns a copy of the circuit with every id, including the input ids of the gates and
+    /// the ids of the input and output groups, moved by `remap`
+    pub fn remapped(&self, remap: &IdRemap) -> LAC<T> {
+        let mut basic_layer = BasicLayer::new();
+        for (id, value) in &self.basic_layer.values {
+            let mut value = value.clone();
+            value.set_id(remap.map(*id));
+            basic_layer.append_value(value);
+        }
+        let mut inputs = self.inputs.clone();
+        for group in inputs.iter_mut() {
+            group.set_ids(remap.map_ids(group.get_ids()));
+        }
+        let mut outputs = self.outputs.clone();
+        for group in outputs.iter_mut() {
+            group.set_ids(remap.map_ids(group.get_ids()));
+        }
+        LAC {
+            basic_layer,
+            layers: self
+                .layers
+                .iter()
+                .map(|layer| layer.remapped(&|id| remap.map(id), &|id| remap.map(id)))
+                .collect(),
+            inputs,
+            outputs,
+        }
+    }
+
+    /// Largest id used by a basic value or a gate, 0 for an empty circuit
+    pub fn get_max_id(&self) -> u64 {
+        let basic_max = self.basic_layer.values.keys().max().copied().unwrap_or(0);
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.gates.keys().max().copied())
+            .fold(basic_max, u64::max)
+    }
+
+    /// Ids a new layer can read, the gates of the last layer or the basic values of a circuit
+    /// without layers, sorted
+    pub fn get_last_layer_ids(&self) -> Vec<u64> {
+        match self.layers.last() {
+            Some(layer) => layer.get_gate_ids(),
+            None => self.basic_layer.values.keys().copied().sorted().collect(),
         }
-        //TODO: merge_lac should use merge_layer method
-        for layer in lac.layers {
-            let degree = layer.degree.unwrap() as usize;
-            for (id, gate) in layer.gates {
-                self.layers[degree].gates.insert(id, gate);
+    }
+
+    /// Appends layers relaying every gate of the last layer until the circuit has
+    /// `layers_amount` layers. Relays read the constant gate 0 of the previous layer, so the
+    /// last layer must hold it unless it is empty: its gate 0 must be 0+0 or 0*0 of the gate 0
+    /// of every layer below, down to the basic value 0. Padding layers relay it too.
+    pub fn pad_to_layers_amount(&mut self, layers_amount: usize) -> Result<(), MergeError>
+    where
+        T: PartialEq,
+    {
+        if self.layers.len() < layers_amount && !self.get_last_layer_ids().is_empty() {
+            if let Some(degree) = self.get_missing_constant_degree() {
+                return Err(MergeError::MissingConstant { degree });
             }
         }
+        while self.layers.len() < layers_amount {
+            let ids = self.get_last_layer_ids();
+            let mut layer: Layer<T> = Layer::new();
+            layer.set_degree(self.layers.len() as u64 + 1);
+            layer.copy_gates_by_ids(ids);
+            self.layers.push(layer);
+        }
+        Ok(())
     }
 
-    pub fn add_layers(&mut self, layers: Vec<Layer<T>>) {
-        for layer in layers {
-            let degree = layer.degree.unwrap() as usize;
-            for (id, gate) in layer.gates {
-                self.layers[degree].gates.insert(id, gate);
+    /// Degree of the highest layer, 0 for the basic layer, whose gate 0 is not the constant 0,
+    /// None if the constant 0 is relayed from the basic value 0 up to the last layer
+    fn get_missing_constant_degree(&self) -> Option<u64>
+    where
+        T: PartialEq,
+    {
+        for (position, layer) in self.layers.iter().enumerate().rev() {
+            let is_constant = layer.gates.get(&0).is_some_and(|gate| {
+                let gate = gate.borrow();
+                gate.gate_type != GateType::R1CS && gate.input_id == Some([0, 0])
+            });
+            if !is_constant {
+                return Some(position as u64 + 1);
             }
         }
+        let zero: T = std::iter::empty().sum();
+        match self.basic_layer.values.get(&0) {
+            Some(value) if value.value == Some(zero) => None,
+            _ => Some(0),
+        }
     }
 
+    /// Merges every layer into the existing layer with the same degree
+    pub fn add_layers(&mut self, layers: Vec<Layer<T>>) -> Result<(), MergeError>
+    where
+        T: PartialEq,
+    {
+        for layer in layers {
+            let degree = layer.degree.ok_or(MergeError::MissingDegree)?;
+            self.pad_to_layers_amount(degree as usize)?;
+            self.layers[(degree - 1) as usize].try_merge_layer(layer)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluates every layer and returns the outputs of the last layer sorted by id,
+    /// including the constant gates. Use `get_named_outputs` for the declared outputs only.
     pub fn evaluate(&mut self) -> Vec<T> {
-        for i in 0..self.layers.len() {
-            println!("LAC evaluating layer {}", i);
-            self.layers[i] = self.clone().layers[i].evaluate(self.clone());
+        let mut previous: HashMap<u64, T> = self
+            .basic_layer
+            .values
+            .iter()
+            .map(|(id, value)| (*id, value.value.unwrap()))
+            .collect();
+        for layer in self.layers.iter_mut() {
+            layer.evaluate(&previous);
+            previous = layer.output.clone();
         }
-        println!("LAC evaluated");
         let mut res: Vec<T> = Vec::new();
-        for id in self.layers.last().unwrap().gates.keys().sorted() {
-            println!("id: {}", id);
-            res.push(
-                self.layers.last().unwrap().gates[id]
-                    .borrow()
-                    .output
-                    .unwrap()
-                    .clone(),
-            );
+        for id in previous.keys().sorted() {
+            res.push(previous[id]);
         }
         res
     }
 }
 
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> Default
+    for LAC<T>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone)]
 pub struct Layer<T> {
-    degree: Option<u64>,
-    gates: HashMap<u64, RefCell<Gate<T>>>, //id -> gate
-    output: HashMap<u64, T>,
+    pub(crate) degree: Option<u64>,
+    pub(crate) gates: HashMap<u64, RefCell<Gate<T>>>, //id -> gate
+    pub(crate) output: HashMap<u64, T>,
 }
 
 impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> Layer<T> {
@@ -155,13 +529,85 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         self.gates.insert(1, RefCell::new(gate1));
     }
 
-    //TODO: merge_layer should check if gate exists with such id
+    /// Merges the gates of `layer` into this one, a gate with an already used id replaces
+    /// the existing gate. Use `try_merge_layer` to detect conflicting ids instead.
     pub fn merge_layer(&mut self, layer: Layer<T>) {
+        if self.degree.is_none() {
+            self.degree = layer.degree;
+        }
         for (id, gate) in layer.gates {
             self.gates.insert(id, gate);
         }
     }
 
+    /// Merges the gates of `layer` into this one. Gates sharing an id must be identical,
+    /// otherwise nothing is merged and the conflicting id is returned.
+    pub fn try_merge_layer(&mut self, layer: Layer<T>) -> Result<(), MergeError>
+    where
+        T: PartialEq,
+    {
+        for (id, gate) in &layer.gates {
+            if let Some(existing) = self.gates.get(id) {
+                if !existing.borrow().same_wiring(&gate.borrow()) {
+                    return Err(MergeError::ConflictingGate {
+                        degree: self.degree.or(layer.degree).unwrap_or(0),
+                        id: *id,
+                    });
+                }
+            }
+        }
+        if self.degree.is_none() {
+            self.degree = layer.degree;
+        }
+        for (id, gate) in layer.gates {
+            self.gates.entry(id).or_insert(gate);
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the layer with the gate ids mapped by `map_out` and the ids
+    /// read from the previous layer mapped by `map_in`
+    pub fn remapped(
+        &self,
+        map_out: &impl Fn(u64) -> u64,
+        map_in: &impl Fn(u64) -> u64,
+    ) -> Layer<T> {
+        let mut layer: Layer<T> = Layer::new();
+        layer.degree = self.degree;
+        for gate in self.gates.values() {
+            layer.append_gate(gate.borrow().remapped(map_out, map_in));
+        }
+        layer
+    }
+
+    /// Sets the degree of the layer and of every gate in it
+    pub fn set_gates_degree(&mut self, degree: u64) {
+        self.degree = Some(degree);
+        for gate in self.gates.values() {
+            gate.borrow_mut().set_degree(degree);
+        }
+    }
+
+    /// Ids of the gates in the layer, sorted
+    pub fn get_gate_ids(&self) -> Vec<u64> {
+        self.gates.keys().copied().sorted().collect()
+    }
+
+    /// Ids of the previous layer read by any gate of the layer, sorted and deduplicated
+    pub fn get_read_ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = Vec::new();
+        for gate in self.gates.values() {
+            let gate = gate.borrow();
+            if let Some(input_id) = gate.input_id {
+                ids.extend(input_id);
+            }
+            if let Some(input_id_r1cs) = &gate.input_id_R1CS {
+                ids.extend(input_id_r1cs[0].iter().chain(input_id_r1cs[1].iter()));
+            }
+        }
+        ids.into_iter().sorted().dedup().collect()
+    }
+
     pub fn copy_gates_by_ids(&mut self, ids: Vec<u64>) {
         for id in ids {
             let mut gate: Gate<T> = Gate::new_add_gate();
@@ -178,21 +624,65 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         }
     }
 
-    fn evaluate(&mut self, lac: LAC<T>) -> Layer<T> {
+    /// Empty layer of degree `degree` holding the constants 0 and 1
+    pub(crate) fn new_with_constants(degree: u64) -> Self {
+        let mut layer: Layer<T> = Layer::new();
+        layer.set_degree(degree);
+        layer.add_gate_0_and_1(degree);
+        layer
+    }
+
+    pub(crate) fn append_add_gate(&mut self, id: u64, input_id: [u64; 2]) {
+        let mut gate: Gate<T> = Gate::new_add_gate();
+        gate.set_all(self.degree, Some(id), Some(input_id), None, None);
+        self.append_gate(gate);
+    }
+
+    pub(crate) fn append_mult_gate(&mut self, id: u64, input_id: [u64; 2]) {
+        let mut gate: Gate<T> = Gate::new_mult_gate();
+        gate.set_all(self.degree, Some(id), Some(input_id), None, None);
+        self.append_gate(gate);
+    }
+
+    #[allow(non_snake_case)]
+    pub(crate) fn append_R1CS_gate(
+        &mut self,
+        id: u64,
+        input_id_R1CS: [Vec<u64>; 2],
+        R1CS_weights: [Vec<T>; 2],
+    ) {
+        let mut gate: Gate<T> = Gate::new_R1CS_gate();
+        gate.set_all(
+            self.degree,
+            Some(id),
+            None,
+            Some(input_id_R1CS),
+            Some(R1CS_weights),
+        );
+        self.append_gate(gate);
+    }
+
+    fn evaluate(&mut self, previous: &HashMap<u64, T>) {
         for (id, gate) in self.gates.iter() {
-            println!("evaluating gate {}", id);
             let mut g = gate.borrow_mut();
-            g.set_input(lac.clone());
+            g.set_input_from(previous);
             g.evaluate();
             self.output.insert(*id, g.output.unwrap());
         }
-        self.clone()
+    }
+}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> Default
+    for Layer<T>
+{
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct BasicLayer<T> {
-    values: HashMap<u64, Value<T>>, //id -> Value
+    pub(crate) values: HashMap<u64, Value<T>>, //id -> Value
 }
 
 impl<T> BasicLayer<T> {
@@ -211,12 +701,34 @@ impl<T> BasicLayer<T> {
             self.values.insert(value.id.unwrap(), value);
         }
     }
+
+    fn try_append_value(&mut self, id: u64, value: Value<T>) -> Result<(), MergeError>
+    where
+        T: PartialEq,
+    {
+        match self.values.get(&id) {
+            Some(existing) if existing.value != value.value => {
+                Err(MergeError::ConflictingValue { id })
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.values.insert(id, value);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T> Default for BasicLayer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Value<T> {
-    id: Option<u64>,
-    value: Option<T>,
+    pub(crate) id: Option<u64>,
+    pub(crate) value: Option<T>,
 }
 
 impl<T> Value<T> {
@@ -241,9 +753,15 @@ impl<T> Value<T> {
     }
 }
 
+impl<T> Default for Value<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[allow(non_snake_case)]
 #[derive(Clone, Debug, PartialEq)]
-enum GateType {
+pub(crate) enum GateType {
     Add,
     Mult,
     R1CS,
@@ -252,15 +770,15 @@ enum GateType {
 #[allow(non_snake_case)]
 #[derive(Clone)]
 pub struct Gate<T> {
-    degree: Option<u64>,
-    gate_type: GateType,
-    id: Option<u64>,
-    input_id: Option<[u64; 2]>,           //input id
-    input_id_R1CS: Option<[Vec<u64>; 2]>, //input ids
+    pub(crate) degree: Option<u64>,
+    pub(crate) gate_type: GateType,
+    pub(crate) id: Option<u64>,
+    pub(crate) input_id: Option<[u64; 2]>,           //input id
+    pub(crate) input_id_R1CS: Option<[Vec<u64>; 2]>, //input ids
     input: Option<[T; 2]>,
     input_R1CS: Option<[Vec<T>; 2]>,
-    R1CS_weights: Option<[Vec<T>; 2]>,
-    output: Option<T>, //output value
+    pub(crate) R1CS_weights: Option<[Vec<T>; 2]>,
+    pub(crate) output: Option<T>, //output value
 }
 
 impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> Gate<T> {
@@ -411,6 +929,77 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::De
         }
     }
 
+    /// Reads the inputs of the gate from the outputs of the previous layer, indexed by id
+    fn set_input_from(&mut self, previous: &HashMap<u64, T>) {
+        match self.gate_type {
+            GateType::Add | GateType::Mult => {
+                let input_id = self.input_id.unwrap();
+                self.input = Some([previous[&input_id[0]], previous[&input_id[1]]]);
+            }
+            GateType::R1CS => {
+                let input_id_r1cs = self.input_id_R1CS.as_ref().unwrap();
+                self.input_R1CS = Some([
+                    input_id_r1cs[0].iter().map(|id| previous[id]).collect(),
+                    input_id_r1cs[1].iter().map(|id| previous[id]).collect(),
+                ]);
+            }
+        }
+    }
+
+    /// Output of the gate given the outputs of the previous layer, without storing anything.
+    /// Returns the first input id missing from `previous` instead if there is one.
+    pub(crate) fn compute(&self, previous: &HashMap<u64, T>) -> Result<T, u64> {
+        let read = |id: &u64| previous.get(id).copied().ok_or(*id);
+        match self.gate_type {
+            GateType::Add => {
+                let input_id = self.input_id.unwrap();
+                Ok(read(&input_id[0])? + read(&input_id[1])?)
+            }
+            GateType::Mult => {
+                let input_id = self.input_id.unwrap();
+                Ok(read(&input_id[0])? * read(&input_id[1])?)
+            }
+            GateType::R1CS => {
+                let input_id_r1cs = self.input_id_R1CS.as_ref().unwrap();
+                let weights = self.R1CS_weights.as_ref().unwrap();
+                let side = |i: usize| -> Result<T, u64> {
+                    let terms = weights[i]
+                        .iter()
+                        .zip(&input_id_r1cs[i])
+                        .map(|(weight, id)| read(id).map(|x| *weight * x))
+                        .collect::<Result<Vec<T>, u64>>()?;
+                    Ok(terms.into_iter().sum())
+                };
+                Ok(side(0)? * side(1)?)
+            }
+        }
+    }
+
+    /// Two gates have the same wiring if they compute the same function of the same ids
+    fn same_wiring(&self, other: &Gate<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        self.gate_type == other.gate_type
+            && self.id == other.id
+            && self.input_id == other.input_id
+            && self.input_id_R1CS == other.input_id_R1CS
+            && self.R1CS_weights == other.R1CS_weights
+    }
+
+    fn remapped(&self, map_out: &impl Fn(u64) -> u64, map_in: &impl Fn(u64) -> u64) -> Gate<T> {
+        let mut gate = self.clone();
+        gate.id = self.id.map(map_out);
+        gate.input_id = self.input_id.map(|[id0, id1]| [map_in(id0), map_in(id1)]);
+        gate.input_id_R1CS = self.input_id_R1CS.as_ref().map(|[ids0, ids1]| {
+            [
+                ids0.iter().map(|id| map_in(*id)).collect(),
+                ids1.iter().map(|id| map_in(*id)).collect(),
+            ]
+        });
+        gate
+    }
+
     pub fn get_output(&mut self) -> T {
         self.evaluate();
         self.output.unwrap()
@@ -0,0 +1,200 @@
+use crate::utils::*;
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// Structural problem found by `LAC::validate`. `position` is the index of the layer, which
+/// should have degree `position + 1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    MissingBasicValue {
+        id: u64,
+    },
+    MissingDegree {
+        position: usize,
+    },
+    WrongLayerDegree {
+        position: usize,
+        degree: u64,
+    },
+    WrongGateDegree {
+        position: usize,
+        id: u64,
+        degree: Option<u64>,
+    },
+    MissingGateInputs {
+        position: usize,
+        id: u64,
+    },
+    WeightsLength {
+        position: usize,
+        id: u64,
+    },
+    MissingWire {
+        position: usize,
+        id: u64,
+        input_id: u64,
+    },
+    MissingInputWire {
+        name: String,
+        id: u64,
+    },
+    MissingOutputWire {
+        name: String,
+        id: u64,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::MissingBasicValue { id } => {
+                write!(f, "basic value {} has no value", id)
+            }
+            ValidationError::MissingDegree { position } => {
+                write!(f, "layer {} has no degree", position)
+            }
+            ValidationError::WrongLayerDegree { position, degree } => {
+                write!(f, "layer {} has degree {}", position, degree)
+            }
+            ValidationError::WrongGateDegree {
+                position,
+                id,
+                degree,
+            } => write!(
+                f,
+                "gate {} of layer {} has degree {:?}",
+                id, position, degree
+            ),
+            ValidationError::MissingGateInputs { position, id } => {
+                write!(f, "gate {} of layer {} has no input ids", id, position)
+            }
+            ValidationError::WeightsLength { position, id } => write!(
+                f,
+                "R1CS gate {} of layer {} has not one weight per input",
+                id, position
+            ),
+            ValidationError::MissingWire {
+                position,
+                id,
+                input_id,
+            } => write!(
+                f,
+                "gate {} of layer {} reads {} which the previous layer does not compute",
+                id, position, input_id
+            ),
+            ValidationError::MissingInputWire { name, id } => {
+                write!(f, "input group {} refers to missing value {}", name, id)
+            }
+            ValidationError::MissingOutputWire { name, id } => {
+                write!(f, "output group {} refers to missing gate {}", name, id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl<T: Add<Output = T> + Mul<Output = T> + Copy + std::iter::Sum + std::fmt::Debug> LAC<T> {
+    /// Checks that the circuit can be evaluated: layers have consecutive degrees, every gate
+    /// reads ids computed by the previous layer and every declared group exists.
+    /// Returns every problem found, sorted by layer and id.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors: Vec<ValidationError> = Vec::new();
+
+        for id in self.basic_layer.values.keys().sorted() {
+            if self.basic_layer.values[id].value.is_none() {
+                errors.push(ValidationError::MissingBasicValue { id: *id });
+            }
+        }
+
+        let mut previous: HashSet<u64> = self.basic_layer.values.keys().copied().collect();
+        for (position, layer) in self.layers.iter().enumerate() {
+            let expected_degree = position as u64 + 1;
+            match layer.degree {
+                None => errors.push(ValidationError::MissingDegree { position }),
+                Some(degree) if degree != expected_degree => {
+                    errors.push(ValidationError::WrongLayerDegree { position, degree })
+                }
+                _ => {}
+            }
+
+            for id in layer.gates.keys().sorted() {
+                let id = *id;
+                let gate = layer.gates[&id].borrow();
+                if gate.degree != Some(expected_degree) {
+                    errors.push(ValidationError::WrongGateDegree {
+                        position,
+                        id,
+                        degree: gate.degree,
+                    });
+                }
+                let input_ids: Vec<u64> = match gate.gate_type {
+                    GateType::Add | GateType::Mult => match gate.input_id {
+                        Some(input_id) => input_id.to_vec(),
+                        None => {
+                            errors.push(ValidationError::MissingGateInputs { position, id });
+                            continue;
+                        }
+                    },
+                    GateType::R1CS => match (&gate.input_id_R1CS, &gate.R1CS_weights) {
+                        (Some(input_id), Some(weights)) => {
+                            if input_id[0].len() != weights[0].len()
+                                || input_id[1].len() != weights[1].len()
+                            {
+                                errors.push(ValidationError::WeightsLength { position, id });
+                            }
+                            input_id[0]
+                                .iter()
+                                .chain(input_id[1].iter())
+                                .copied()
+                                .collect()
+                        }
+                        _ => {
+                            errors.push(ValidationError::MissingGateInputs { position, id });
+                            continue;
+                        }
+                    },
+                };
+                for input_id in input_ids.into_iter().sorted().dedup() {
+                    if !previous.contains(&input_id) {
+                        errors.push(ValidationError::MissingWire {
+                            position,
+                            id,
+                            input_id,
+                        });
+                    }
+                }
+            }
+            previous = layer.gates.keys().copied().collect();
+        }
+
+        for group in &self.inputs {
+            for id in group.get_ids() {
+                if !self.basic_layer.values.contains_key(id) {
+                    errors.push(ValidationError::MissingInputWire {
+                        name: group.get_name().to_string(),
+                        id: *id,
+                    });
+                }
+            }
+        }
+        for group in &self.outputs {
+            for id in group.get_ids() {
+                if !previous.contains(id) {
+                    errors.push(ValidationError::MissingOutputWire {
+                        name: group.get_name().to_string(),
+                        id: *id,
+                    });
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
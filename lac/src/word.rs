@@ -0,0 +1,81 @@
+//Word operations on bit_strings given by their ids, least significant bit first as in
+//integer.rs. They only rewire ids and add no gates: the returned ids are passed as inputs of
+//the next gadget, which reads them from its previous layer. Shifts fill with the constant
+//id 0, so that layer must hold the constant 0.
+
+/// Id of the constant 0 used to fill shifted words
+pub const ZERO_ID: u64 = 0;
+
+/// Rotates the word `ids` left by `n` bits, towards the most significant bit
+pub fn rotate_left_ids(ids: Vec<u64>, n: u64) -> Vec<u64> {
+    let size = ids.len();
+    if size == 0 {
+        return ids;
+    }
+    let n = n as usize % size;
+    (0..size).map(|i| ids[(i + size - n) % size]).collect()
+}
+
+/// Rotates the word `ids` right by `n` bits, towards the least significant bit
+pub fn rotate_right_ids(ids: Vec<u64>, n: u64) -> Vec<u64> {
+    let size = ids.len();
+    if size == 0 {
+        return ids;
+    }
+    let n = n as usize % size;
+    (0..size).map(|i| ids[(i + n) % size]).collect()
+}
+
+/// Shifts the word `ids` left by `n` bits filling with zeros, the top `n` bits are dropped
+pub fn shift_left_ids(ids: Vec<u64>, n: u64) -> Vec<u64> {
+    let n = n as usize;
+    (0..ids.len())
+        .map(|i| if i < n { ZERO_ID } else { ids[i - n] })
+        .collect()
+}
+
+/// Shifts the word `ids` right by `n` bits filling with zeros, the low `n` bits are dropped
+pub fn shift_right_ids(ids: Vec<u64>, n: u64) -> Vec<u64> {
+    let n = n as usize;
+    (0..ids.len())
+        .map(|i| {
+            if i + n < ids.len() {
+                ids[i + n]
+            } else {
+                ZERO_ID
+            }
+        })
+        .collect()
+}
+
+/// Reverses the order of the bytes of the word `ids`, whose length must be a multiple of 8
+pub fn byte_swap_ids(ids: Vec<u64>) -> Vec<u64> {
+    assert!(
+        ids.len().is_multiple_of(8),
+        "can not byte swap a word of {} bits",
+        ids.len()
+    );
+    ids.chunks(8).rev().flatten().copied().collect()
+}
+
+/// Splits `ids` into consecutive words of `w` bits, the length must be a multiple of `w`
+pub fn split_ids(ids: Vec<u64>, w: u64) -> Vec<Vec<u64>> {
+    assert!(
+        ids.len().is_multiple_of(w as usize),
+        "can not split {} ids into words of {} bits",
+        ids.len(),
+        w
+    );
+    ids.chunks(w as usize).map(|word| word.to_vec()).collect()
+}
+
+/// Lane `(x, y)` of a Keccak state of 25 lanes of `w` bits, stored at index x + 5y
+pub fn get_lane_ids(state_ids: Vec<u64>, x: u64, y: u64, w: u64) -> Vec<u64> {
+    let index = (x % 5 + 5 * (y % 5)) * w;
+    state_ids[(index as usize)..((index + w) as usize)].to_vec()
+}
+
+/// Concatenates `words`, the first word holds the least significant bits
+pub fn concat_ids(words: Vec<Vec<u64>>) -> Vec<u64> {
+    words.concat()
+}
@@ -1,3 +1,4 @@
+use crate::interface::*;
 use crate::utils::*;
 
 //OR gate implemented using XOR(x0,x1) = x0+x1-2*x0*x1
@@ -6,6 +7,7 @@ use crate::utils::*;
 //  layer1:    g_0=g_0+g_1    g_1=g_0*g_1
 //  layer2:       g_0=(g_0-2*g_1)*1
 
+///x0 and x1 are the input groups "x0" and "x1", other values are set with LAC::assign_inputs
 pub fn get_xor_lac_circuit(x0: i64, x1: i64) -> LAC<i64> {
     let mut lac = LAC::new();
 
@@ -16,6 +18,12 @@ pub fn get_xor_lac_circuit(x0: i64, x1: i64) -> LAC<i64> {
     let layers = vec![layer1, layer2];
     lac.append_layers(layers);
 
+    lac.declare_input("x0", Visibility::Private, vec![2])
+        .unwrap();
+    lac.declare_input("x1", Visibility::Private, vec![3])
+        .unwrap();
+    lac.declare_output("out", vec![2]).unwrap();
+
     lac
 }
 
@@ -117,7 +125,7 @@ fn get_xor_second_layer(in_ids: Vec<u64>, out_ids: Vec<u64>, degree: u64) -> Lay
 pub fn get_xor_as_layers(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Vec<Layer<i64>> {
     //let layer0 = get_xor_zero_layer(in_ids.clone(), in_ids.clone(), degree);
     let layer1 = get_xor_first_layer(in_ids.clone(), in_ids.clone(), degree);
-    let layer2 = get_xor_second_layer(in_ids.clone(), vec![out_id], degree);
+    let layer2 = get_xor_second_layer(in_ids.clone(), vec![out_id], degree + 1);
     let layers = vec![layer1, layer2];
     layers
 }
@@ -139,3 +147,45 @@ pub fn get_xor_bitstring_as_layers(
     }
     layers
 }
+
+//Single layer XOR using XOR(x0,x1) = (x0-x1)^2, it does not read any constant nor overwrite
+//its inputs:
+//  layer1:    g_out=(x0-x1)*(x0-x1)
+
+#[allow(non_snake_case)]
+pub fn get_xor_as_layer(in_ids: Vec<u64>, out_id: u64, degree: u64) -> Layer<i64> {
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(degree);
+
+    let mut gate: Gate<i64> = Gate::new_R1CS_gate();
+
+    let input_id_R1CS = Some([vec![in_ids[0], in_ids[1]], vec![in_ids[0], in_ids[1]]]);
+    let weights_R1CS = Some([vec![1, -1], vec![1, -1]]);
+    gate.set_all(
+        Some(degree),
+        Some(out_id),
+        None,
+        input_id_R1CS,
+        weights_R1CS,
+    );
+
+    layer.append_gate(gate);
+
+    layer
+}
+
+///XOR for bit_string, uses 1 layer
+pub fn get_xor_bitstring_as_layer(
+    in_ids0: Vec<u64>,
+    in_ids1: Vec<u64>,
+    out_ids: Vec<u64>,
+    degree: u64,
+) -> Layer<i64> {
+    let size = in_ids0.len();
+    let mut layer: Layer<i64> = Layer::new();
+    for i in 0..size {
+        let layer_xor_bit_i = get_xor_as_layer(vec![in_ids0[i], in_ids1[i]], out_ids[i], degree);
+        layer.merge_layer(layer_xor_bit_i);
+    }
+    layer
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::*;
+use lac::assertion::*;
+use lac::utils::*;
+use lac::xor::*;
+
+#[test]
+fn test_boolean_assertion() {
+    let inputs = [-2, -1, 0, 1, 2, 3];
+    let layer = get_boolean_assertion_as_layer((2..8).collect(), (10..16).collect(), 1);
+    let outputs = evaluate_layers(&inputs, vec![layer]);
+    for (i, x) in inputs.iter().enumerate() {
+        let check = outputs[&(10 + i as u64)];
+        assert_eq!(check == 0, *x == 0 || *x == 1);
+        assert!(check >= 0);
+    }
+}
+
+#[test]
+fn test_equality_assertion() {
+    let inputs = [5, 5, -3, 4, 0, 0];
+    let layer = get_equality_assertion_as_layer(vec![2, 4, 6], vec![3, 5, 7], vec![10, 11, 12], 1);
+    let outputs = evaluate_layers(&inputs, vec![layer]);
+    assert_eq!([outputs[&10], outputs[&11], outputs[&12]], [0, -7, 0]);
+}
+
+#[test]
+fn test_assertion_fold() {
+    // 3 - 3 cancels with equal coefficients, random ones catch it
+    let layer = get_assertion_fold_as_layer(vec![2, 3, 4], vec![1, 1, 1], 10, 1);
+    assert_eq!(evaluate_layers(&[3, -3, 0], vec![layer])[&10], 0);
+    let layer = get_assertion_fold_as_layer(vec![2, 3, 4], vec![17, 91, 4], 10, 1);
+    assert_eq!(
+        evaluate_layers(&[3, -3, 0], vec![layer])[&10],
+        17 * 3 - 91 * 3
+    );
+    let layer = get_assertion_fold_as_layer(vec![2, 3, 4], vec![17, 91, 4], 10, 1);
+    assert_eq!(evaluate_layers(&[0, 0, 0], vec![layer])[&10], 0);
+}
+
+#[test]
+fn test_assert_boolean_inputs() {
+    for (x0, x1) in [(0, 0), (0, 1), (1, 1), (2, 1), (1, -1)] {
+        let mut lac = get_xor_lac_circuit(x0, x1);
+        let layers_amount = lac.get_layers_amount();
+        let gates_amount = lac.get_gates_amount();
+        let id = lac.assert_boolean(vec![2, 3]).unwrap();
+        assert_eq!(lac.get_layers_amount(), layers_amount);
+        // 2 booleanity checks and the fold count 3 each, plus the carried 1
+        assert_eq!(lac.get_gates_amount(), gates_amount + 2 * 3 + 1 + 3);
+        assert_eq!(lac.validate(), Ok(()));
+
+        lac.evaluate();
+        let expected: Vec<u64> = if [x0, x1].iter().all(|x| *x == 0 || *x == 1) {
+            vec![]
+        } else {
+            vec![id]
+        };
+        assert_eq!(lac.get_failed_assertions(), expected);
+        let outputs = lac.get_named_outputs().unwrap();
+        assert_eq!(outputs[ASSERTIONS].len(), 1);
+    }
+}
+
+#[test]
+fn test_assert_boolean_twice() {
+    let mut lac = get_xor_lac_circuit(1, 3);
+    let id0 = lac.assert_boolean(vec![2]).unwrap();
+    let id1 = lac.assert_boolean(vec![3]).unwrap();
+    assert_ne!(id0, id1);
+    assert_eq!(
+        lac.get_output_group(ASSERTIONS).unwrap().get_ids(),
+        [id0, id1]
+    );
+    assert_eq!(lac.validate(), Ok(()));
+    lac.evaluate();
+    assert_eq!(lac.get_failed_assertions(), vec![id1]);
+}
+
+#[test]
+fn test_assert_boolean_single_layer() {
+    // a circuit without layers is padded to the 2 layers of the fold
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for (id, x) in [0, 1, 1, 5].iter().enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id as u64, *x);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    let id = lac.assert_boolean(vec![2, 3]).unwrap();
+    assert_eq!(lac.get_layers_amount(), 2);
+    assert_eq!(lac.validate(), Ok(()));
+    lac.evaluate();
+    assert_eq!(lac.get_failed_assertions(), vec![id]);
+}
+
+#[test]
+fn test_assert_checks_equality() {
+    // a single equality check can be negative, it is not folded with other checks
+    for (x0, x1) in [(1, 1), (0, 1), (1, 0)] {
+        let mut lac = get_xor_lac_circuit(x0, x1);
+        let check_id = lac.get_max_id() + 1;
+        let layer = get_equality_assertion_as_layer(vec![2], vec![3], vec![check_id], 1);
+        let id = lac.assert_checks(layer).unwrap();
+        assert_eq!(lac.validate(), Ok(()));
+        lac.evaluate();
+        let expected: Vec<u64> = if x0 == x1 { vec![] } else { vec![id] };
+        assert_eq!(lac.get_failed_assertions(), expected);
+    }
+}
@@ -1,6 +1,8 @@
+use lac::interface::*;
 use lac::or::*;
 use lac::utils::*;
 use lac::xor::*;
+use std::collections::HashMap;
 
 #[test]
 fn test_multiplication_gate() {
@@ -177,3 +179,337 @@ fn get_xor_bitstring_basic_layer(
 
     basic_layer
 }
+
+fn get_single_gate_lac(x0: i64, x1: i64, mult: bool) -> LAC<i64> {
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    let mut value0: Value<i64> = Value::new();
+    let mut value1: Value<i64> = Value::new();
+    let mut value2: Value<i64> = Value::new();
+    let mut value3: Value<i64> = Value::new();
+    value0.set_all(0, 0);
+    value1.set_all(1, 1);
+    value2.set_all(2, x0);
+    value3.set_all(3, x1);
+    basic_layer.append_values(vec![value0, value1, value2, value3]);
+    lac.set_basic_layer(basic_layer);
+
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(1);
+    layer.add_gate_0_and_1(1);
+    let mut gate: Gate<i64> = if mult {
+        Gate::new_mult_gate()
+    } else {
+        Gate::new_add_gate()
+    };
+    gate.set_all(Some(1), Some(2), Some([2, 3]), None, None);
+    layer.append_gate(gate);
+    lac.append_layer(layer);
+
+    lac
+}
+
+#[test]
+fn test_merge_lac_conflicting_gate() {
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let other = get_single_gate_lac(3, 5, true);
+    assert_eq!(
+        lac.merge_lac(other),
+        Err(MergeError::ConflictingGate { degree: 1, id: 2 })
+    );
+    assert_eq!(lac.evaluate(), vec![0, 1, 8]);
+}
+
+#[test]
+fn test_merge_lac_conflicting_value() {
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let other = get_single_gate_lac(4, 5, false);
+    assert_eq!(
+        lac.merge_lac(other),
+        Err(MergeError::ConflictingValue { id: 2 })
+    );
+}
+
+#[test]
+fn test_merge_lac_identical() {
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let other = get_single_gate_lac(3, 5, false);
+    assert_eq!(lac.merge_lac(other), Ok(()));
+    assert_eq!(lac.get_layers_amount(), 1);
+    assert_eq!(lac.evaluate(), vec![0, 1, 8]);
+}
+
+#[test]
+fn test_merge_lac_remapped() {
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let other = get_single_gate_lac(6, 7, true);
+    let remap = lac.merge_lac_remapped(other).unwrap();
+    assert_eq!(remap.map(0), 0);
+    assert_eq!(remap.map(1), 1);
+    assert_eq!(remap.map(2), 4);
+    assert_eq!(lac.get_max_id(), 5);
+    assert_eq!(lac.evaluate(), vec![0, 1, 8, 42]);
+}
+
+#[test]
+fn test_last_layer_ids() {
+    // the basic value 3 is not relayed by the layer
+    let lac = get_single_gate_lac(3, 5, false);
+    assert_eq!(lac.get_last_layer_ids(), vec![0, 1, 2]);
+
+    let mut lac: LAC<i64> = LAC::new();
+    assert_eq!(lac.get_last_layer_ids(), Vec::<u64>::new());
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for id in [3, 0, 1] {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id, 1);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    assert_eq!(lac.get_last_layer_ids(), vec![0, 1, 3]);
+}
+
+#[test]
+fn test_merge_lac_pads_shallower_circuit() {
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(2);
+    layer.add_gate_0_and_1(2);
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(Some(2), Some(2), Some([2, 2]), None, None);
+    layer.append_gate(gate);
+    lac.append_layer(layer);
+
+    let other = get_single_gate_lac(6, 7, true);
+    let remap = lac.merge_lac_remapped(other).unwrap();
+    assert_eq!(lac.get_layers_amount(), 2);
+    assert_eq!(remap.map(2), 4);
+    assert_eq!(lac.evaluate(), vec![0, 1, 64, 42]);
+
+    let mut shallow = get_single_gate_lac(6, 7, true);
+    let deep = lac.remapped(&IdRemap::new(10));
+    shallow.merge_lac(deep).unwrap();
+    assert_eq!(shallow.get_layers_amount(), 2);
+    assert_eq!(shallow.evaluate(), vec![0, 1, 42, 64, 42]);
+}
+
+#[test]
+fn test_merge_lac_padding_needs_constant() {
+    let deep = get_single_gate_lac(3, 5, false);
+    let mut lac = get_single_gate_lac(6, 7, true);
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(2);
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(Some(2), Some(2), Some([2, 2]), None, None);
+    layer.append_gate(gate);
+    lac.append_layer(layer);
+
+    // the last layer of deep holds gate 0, its relays can pad it to 3 layers
+    let mut padded = deep.clone();
+    assert_eq!(padded.pad_to_layers_amount(3), Ok(()));
+    assert_eq!(padded.evaluate(), vec![0, 1, 8]);
+
+    // the last layer of lac only holds gate 2, relays would read a missing gate 0
+    let mut shallow = lac.clone();
+    assert_eq!(
+        shallow.pad_to_layers_amount(3),
+        Err(MergeError::MissingConstant { degree: 2 })
+    );
+    let mut deeper = deep.clone();
+    deeper.pad_to_layers_amount(3).unwrap();
+    assert_eq!(
+        lac.merge_lac(deeper),
+        Err(MergeError::MissingConstant { degree: 2 })
+    );
+    assert_eq!(lac.get_layers_amount(), 2);
+
+    // gate 0 of the last layer is a value, relays would add it to every gate they copy
+    let mut lac = deep.clone();
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(2);
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(Some(2), Some(0), Some([2, 2]), None, None);
+    layer.append_gate(gate);
+    layer.copy_gates_by_ids(vec![1, 2]);
+    lac.append_layer(layer);
+    assert_eq!(
+        lac.pad_to_layers_amount(3),
+        Err(MergeError::MissingConstant { degree: 2 })
+    );
+
+    // the constant 0 relayed from a basic value 0 that is not 0
+    let mut lac = get_single_gate_lac(3, 5, false);
+    let mut basic_layer = lac.get_basic_layer().clone();
+    let mut value: Value<i64> = Value::new();
+    value.set_all(0, 4);
+    basic_layer.append_value(value);
+    lac.set_basic_layer(basic_layer);
+    assert_eq!(
+        lac.pad_to_layers_amount(2),
+        Err(MergeError::MissingConstant { degree: 0 })
+    );
+
+    // an empty circuit has nothing to relay
+    let mut empty: LAC<i64> = LAC::new();
+    assert_eq!(empty.merge_lac(padded), Ok(()));
+    assert_eq!(empty.get_layers_amount(), 3);
+}
+
+fn get_named_xor_bitstring_lac() -> LAC<i64> {
+    let in_ids0: Vec<u64> = vec![2, 3, 4];
+    let in_ids1: Vec<u64> = vec![5, 6, 7];
+    let out_ids: Vec<u64> = vec![2, 3, 4];
+    let mut lac: LAC<i64> = LAC::new();
+    let basic_layer =
+        get_xor_bitstring_basic_layer(vec![0; 3], vec![0; 3], in_ids0.clone(), in_ids1.clone());
+    lac.set_basic_layer(basic_layer);
+    lac.append_layers(get_xor_bitstring_as_layers(
+        in_ids0.clone(),
+        in_ids1.clone(),
+        out_ids.clone(),
+        1,
+    ));
+    lac.declare_input("a", Visibility::Public, in_ids0).unwrap();
+    lac.declare_input("b", Visibility::Private, in_ids1)
+        .unwrap();
+    lac.declare_output("a_xor_b", out_ids).unwrap();
+    lac
+}
+
+#[test]
+fn test_named_inputs_and_outputs() {
+    let mut lac = get_named_xor_bitstring_lac();
+    assert_eq!(lac.get_input_size(), 6);
+    assert_eq!(lac.get_input_ids(Visibility::Public), vec![2, 3, 4]);
+    assert_eq!(lac.get_input_ids(Visibility::Private), vec![5, 6, 7]);
+
+    let inputs = HashMap::from([("a", vec![1, 1, 0]), ("b", vec![0, 1, 1])]);
+    let outputs = lac.evaluate_named(&inputs).unwrap();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs["a_xor_b"], vec![1, 0, 1]);
+
+    let inputs = HashMap::from([("a", vec![0, 1, 0]), ("b", vec![0, 0, 0])]);
+    let outputs = lac.evaluate_named(&inputs).unwrap();
+    assert_eq!(outputs["a_xor_b"], vec![0, 1, 0]);
+}
+
+#[test]
+fn test_named_inputs_errors() {
+    let mut lac = get_named_xor_bitstring_lac();
+    assert_eq!(
+        lac.declare_input("a", Visibility::Private, vec![8]),
+        Err(InterfaceError::DuplicateName("a".to_string()))
+    );
+    assert_eq!(
+        lac.evaluate_named(&HashMap::from([("a", vec![1, 1, 0])])),
+        Err(InterfaceError::MissingInput("b".to_string()))
+    );
+    assert_eq!(
+        lac.evaluate_named(&HashMap::from([("a", vec![1, 1, 0]), ("c", vec![1])])),
+        Err(InterfaceError::UnknownInput("c".to_string()))
+    );
+    assert_eq!(
+        lac.set_input("b", &[1, 0]),
+        Err(InterfaceError::WrongLength {
+            name: "b".to_string(),
+            expected: 3,
+            got: 2
+        })
+    );
+
+    lac.declare_output("constants", vec![0, 1]).unwrap();
+    assert_eq!(
+        lac.evaluate_named(&HashMap::from([("a", vec![0; 3]), ("b", vec![0; 3])])),
+        Err(InterfaceError::MissingOutputWire {
+            name: "constants".to_string(),
+            id: 0
+        })
+    );
+}
+
+#[test]
+fn test_named_groups_follow_remapped_merge() {
+    let mut lac = get_named_xor_bitstring_lac();
+    let mut other = get_single_gate_lac(6, 7, true);
+    other
+        .declare_input("x", Visibility::Public, vec![2, 3])
+        .unwrap();
+    other.declare_output("product", vec![2]).unwrap();
+    let remap = lac.merge_lac_remapped(other.clone()).unwrap();
+    assert_eq!(
+        lac.get_input_group("x").unwrap().get_ids(),
+        &remap.map_ids(&[2, 3])[..]
+    );
+
+    let inputs = HashMap::from([
+        ("a", vec![1, 0, 0]),
+        ("b", vec![1, 1, 0]),
+        ("x", vec![3, 4]),
+    ]);
+    let outputs = lac.evaluate_named(&inputs).unwrap();
+    assert_eq!(outputs["a_xor_b"], vec![0, 1, 0]);
+    assert_eq!(outputs["product"], vec![12]);
+
+    assert_eq!(
+        lac.merge_lac_remapped(other),
+        Err(MergeError::ConflictingGroup {
+            name: "x".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_assign_inputs() {
+    // one circuit evaluated with every witness
+    let mut lac = get_xor_lac_circuit(0, 0);
+    let mut or = get_or_lac_circuit(0, 0);
+    for (x0, x1) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let mut assignment: Assignment<i64> = Assignment::new();
+        assignment.set("x0", vec![x0]);
+        assignment.set("x1", vec![x1]);
+        lac.assign_inputs(&assignment).unwrap();
+        lac.evaluate();
+        assert_eq!(lac.get_named_outputs().unwrap()["out"], vec![x0 ^ x1]);
+        or.assign_inputs(&assignment).unwrap();
+        or.evaluate();
+        assert_eq!(or.get_named_outputs().unwrap()["out"], vec![x0 | x1]);
+    }
+}
+
+#[test]
+fn test_assign_inputs_errors() {
+    // a failed assignment changes no value
+    let mut lac = get_named_xor_bitstring_lac();
+    let mut assignment: Assignment<i64> = Assignment::new();
+    assignment.set("a", vec![1, 1, 0]);
+    assignment.set("b", vec![0, 1, 1]);
+    lac.assign_inputs(&assignment).unwrap();
+
+    let mut wrong = assignment.clone();
+    wrong.set("a", vec![0, 0, 0]);
+    wrong.set("b", vec![1, 0]);
+    assert_eq!(
+        lac.assign_inputs(&wrong),
+        Err(InterfaceError::WrongLength {
+            name: "b".to_string(),
+            expected: 3,
+            got: 2
+        })
+    );
+    let mut missing: Assignment<i64> = Assignment::new();
+    missing.set("a", vec![0, 0, 0]);
+    assert_eq!(
+        lac.assign_inputs(&missing),
+        Err(InterfaceError::MissingInput("b".to_string()))
+    );
+    let mut unknown = assignment.clone();
+    unknown.set("c", vec![1]);
+    assert_eq!(
+        lac.assign_inputs(&unknown),
+        Err(InterfaceError::UnknownInput("c".to_string()))
+    );
+    assert_eq!(unknown.get_names(), vec!["a", "b", "c"]);
+
+    lac.evaluate();
+    assert_eq!(lac.get_named_outputs().unwrap()["a_xor_b"], vec![1, 0, 1]);
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::*;
+use lac::builder::*;
+
+#[test]
+fn test_wires_are_relayed_to_their_readers() {
+    //x=2, y=3, z=5 as the basic values 2, 3, 4
+    let mut builder = CircuitBuilder::new(1, 100);
+    let xy = builder.mult(2, 3);
+    let xyz = builder.r1cs(&[(xy, 1)], &[(4, 1), (1, 1)]);
+    let sum = builder.linear(&[(xyz, 1), (2, 10), (xy, -1)]);
+    assert_eq!(builder.get_level(sum), 3);
+    let layers = builder.finish(&[sum, 3]);
+    assert_eq!(layers.len(), 3);
+
+    let outputs = evaluate_layers(&[2, 3, 5], layers);
+    assert_eq!(outputs[&sum], 2 * 3 * 6 + 20 - 6);
+    assert_eq!(outputs[&3], 3);
+    assert_eq!((outputs[&0], outputs[&1]), (0, 1));
+}
+
+#[test]
+fn test_select_window() {
+    let bytes: Vec<i64> = vec![0xc2, 0x80, 0xa0, 7, 9, 11];
+    let size = bytes.len();
+    let values: Vec<Combination> = (0..size).map(|i| vec![(2 + i as u64, 1)]).collect();
+    let selector_ids: Vec<u64> = ((2 + size as u64)..(2 + 2 * size as u64)).collect();
+    for position in 0..size {
+        let mut builder = CircuitBuilder::new(1, 100);
+        let selected = builder.select(&selector_ids, &values);
+        let window = builder.select_window(&selector_ids, &values, 3);
+        builder.assert_one_hot(&selector_ids, &[(1, position as i64)]);
+        let check = builder.fold_checks();
+        let layers = builder.finish(&[&[selected, check], &window[..]].concat());
+
+        let inputs = [bytes.clone(), get_one_hot(position, size)].concat();
+        let outputs = evaluate_layers(&inputs, layers);
+        assert_eq!(outputs[&selected], bytes[position]);
+        for (k, id) in window.iter().enumerate() {
+            assert_eq!(outputs[id], *bytes.get(position + k).unwrap_or(&0));
+        }
+        assert_eq!(outputs[&check], 0);
+    }
+}
+
+#[test]
+fn test_one_hot_assertion_fails() {
+    let selector_ids: Vec<u64> = (2..6).collect();
+    let selectors = [
+        vec![0, 0, 1, 0],
+        vec![0, 1, 0, 0],
+        vec![0, 0, 0, 0],
+        vec![0, 1, 1, 0],
+        vec![1, -1, 1, 0],
+        vec![0, 2, 0, 0],
+    ];
+    for (i, selector) in selectors.iter().enumerate() {
+        let mut builder = CircuitBuilder::new(1, 100);
+        builder.assert_one_hot(&selector_ids, &[(6, 1)]);
+        let check = builder.fold_checks();
+        let layers = builder.finish(&[check]);
+        let outputs = evaluate_layers(&[selector.clone(), vec![2]].concat(), layers);
+        assert_eq!(outputs[&check] == 0, i == 0, "selector {:?}", selector);
+    }
+}
+
+#[test]
+fn test_prefix_mask_assertion() {
+    let mask_ids: Vec<u64> = (2..7).collect();
+    let masks = [
+        (vec![1, 1, 1, 0, 0], 3, true),
+        (vec![0, 0, 0, 0, 0], 0, true),
+        (vec![1, 1, 1, 1, 1], 5, true),
+        (vec![1, 1, 1, 0, 0], 2, false),
+        (vec![1, 0, 1, 0, 0], 2, false),
+        (vec![2, 0, 0, 0, 0], 2, false),
+    ];
+    for (mask, length, holds) in masks {
+        let mut builder = CircuitBuilder::new(1, 100);
+        builder.assert_prefix_mask(&mask_ids, &[(7, 1)]);
+        let check = builder.fold_checks();
+        let layers = builder.finish(&[check]);
+        let outputs = evaluate_layers(&[mask.clone(), vec![length]].concat(), layers);
+        assert_eq!(outputs[&check] == 0, holds, "mask {:?}", mask);
+    }
+}
+
+#[test]
+fn test_builder_after_layers() {
+    //a second builder reads the last layer of the first one
+    let mut builder = CircuitBuilder::new(1, 100);
+    let x = builder.mult(2, 2);
+    let mut layers = builder.finish(&[x]);
+
+    let mut builder = CircuitBuilder::new(2, 200);
+    builder.assert_zero(&[(x, 1), (1, -49)]);
+    let check = builder.fold_checks();
+    layers.extend(builder.finish(&[check]));
+    assert_eq!(evaluate_layers(&[7], layers.clone())[&check], 0);
+    assert_ne!(evaluate_layers(&[6], layers)[&check], 0);
+}
@@ -0,0 +1,33 @@
+//Fixture shared by the tests of the gadgets: a circuit whose basic layer holds the constants 0
+//and 1 followed by the inputs from id 2.
+#![allow(dead_code)]
+
+use lac::utils::*;
+use std::collections::HashMap;
+
+/// Circuit with no layer whose basic layer holds 0, 1 and `inputs`
+pub fn get_lac(inputs: &[i64]) -> LAC<i64> {
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for (id, x) in [0, 1].iter().chain(inputs).enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id as u64, *x);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    lac
+}
+
+/// Checks that the circuit is valid and returns the outputs of its last layer
+pub fn evaluate(mut lac: LAC<i64>) -> HashMap<u64, i64> {
+    assert_eq!(lac.validate(), Ok(()));
+    lac.get_trace().pop().unwrap()
+}
+
+/// Evaluates `layers` on a basic layer holding 0, 1 and `inputs`, checking that the circuit is
+/// valid, and returns the outputs of the last layer
+pub fn evaluate_layers(inputs: &[i64], layers: Vec<Layer<i64>>) -> HashMap<u64, i64> {
+    let mut lac = get_lac(inputs);
+    lac.append_layers(layers);
+    evaluate(lac)
+}
@@ -0,0 +1,144 @@
+use lac::interface::*;
+use lac::serialize::*;
+use lac::utils::*;
+use lac::validate::*;
+use lac::xor::*;
+use std::collections::HashMap;
+
+fn get_xor_lac(bits: usize) -> LAC<i64> {
+    let in_ids0: Vec<u64> = (2..(2 + bits as u64)).collect();
+    let in_ids1: Vec<u64> = ((2 + bits as u64)..(2 + 2 * bits as u64)).collect();
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for id in 0..(2 + 2 * bits as u64) {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id, if id == 1 { 1 } else { 0 });
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    lac.append_layers(get_xor_bitstring_as_layers(
+        in_ids0.clone(),
+        in_ids1.clone(),
+        in_ids0.clone(),
+        1,
+    ));
+    lac.declare_input("a", Visibility::Public, in_ids0.clone())
+        .unwrap();
+    lac.declare_input("b", Visibility::Private, in_ids1)
+        .unwrap();
+    lac.declare_output("a_xor_b", in_ids0).unwrap();
+    lac
+}
+
+#[test]
+fn test_serialize_roundtrip() {
+    let lac = get_xor_lac(3);
+    let bytes = lac.to_bytes();
+    let mut decoded = LAC::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(decoded.get_layers_amount(), 2);
+    assert_eq!(decoded.get_gates_amount(), lac.get_gates_amount());
+    assert_eq!(decoded.get_input_groups(), lac.get_input_groups());
+    assert_eq!(decoded.get_output_groups(), lac.get_output_groups());
+    assert_eq!(decoded.validate(), Ok(()));
+
+    let inputs = HashMap::from([("a", vec![1, 0, 1]), ("b", vec![1, 1, 0])]);
+    let outputs = decoded.evaluate_named(&inputs).unwrap();
+    assert_eq!(outputs["a_xor_b"], vec![0, 1, 1]);
+}
+
+#[test]
+fn test_deserialize_errors() {
+    let bytes = get_xor_lac(2).to_bytes();
+    assert_eq!(
+        LAC::from_bytes(b"NOPE\x01\0\0\0").err(),
+        Some(DecodeError::InvalidMagic)
+    );
+    assert_eq!(
+        LAC::from_bytes(&bytes[..bytes.len() - 1]).err(),
+        Some(DecodeError::UnexpectedEnd)
+    );
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        LAC::from_bytes(&trailing).err(),
+        Some(DecodeError::TrailingBytes)
+    );
+    let mut version = bytes;
+    version[4] = 9;
+    assert_eq!(
+        LAC::from_bytes(&version).err(),
+        Some(DecodeError::UnsupportedVersion(9))
+    );
+}
+
+#[test]
+fn test_validate_reports_broken_wiring() {
+    let mut lac = get_xor_lac(1);
+    let mut layer: Layer<i64> = Layer::new();
+    layer.set_degree(4);
+    let mut gate: Gate<i64> = Gate::new_mult_gate();
+    gate.set_all(Some(4), Some(7), Some([2, 9]), None, None);
+    layer.append_gate(gate);
+    lac.append_layer(layer);
+
+    assert_eq!(
+        lac.validate(),
+        Err(vec![
+            ValidationError::WrongLayerDegree {
+                position: 2,
+                degree: 4
+            },
+            ValidationError::WrongGateDegree {
+                position: 2,
+                id: 7,
+                degree: Some(4)
+            },
+            ValidationError::MissingWire {
+                position: 2,
+                id: 7,
+                input_id: 9
+            },
+            ValidationError::MissingOutputWire {
+                name: "a_xor_b".to_string(),
+                id: 2
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_export_json_and_dot() {
+    let lac = get_xor_lac(1);
+    let json = lac.to_json();
+    assert!(json.starts_with("{\"basic_layer\":[{\"id\":0,\"value\":0},{\"id\":1,\"value\":1},"));
+    assert!(json.contains("{\"id\":3,\"type\":\"mult\",\"inputs\":[2, 3]}"));
+    assert!(json.contains(
+        "{\"id\":2,\"type\":\"r1cs\",\"inputs\":[[2, 3], [1]],\"weights\":[[1, -2], [1]]}"
+    ));
+    assert!(json.ends_with(
+        "\"inputs\":[{\"name\":\"a\",\"visibility\":\"public\",\"ids\":[2]},{\"name\":\"b\",\"visibility\":\"private\",\"ids\":[3]}],\"outputs\":[{\"name\":\"a_xor_b\",\"ids\":[2]}]}"
+    ));
+
+    let dot = lac.to_dot();
+    assert!(dot.starts_with("digraph lac {"));
+    assert!(dot.contains("    L1_3 [label=\"* 3\"];"));
+    assert!(dot.contains("  L1_3 -> L2_2;"));
+}
+
+#[test]
+fn test_export_r1cs() {
+    let lac = get_xor_lac(1);
+    let r1cs = lac.to_r1cs_json();
+    //one, output, a, b, then gates 0, 1, 2, 3 of layer 1
+    assert!(r1cs.starts_with(
+        "{\"n_variables\":8,\"n_outputs\":1,\"n_public_inputs\":1,\"n_private_inputs\":1,"
+    ));
+    //layer 1: 0 = 0 + 0, 1 = 0 + 1, 2 = a + b, 3 = a * b
+    assert!(r1cs.contains("[{},{\"0\":1},{\"4\":1}]"));
+    assert!(r1cs.contains("[{\"0\":1},{\"0\":1},{\"5\":1}]"));
+    assert!(r1cs.contains("[{\"2\":1,\"3\":1},{\"0\":1},{\"6\":1}]"));
+    assert!(r1cs.contains("[{\"2\":1},{\"3\":1},{\"7\":1}]"));
+    //layer 2: out = (g2 - 2 g3) * g1
+    assert!(r1cs.contains("[{\"6\":1,\"7\":-2},{\"5\":1},{\"1\":1}]"));
+}
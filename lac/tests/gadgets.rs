@@ -0,0 +1,222 @@
+mod common;
+
+use common::*;
+use lac::adder::*;
+use lac::majority::*;
+use lac::mux::*;
+use lac::nand::*;
+use lac::nor::*;
+use lac::or::*;
+use lac::utils::*;
+use lac::xnor::*;
+use lac::xor::*;
+
+/// Every assignment of `n` bits
+fn get_all_bits(n: usize) -> Vec<Vec<i64>> {
+    (0..(1 << n))
+        .map(|x: usize| (0..n).map(|i| ((x >> i) & 1) as i64).collect())
+        .collect()
+}
+
+#[test]
+fn test_nand() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, get_nand_as_layers(vec![2, 3], 4, 1));
+        assert_eq!(outputs[&4], 1 - x[0] * x[1]);
+    }
+}
+
+#[test]
+fn test_nor() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, vec![get_nor_as_layer(vec![2, 3], 4, 1)]);
+        assert_eq!(outputs[&4], 1 - (x[0] | x[1]));
+    }
+}
+
+#[test]
+fn test_or() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, get_or_as_layers(vec![2, 3], 4, 1));
+        assert_eq!(outputs[&4], x[0] | x[1]);
+    }
+}
+
+#[test]
+fn test_xnor() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, vec![get_xnor_as_layer(vec![2, 3], 4, 1)]);
+        assert_eq!(outputs[&4], 1 - (x[0] ^ x[1]));
+    }
+}
+
+#[test]
+fn test_xor() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, vec![get_xor_as_layer(vec![2, 3], 4, 1)]);
+        assert_eq!(outputs[&4], x[0] ^ x[1]);
+    }
+}
+
+#[test]
+fn test_mux() {
+    for x in get_all_bits(3) {
+        let outputs = evaluate_layers(&x, get_mux_as_layers(2, vec![3, 4], 5, 1));
+        let expected = if x[0] == 0 { x[1] } else { x[2] };
+        assert_eq!(outputs[&5], expected);
+    }
+}
+
+#[test]
+fn test_mux_in_place() {
+    for x in get_all_bits(3) {
+        let expected = if x[0] == 0 { x[1] } else { x[2] };
+        let outputs = evaluate_layers(&x, get_mux_as_layers(2, vec![3, 4], 3, 1));
+        assert_eq!(outputs[&3], expected);
+        let outputs = evaluate_layers(&x, get_mux_as_layers(2, vec![3, 4], 4, 1));
+        assert_eq!(outputs[&4], expected);
+    }
+}
+
+#[test]
+fn test_majority() {
+    for x in get_all_bits(3) {
+        let outputs = evaluate_layers(&x, get_majority_as_layers(vec![2, 3, 4], 5, 1));
+        let expected = if x[0] + x[1] + x[2] >= 2 { 1 } else { 0 };
+        assert_eq!(outputs[&5], expected);
+    }
+}
+
+#[test]
+fn test_half_adder() {
+    for x in get_all_bits(2) {
+        let outputs = evaluate_layers(&x, vec![get_half_adder_as_layer(vec![2, 3], vec![4, 5], 1)]);
+        assert_eq!(outputs[&4] + 2 * outputs[&5], x[0] + x[1]);
+    }
+}
+
+#[test]
+fn test_full_adder() {
+    for x in get_all_bits(3) {
+        let outputs = evaluate_layers(&x, get_full_adder_as_layers(vec![2, 3, 4], vec![5, 6], 1));
+        assert_eq!(outputs[&5] + 2 * outputs[&6], x[0] + x[1] + x[2]);
+    }
+}
+
+#[test]
+fn test_gadgets_cost() {
+    let count = |layers: &Vec<Layer<i64>>| -> (usize, usize) {
+        let gates = layers.iter().map(|layer| layer.gates_amount()).sum();
+        (layers.len(), gates)
+    };
+    // constants 0 and 1 are counted as gates
+    assert_eq!(count(&get_nand_as_layers(vec![2, 3], 4, 1)), (2, 4));
+    assert_eq!(count(&get_or_as_layers(vec![2, 3], 4, 1)), (2, 4));
+    assert_eq!(count(&vec![get_nor_as_layer(vec![2, 3], 4, 1)]), (1, 1));
+    assert_eq!(count(&vec![get_xnor_as_layer(vec![2, 3], 4, 1)]), (1, 1));
+    assert_eq!(count(&vec![get_xor_as_layer(vec![2, 3], 4, 1)]), (1, 1));
+    assert_eq!(count(&get_mux_as_layers(2, vec![3, 4], 5, 1)), (2, 3));
+    assert_eq!(count(&get_majority_as_layers(vec![2, 3, 4], 5, 1)), (2, 3));
+    let half_adder = vec![get_half_adder_as_layer(vec![2, 3], vec![4, 5], 1)];
+    assert_eq!(count(&half_adder), (1, 2));
+    let full_adder = get_full_adder_as_layers(vec![2, 3, 4], vec![5, 6], 1);
+    assert_eq!(count(&full_adder), (2, 5));
+}
+
+#[test]
+fn test_bitstring_gadgets() {
+    // three bit words, bit i of x, y and z are the inputs of column i of the truth table
+    for inputs in get_all_bits(3) {
+        let x = [inputs[0]; 3];
+        let y = [inputs[1]; 3];
+        let z = [inputs[2]; 3];
+        let words: Vec<i64> = x.iter().chain(&y).chain(&z).copied().collect();
+        let (x_ids, y_ids, z_ids) = (vec![2, 3, 4], vec![5, 6, 7], vec![8, 9, 10]);
+        let out_ids: Vec<u64> = vec![11, 12, 13];
+        let carry_ids: Vec<u64> = vec![14, 15, 16];
+
+        let or = get_or_bitstring_as_layers(x_ids.clone(), y_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, or);
+        assert!(out_ids
+            .iter()
+            .all(|id| outputs[id] == inputs[0] | inputs[1]));
+
+        let nand = get_nand_bitstring_as_layers(x_ids.clone(), y_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, nand);
+        assert!(out_ids
+            .iter()
+            .all(|id| outputs[id] == 1 - (inputs[0] & inputs[1])));
+
+        let nor = get_nor_bitstring_as_layer(x_ids.clone(), y_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, vec![nor]);
+        assert!(out_ids
+            .iter()
+            .all(|id| outputs[id] == 1 - (inputs[0] | inputs[1])));
+
+        let xnor = get_xnor_bitstring_as_layer(x_ids.clone(), y_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, vec![xnor]);
+        assert!(out_ids
+            .iter()
+            .all(|id| outputs[id] == 1 - (inputs[0] ^ inputs[1])));
+
+        let xor = get_xor_bitstring_as_layer(x_ids.clone(), y_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, vec![xor]);
+        assert!(out_ids
+            .iter()
+            .all(|id| outputs[id] == inputs[0] ^ inputs[1]));
+
+        let mux = get_mux_bitstring_as_layers(2, y_ids.clone(), z_ids.clone(), out_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, mux);
+        let expected = if inputs[0] == 0 { inputs[1] } else { inputs[2] };
+        assert!(out_ids.iter().all(|id| outputs[id] == expected));
+        let mux = get_mux_bitstring_as_layers(2, y_ids.clone(), z_ids.clone(), y_ids.clone(), 1);
+        let outputs = evaluate_layers(&words, mux);
+        assert!(y_ids.iter().all(|id| outputs[id] == expected));
+
+        let majority = get_majority_bitstring_as_layers(
+            x_ids.clone(),
+            y_ids.clone(),
+            z_ids.clone(),
+            out_ids.clone(),
+            1,
+        );
+        let outputs = evaluate_layers(&words, majority);
+        let expected = if inputs.iter().sum::<i64>() >= 2 {
+            1
+        } else {
+            0
+        };
+        assert!(out_ids.iter().all(|id| outputs[id] == expected));
+
+        let half_adder = get_half_adder_bitstring_as_layer(
+            x_ids.clone(),
+            y_ids.clone(),
+            out_ids.clone(),
+            carry_ids.clone(),
+            1,
+        );
+        let outputs = evaluate_layers(&words, vec![half_adder]);
+        for i in 0..3 {
+            assert_eq!(
+                outputs[&out_ids[i]] + 2 * outputs[&carry_ids[i]],
+                inputs[0] + inputs[1]
+            );
+        }
+
+        let full_adder = get_full_adder_bitstring_as_layers(
+            x_ids.clone(),
+            y_ids.clone(),
+            z_ids.clone(),
+            out_ids.clone(),
+            carry_ids.clone(),
+            1,
+        );
+        let outputs = evaluate_layers(&words, full_adder);
+        for i in 0..3 {
+            assert_eq!(
+                outputs[&out_ids[i]] + 2 * outputs[&carry_ids[i]],
+                inputs.iter().sum::<i64>()
+            );
+        }
+    }
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use common::*;
+use lac::integer::*;
+use lac::utils::*;
+use std::collections::HashMap;
+
+fn get_bits(x: u64, size: usize) -> Vec<i64> {
+    (0..size).map(|i| ((x >> i) & 1) as i64).collect()
+}
+
+fn get_number(outputs: &HashMap<u64, i64>, ids: &[u64]) -> u64 {
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            assert!(outputs[id] == 0 || outputs[id] == 1);
+            (outputs[id] as u64) << i
+        })
+        .sum()
+}
+
+/// Ids of two words of `size` bits from id 2, followed by ids for the sum and the carries
+fn get_word_ids(size: usize) -> [Vec<u64>; 4] {
+    let size = size as u64;
+    [0, 1, 2, 3].map(|k| ((2 + k * size)..(2 + (k + 1) * size)).collect())
+}
+
+/// Pairs of words: every pair up to 4 bits, and a few edge cases for 32 bits
+fn get_word_pairs() -> Vec<(usize, u64, u64)> {
+    let mut pairs: Vec<(usize, u64, u64)> = Vec::new();
+    for size in 1..=4 {
+        for x0 in 0..(1 << size) {
+            for x1 in 0..(1 << size) {
+                pairs.push((size, x0, x1));
+            }
+        }
+    }
+    let words = [
+        0, 1, 0x7fffffff, 0x80000000, 0xffffffff, 0x12345678, 0x9abcdef0,
+    ];
+    for x0 in words {
+        for x1 in words {
+            pairs.push((32, x0, x1));
+        }
+    }
+    pairs.push((5, 21, 11));
+    pairs.push((7, 100, 27));
+    pairs
+}
+
+fn get_inputs(size: usize, x0: u64, x1: u64) -> Vec<i64> {
+    [get_bits(x0, size), get_bits(x1, size)].concat()
+}
+
+#[test]
+fn test_ripple_carry_adder() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, out_ids, carry_ids] = get_word_ids(size);
+        let layers = get_ripple_carry_adder_as_layers(
+            in_ids0,
+            in_ids1,
+            out_ids.clone(),
+            carry_ids.clone(),
+            1,
+        );
+        assert_eq!(layers.len(), 2 * size - 1);
+        let outputs = evaluate_layers(&get_inputs(size, x0, x1), layers);
+        let mask = (1u64 << size) - 1;
+        assert_eq!(get_number(&outputs, &out_ids), (x0 + x1) & mask);
+        assert_eq!(outputs[&carry_ids[size - 1]], ((x0 + x1) >> size) as i64);
+    }
+}
+
+#[test]
+fn test_carry_lookahead_adder() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, out_ids, carry_ids] = get_word_ids(size);
+        let layers = get_carry_lookahead_adder_as_layers(
+            in_ids0,
+            in_ids1,
+            out_ids.clone(),
+            carry_ids.clone(),
+            1,
+        );
+        let outputs = evaluate_layers(&get_inputs(size, x0, x1), layers);
+        let mask = (1u64 << size) - 1;
+        assert_eq!(get_number(&outputs, &out_ids), (x0 + x1) & mask);
+        for (i, id) in carry_ids.iter().enumerate() {
+            let low_mask = (1u64 << (i + 1)) - 1;
+            let carry = ((x0 & low_mask) + (x1 & low_mask)) >> (i + 1);
+            assert_eq!(outputs[id], carry as i64);
+        }
+    }
+}
+
+#[test]
+fn test_field_adder() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, _, _] = get_word_ids(size);
+        let hint_ids: Vec<u64> = (0..=size as u64).map(|i| 2 + 2 * size as u64 + i).collect();
+        let check_ids: Vec<u64> = (0..(size as u64 + 2)).map(|i| 1000 + i).collect();
+        let (bits0, bits1) = (get_bits(x0, size), get_bits(x1, size));
+        let hints = get_field_adder_hints(&bits0, &bits1);
+        assert_eq!(hints.len(), size + 1);
+
+        let layer = get_field_adder_as_layer(
+            in_ids0.clone(),
+            in_ids1.clone(),
+            hint_ids.clone(),
+            check_ids.clone(),
+            1,
+        );
+        let inputs = [bits0.clone(), bits1.clone(), hints.clone()].concat();
+        let outputs = evaluate_layers(&inputs, vec![layer.clone()]);
+        assert_eq!(get_number(&outputs, &hint_ids), x0 + x1);
+        assert!(check_ids.iter().all(|id| outputs[id] == 0));
+
+        // a wrong sum or a non boolean hint is caught by some check
+        let mut wrong_hints = hints.clone();
+        wrong_hints[0] = 1 - wrong_hints[0];
+        let inputs = [bits0.clone(), bits1.clone(), wrong_hints].concat();
+        let outputs = evaluate_layers(&inputs, vec![layer.clone()]);
+        assert!(check_ids.iter().any(|id| outputs[id] != 0));
+
+        if hints[0] == 0 && size > 1 && hints[1] == 1 {
+            let mut wrong_hints = hints.clone();
+            wrong_hints[0] = 2;
+            wrong_hints[1] = 0;
+            let inputs = [bits0, bits1, wrong_hints].concat();
+            let outputs = evaluate_layers(&inputs, vec![layer]);
+            assert_eq!(outputs[&check_ids[size + 1]], 0);
+            assert!(check_ids.iter().any(|id| outputs[id] != 0));
+        }
+    }
+}
+
+#[test]
+fn test_subtraction() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, out_ids, carry_ids] = get_word_ids(size);
+        let layers =
+            get_subtraction_as_layers(in_ids0, in_ids1, out_ids.clone(), carry_ids.clone(), 1);
+        let outputs = evaluate_layers(&get_inputs(size, x0, x1), layers);
+        let mask = (1u64 << size) - 1;
+        assert_eq!(get_number(&outputs, &out_ids), x0.wrapping_sub(x1) & mask);
+        assert_eq!(outputs[&carry_ids[size - 1]], (x0 >= x1) as i64);
+    }
+}
+
+#[test]
+fn test_less_than() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, _, _] = get_word_ids(size);
+        let layers = get_less_than_as_layers(in_ids0, in_ids1, 1000, 1);
+        let outputs = evaluate_layers(&get_inputs(size, x0, x1), layers);
+        assert_eq!(outputs[&1000], (x0 < x1) as i64);
+    }
+}
+
+#[test]
+fn test_equality() {
+    for (size, x0, x1) in get_word_pairs() {
+        let [in_ids0, in_ids1, _, _] = get_word_ids(size);
+        let layers = get_equality_as_layers(in_ids0, in_ids1, 1000, 1);
+        let outputs = evaluate_layers(&get_inputs(size, x0, x1), layers);
+        assert_eq!(outputs[&1000], (x0 == x1) as i64);
+    }
+}
+
+#[test]
+fn test_integer_cost() {
+    let count = |layers: &Vec<Layer<i64>>| -> (usize, usize) {
+        let gates = layers.iter().map(|layer| layer.gates_amount()).sum();
+        (layers.len(), gates)
+    };
+    let [in_ids0, in_ids1, out_ids, carry_ids] = get_word_ids(32);
+    let hint_ids: Vec<u64> = (200..233).collect();
+    let check_ids: Vec<u64> = (300..334).collect();
+
+    let layers = get_ripple_carry_adder_as_layers(
+        in_ids0.clone(),
+        in_ids1.clone(),
+        out_ids.clone(),
+        carry_ids.clone(),
+        1,
+    );
+    assert_eq!(count(&layers), (63, 4189));
+    let layers = get_carry_lookahead_adder_as_layers(
+        in_ids0.clone(),
+        in_ids1.clone(),
+        out_ids.clone(),
+        carry_ids.clone(),
+        1,
+    );
+    assert_eq!(count(&layers), (7, 720));
+    let layer = get_field_adder_as_layer(in_ids0.clone(), in_ids1.clone(), hint_ids, check_ids, 1);
+    assert_eq!(count(&vec![layer]), (1, 69));
+    let layers = get_subtraction_as_layers(in_ids0.clone(), in_ids1.clone(), out_ids, carry_ids, 1);
+    assert_eq!(count(&layers), (7, 721));
+    let layers = get_less_than_as_layers(in_ids0.clone(), in_ids1.clone(), 1000, 1);
+    assert_eq!(count(&layers), (7, 172));
+    let layers = get_equality_as_layers(in_ids0, in_ids1, 1000, 1);
+    assert_eq!(count(&layers), (6, 75));
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::*;
+use lac::packing::*;
+
+#[test]
+fn test_pack() {
+    for x in [0, 1, 0b1011, 0xffff, 0x1234_5678_9abc, (1 << 62) - 1] {
+        for k in [16, 48, 62] {
+            let bits = get_unpack_hints(x, k);
+            let ids: Vec<u64> = (2..(2 + k as u64)).collect();
+            let mut lac = get_lac(&bits);
+            lac.append_layer(get_pack_as_layer(ids, 100, 1));
+            assert_eq!(evaluate(lac)[&100], x & ((1 << k) - 1));
+        }
+    }
+}
+
+#[test]
+fn test_pack_bitstring() {
+    let bytes: [i64; 3] = [0x12, 0xab, 0x07];
+    let bits: Vec<i64> = bytes.iter().flat_map(|b| get_unpack_hints(*b, 8)).collect();
+    let ids: Vec<u64> = (2..26).collect();
+    let mut lac = get_lac(&bits[..20]);
+    lac.append_layer(get_pack_bitstring_as_layer(
+        ids[..20].to_vec(),
+        vec![100, 101, 102],
+        8,
+        1,
+    ));
+    let outputs = evaluate(lac);
+    assert_eq!(
+        [outputs[&100], outputs[&101], outputs[&102]],
+        [0x12, 0xab, 0x07 & 0xf]
+    );
+}
+
+#[test]
+#[should_panic(expected = "can not pack")]
+fn test_pack_too_many_bits() {
+    get_pack_as_layer((2..65).collect(), 100, 1);
+}
+
+#[test]
+fn test_unpack() {
+    let k = 10;
+    let hint_ids: Vec<u64> = (3..(3 + k as u64)).collect();
+    let check_ids: Vec<u64> = (100..(101 + k as u64)).collect();
+    for x in [0, 1, 513, 1023] {
+        let hints = get_unpack_hints(x, k);
+        let mut lac = get_lac(&[vec![x], hints.clone()].concat());
+        lac.append_layer(get_unpack_as_layer(
+            2,
+            hint_ids.clone(),
+            check_ids.clone(),
+            1,
+        ));
+        let outputs = evaluate(lac);
+        assert!(check_ids.iter().all(|id| outputs[id] == 0));
+        assert!(hint_ids
+            .iter()
+            .zip(&hints)
+            .all(|(id, bit)| outputs[id] == *bit));
+
+        // other bits, non boolean bits or a value out of range make a check non zero
+        let mut wrong = hints.clone();
+        wrong[3] = 1 - wrong[3];
+        let mut non_boolean = hints.clone();
+        non_boolean[0] += 2;
+        non_boolean[1] -= 1;
+        for (x, hints) in [(x, wrong), (x, non_boolean), (x + 1024, hints)] {
+            let mut lac = get_lac(&[vec![x], hints].concat());
+            lac.append_layer(get_unpack_as_layer(
+                2,
+                hint_ids.clone(),
+                check_ids.clone(),
+                1,
+            ));
+            let outputs = evaluate(lac);
+            assert!(check_ids.iter().any(|id| outputs[id] != 0));
+        }
+    }
+}
+
+#[test]
+fn test_pack_then_unpack() {
+    // the unpacked bits of a packed byte are the byte, the hints are relayed to the unpack
+    let byte: i64 = 0b1100_1010;
+    let bits = get_unpack_hints(byte, 8);
+    let bit_ids: Vec<u64> = (2..10).collect();
+    let hint_ids: Vec<u64> = (10..18).collect();
+    let check_ids: Vec<u64> = (100..109).collect();
+    let mut lac = get_lac(&[bits.clone(), bits.clone()].concat());
+
+    let mut layer = get_pack_as_layer(bit_ids, 50, 1);
+    layer.add_gate_0_and_1(1);
+    layer.copy_gates_by_ids(hint_ids.clone());
+    lac.append_layer(layer);
+    lac.append_layer(get_unpack_as_layer(
+        50,
+        hint_ids.clone(),
+        check_ids.clone(),
+        2,
+    ));
+
+    let outputs = evaluate(lac);
+    assert!(check_ids.iter().all(|id| outputs[id] == 0));
+    let unpacked: Vec<i64> = hint_ids.iter().map(|id| outputs[id]).collect();
+    assert_eq!(unpacked, bits);
+}
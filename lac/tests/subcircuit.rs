@@ -0,0 +1,67 @@
+use lac::subcircuit::*;
+use lac::utils::*;
+use lac::xor::*;
+
+fn get_basic_layer(values: Vec<i64>) -> BasicLayer<i64> {
+    let mut basic_layer = BasicLayer::new();
+    for (i, x) in values.iter().enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(i as u64, *x);
+        basic_layer.append_value(value);
+    }
+    basic_layer
+}
+
+fn get_xor_subcircuit() -> SubCircuit<i64> {
+    let layers = get_xor_as_layers(vec![2, 3], 4, 1);
+    SubCircuit::new(layers, vec![2, 3], vec![4])
+}
+
+#[test]
+fn test_subcircuit_ports() {
+    let xor = get_xor_subcircuit();
+    assert_eq!(xor.get_depth(), 2);
+    assert_eq!(xor.get_input_ids(), vec![2, 3]);
+    assert_eq!(xor.get_output_ids(), vec![4]);
+    assert_eq!(xor.get_constant_ids(), vec![0, 1]);
+    assert_eq!(xor.get_max_id(), 4);
+}
+
+#[test]
+#[should_panic(expected = "not an input port")]
+fn test_subcircuit_unbound_read() {
+    let layers = get_xor_as_layers(vec![2, 3], 4, 1);
+    SubCircuit::new(layers, vec![2], vec![4]);
+}
+
+#[test]
+fn test_subcircuit_instances() {
+    // x0 ^ x1 ^ x2 with two instances of the same XOR template
+    for (x0, x1, x2) in [(0, 0, 0), (1, 0, 1), (1, 1, 1), (0, 1, 0), (0, 0, 1)] {
+        let mut lac: LAC<i64> = LAC::new();
+        lac.set_basic_layer(get_basic_layer(vec![0, 1, x0, x1, x2]));
+        let xor = get_xor_subcircuit();
+
+        let first_out = xor.instantiate_into(&mut lac, 1, &[2, 3], &[1, 4]).unwrap();
+        let second_out = xor
+            .instantiate_into(&mut lac, 3, &[first_out[0], 4], &[])
+            .unwrap();
+        assert_ne!(first_out, second_out);
+
+        assert_eq!(lac.get_layers_amount(), 4);
+        let res = lac.evaluate();
+        assert_eq!(*res.last().unwrap(), x0 ^ x1 ^ x2);
+    }
+}
+
+#[test]
+fn test_subcircuit_instance_remap() {
+    let xor = get_xor_subcircuit();
+    let remap = IdRemap::new(100);
+    let layers = xor.instantiate(5, &[7, 9], &remap, &[12]);
+    assert_eq!(layers.len(), 2);
+    assert_eq!(layers[0].get_read_ids(), vec![0, 1, 7, 9, 12]);
+    assert_eq!(layers[0].get_gate_ids(), vec![0, 1, 12, 102, 103]);
+    assert_eq!(layers[1].get_gate_ids(), vec![0, 12, 104]);
+    assert_eq!(xor.get_instance_output_ids(&remap), vec![104]);
+}
@@ -0,0 +1,109 @@
+use lac::trace::*;
+use lac::utils::*;
+use lac::xor::*;
+
+fn get_xor_lac(in0: Vec<i64>, in1: Vec<i64>) -> LAC<i64> {
+    let bits = in0.len() as u64;
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    let values: Vec<i64> = vec![0, 1].into_iter().chain(in0).chain(in1).collect();
+    for (id, x) in values.iter().enumerate() {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id as u64, *x);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    let in_ids0: Vec<u64> = (2..(2 + bits)).collect();
+    let in_ids1: Vec<u64> = ((2 + bits)..(2 + 2 * bits)).collect();
+    lac.append_layers(get_xor_bitstring_as_layers(
+        in_ids0.clone(),
+        in_ids1,
+        in_ids0,
+        1,
+    ));
+    lac
+}
+
+#[test]
+fn test_check_trace_of_evaluation() {
+    let mut lac = get_xor_lac(vec![1, 0, 1], vec![1, 1, 0]);
+    let trace = lac.get_trace();
+    assert_eq!(trace.len(), 3);
+    assert_eq!(trace[2][&2], 0);
+    assert_eq!(trace[2][&3], 1);
+    assert_eq!(lac.check_trace(&trace), Ok(()));
+    assert_eq!(lac.check_trace_all(&trace), Ok(()));
+}
+
+#[test]
+fn test_check_trace_reports_inconsistent_gates() {
+    let mut lac = get_xor_lac(vec![1, 0, 1], vec![1, 1, 0]);
+    let mut trace = lac.get_trace();
+    //product of the first bits claimed 0 instead of 1, and the last output flipped
+    trace[1].insert(5, 0);
+    trace[2].insert(4, 0);
+
+    assert_eq!(
+        lac.check_trace(&trace),
+        Err(TraceError::Inconsistent {
+            degree: 1,
+            id: 5,
+            expected: 1,
+            claimed: 0
+        })
+    );
+    assert_eq!(
+        lac.check_trace_all(&trace),
+        Err(vec![
+            TraceError::Inconsistent {
+                degree: 1,
+                id: 5,
+                expected: 1,
+                claimed: 0
+            },
+            //the first output follows from the claimed product, 2 - 2 * 0
+            TraceError::Inconsistent {
+                degree: 2,
+                id: 2,
+                expected: 2,
+                claimed: 0
+            },
+            TraceError::Inconsistent {
+                degree: 2,
+                id: 4,
+                expected: 1,
+                claimed: 0
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_check_trace_structure() {
+    let mut lac = get_xor_lac(vec![1], vec![0]);
+    let trace = lac.get_trace();
+
+    assert_eq!(
+        lac.check_trace(&trace[..2].to_vec()),
+        Err(TraceError::WrongLayersAmount {
+            expected: 3,
+            got: 2
+        })
+    );
+
+    let mut missing = trace.clone();
+    missing[1].remove(&3);
+    missing[2].insert(9, 1);
+    assert_eq!(
+        lac.check_trace_all(&missing),
+        Err(vec![
+            TraceError::MissingValue { degree: 1, id: 3 },
+            TraceError::MissingInput {
+                degree: 2,
+                id: 2,
+                input_id: 3
+            },
+            TraceError::UnknownGate { degree: 2, id: 9 },
+        ])
+    );
+}
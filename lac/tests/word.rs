@@ -0,0 +1,108 @@
+use lac::utils::*;
+use lac::word::*;
+use lac::xnor::*;
+
+/// Value of the word `ids` when the id `i` holds bit `i - 2` of `x`, and ids 0 and 1 hold
+/// the constants
+fn get_word_value(ids: &[u64], x: u64) -> u64 {
+    ids.iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let bit = match id {
+                0 => 0,
+                1 => 1,
+                id => (x >> (id - 2)) & 1,
+            };
+            bit << i
+        })
+        .sum()
+}
+
+#[test]
+fn test_rotations_and_shifts() {
+    let ids: Vec<u64> = (2..34).collect();
+    for x in [0x12345678u32, 0x80000001, 0xdeadbeef] {
+        for n in [0, 1, 7, 31, 32, 45] {
+            let value = |ids: Vec<u64>| get_word_value(&ids, x as u64) as u32;
+            assert_eq!(
+                value(rotate_left_ids(ids.clone(), n)),
+                x.rotate_left(n as u32)
+            );
+            assert_eq!(
+                value(rotate_right_ids(ids.clone(), n)),
+                x.rotate_right(n as u32)
+            );
+            let shifted_left = x.checked_shl(n as u32).unwrap_or(0);
+            let shifted_right = x.checked_shr(n as u32).unwrap_or(0);
+            assert_eq!(value(shift_left_ids(ids.clone(), n)), shifted_left);
+            assert_eq!(value(shift_right_ids(ids.clone(), n)), shifted_right);
+        }
+        let value = get_word_value(&byte_swap_ids(ids.clone()), x as u64) as u32;
+        assert_eq!(value, x.swap_bytes());
+    }
+    assert_eq!(shift_left_ids(vec![5, 6, 7], 1), vec![ZERO_ID, 5, 6]);
+    assert_eq!(shift_right_ids(vec![5, 6, 7], 2), vec![7, ZERO_ID, ZERO_ID]);
+    assert_eq!(rotate_left_ids(vec![], 3), Vec::<u64>::new());
+    assert_eq!(rotate_right_ids(vec![], 3), Vec::<u64>::new());
+}
+
+#[test]
+fn test_lanes() {
+    let w = 4;
+    let state: Vec<u64> = (2..(2 + 25 * w)).collect();
+    let lanes = split_ids(state.clone(), w);
+    assert_eq!(lanes.len(), 25);
+    for y in 0..5 {
+        for x in 0..5 {
+            let lane = get_lane_ids(state.clone(), x, y, w);
+            assert_eq!(lane, lanes[(x + 5 * y) as usize]);
+            assert_eq!(lane[0], 2 + (x + 5 * y) * w);
+        }
+    }
+    assert_eq!(concat_ids(lanes), state);
+}
+
+#[test]
+#[should_panic(expected = "can not byte swap")]
+fn test_byte_swap_length() {
+    byte_swap_ids((2..14).collect());
+}
+
+#[test]
+fn test_rewired_xnor() {
+    // !(x ^ (x >>> 3)) on a byte with no extra layer for the rotation
+    let x: i64 = 0b1011_0010;
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for id in 0..10 {
+        let mut value: Value<i64> = Value::new();
+        let bit = match id {
+            0 => 0,
+            1 => 1,
+            id => (x >> (id - 2)) & 1,
+        };
+        value.set_all(id, bit);
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+
+    let ids: Vec<u64> = (2..10).collect();
+    let out_ids: Vec<u64> = (10..18).collect();
+    let rotated = rotate_right_ids(ids.clone(), 3);
+    lac.append_layer(get_xnor_bitstring_as_layer(
+        ids,
+        rotated,
+        out_ids.clone(),
+        1,
+    ));
+    assert_eq!(lac.validate(), Ok(()));
+
+    let outputs = lac.get_trace().pop().unwrap();
+    let value: u8 = out_ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (outputs[id] as u8) << i)
+        .sum();
+    let x = x as u8;
+    assert_eq!(value, !(x ^ x.rotate_right(3)));
+}
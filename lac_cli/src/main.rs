@@ -0,0 +1,294 @@
+use keccak::keccak_circuit::*;
+use lac::interface::*;
+use lac::utils::*;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage:
+  lac build keccak --rate <r> --capacity <c> --out-bits <l> [--message-bits <n>] [--out <file>]
+  lac stats <circuit>
+  lac eval <circuit> --input [<group>=]hex:<hex>|bits:<bits> ...
+  lac export <circuit> --format r1cs|dot|json [--out <file>]
+  lac validate <circuit>
+
+hex inputs and outputs hold 8 bits per byte, least significant bit first";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, args) = args.split_first().ok_or(USAGE)?;
+    match command.as_str() {
+        "build" => build(args),
+        "stats" => stats(args),
+        "eval" => eval(args),
+        "export" => export(args),
+        "validate" => validate(args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("unknown command {}\n{}", command, USAGE)),
+    }
+}
+
+/// Values of every `--flag value` pair, a flag may be repeated
+type Flags = HashMap<String, Vec<String>>;
+
+/// Splits `args` into positional arguments and flags
+fn parse_args(args: &[String]) -> Result<(Vec<String>, Flags), String> {
+    let mut positional: Vec<String> = Vec::new();
+    let mut flags: Flags = HashMap::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(flag) = arg.strip_prefix("--") {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for --{}", flag))?;
+            flags
+                .entry(flag.to_string())
+                .or_default()
+                .push(value.clone());
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+    Ok((positional, flags))
+}
+
+fn get_flag<'a>(flags: &'a Flags, flag: &str) -> Option<&'a str> {
+    flags
+        .get(flag)
+        .and_then(|values| values.last())
+        .map(|value| value.as_str())
+}
+
+fn get_number_flag(flags: &Flags, flag: &str) -> Result<u64, String> {
+    let value = get_flag(flags, flag).ok_or_else(|| format!("missing --{}", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("--{} expects a number, got {}", flag, value))
+}
+
+fn get_circuit_path(positional: &[String]) -> Result<&str, String> {
+    match positional {
+        [path] => Ok(path),
+        _ => Err(format!("expected exactly one circuit file\n{}", USAGE)),
+    }
+}
+
+fn read_circuit(path: &str) -> Result<LAC<i64>, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("can not read {}: {}", path, e))?;
+    LAC::from_bytes(&bytes).map_err(|e| format!("can not decode {}: {}", path, e))
+}
+
+fn write_output(flags: &Flags, content: &[u8]) -> Result<(), String> {
+    match get_flag(flags, "out") {
+        Some(path) => {
+            std::fs::write(path, content).map_err(|e| format!("can not write {}: {}", path, e))
+        }
+        None => {
+            use std::io::Write;
+            std::io::stdout()
+                .write_all(content)
+                .map_err(|e| format!("can not write output: {}", e))
+        }
+    }
+}
+
+fn build(args: &[String]) -> Result<(), String> {
+    let (positional, flags) = parse_args(args)?;
+    match positional.as_slice() {
+        [name] if name == "keccak" => {}
+        _ => return Err(format!("only `lac build keccak` is supported\n{}", USAGE)),
+    }
+    let r = get_number_flag(&flags, "rate")?;
+    let c = get_number_flag(&flags, "capacity")?;
+    let l = get_number_flag(&flags, "out-bits")?;
+    let message_bits = match get_flag(&flags, "message-bits") {
+        Some(_) => get_number_flag(&flags, "message-bits")?,
+        None => 256,
+    };
+
+    let lac = get_keccak_lac_circuit_for_size(message_bits, r, c, l).map_err(|e| e.to_string())?;
+    let path = get_flag(&flags, "out").unwrap_or("circuit.bin");
+    std::fs::write(path, lac.to_bytes()).map_err(|e| format!("can not write {}: {}", path, e))?;
+    println!(
+        "wrote {}: {} layers, {} gates",
+        path,
+        lac.get_layers_amount(),
+        lac.get_gates_amount()
+    );
+    Ok(())
+}
+
+fn stats(args: &[String]) -> Result<(), String> {
+    let (positional, _) = parse_args(args)?;
+    let mut lac = read_circuit(get_circuit_path(&positional)?)?;
+    let layers_amount = lac.get_layers_amount();
+    let mut widths: Vec<usize> = Vec::new();
+    for degree in 1..=layers_amount as u64 {
+        widths.push(lac.get_layer_by_degree(degree).gates_amount());
+    }
+
+    println!("layers: {}", layers_amount);
+    println!("gates: {}", lac.get_gates_amount());
+    println!("wires: {}", widths.iter().sum::<usize>());
+    println!("max layer width: {}", widths.iter().max().unwrap_or(&0));
+    println!("input size: {}", lac.get_input_size());
+    for group in lac.get_input_groups() {
+        let visibility = match group.get_visibility() {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+        };
+        println!(
+            "input {} ({}): {} values",
+            group.get_name(),
+            visibility,
+            group.get_ids().len()
+        );
+    }
+    for group in lac.get_output_groups() {
+        println!(
+            "output {}: {} values",
+            group.get_name(),
+            group.get_ids().len()
+        );
+    }
+    Ok(())
+}
+
+/// Parses `hex:<hex>` (8 bits per byte, least significant bit first) or `bits:<0 and 1>`
+fn parse_values(value: &str) -> Result<Vec<i64>, String> {
+    if let Some(hex) = value.strip_prefix("hex:") {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        if !hex.len().is_multiple_of(2) {
+            return Err(format!("odd number of hex digits in {}", value));
+        }
+        let mut bits: Vec<i64> = Vec::new();
+        for i in (0..hex.len()).step_by(2) {
+            let byte = u8::from_str_radix(&hex[i..(i + 2)], 16)
+                .map_err(|_| format!("invalid hex in {}", value))?;
+            for j in 0..8 {
+                bits.push(((byte >> j) & 1) as i64);
+            }
+        }
+        Ok(bits)
+    } else if let Some(bits) = value.strip_prefix("bits:") {
+        bits.chars()
+            .map(|c| match c {
+                '0' => Ok(0),
+                '1' => Ok(1),
+                _ => Err(format!("invalid bit {} in {}", c, value)),
+            })
+            .collect()
+    } else {
+        Err(format!(
+            "input must start with hex: or bits:, got {}",
+            value
+        ))
+    }
+}
+
+fn format_values(values: &[i64]) -> String {
+    if !values.is_empty()
+        && values.len().is_multiple_of(8)
+        && values.iter().all(|v| *v == 0 || *v == 1)
+    {
+        let mut hex = String::from("hex:");
+        for byte in values.chunks(8) {
+            let byte = byte
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (j, bit)| acc | ((*bit as u8) << j));
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        hex
+    } else {
+        format!("{:?}", values)
+    }
+}
+
+fn eval(args: &[String]) -> Result<(), String> {
+    let (positional, flags) = parse_args(args)?;
+    let mut lac = read_circuit(get_circuit_path(&positional)?)?;
+    check_valid(&lac)?;
+
+    let groups: Vec<String> = lac
+        .get_input_groups()
+        .iter()
+        .map(|group| group.get_name().to_string())
+        .collect();
+    let mut inputs: HashMap<String, Vec<i64>> = HashMap::new();
+    for input in flags.get("input").cloned().unwrap_or_default() {
+        let (name, value) = match input.split_once('=') {
+            Some((name, value)) => (name.to_string(), value),
+            None if groups.len() == 1 => (groups[0].clone(), input.as_str()),
+            None => {
+                return Err(format!(
+                    "the circuit has the input groups {:?}, use --input <group>=<value>",
+                    groups
+                ))
+            }
+        };
+        inputs.insert(name, parse_values(value)?);
+    }
+    let inputs: HashMap<&str, Vec<i64>> = inputs
+        .iter()
+        .map(|(name, values)| (name.as_str(), values.clone()))
+        .collect();
+
+    let outputs = lac.evaluate_named(&inputs).map_err(|e| e.to_string())?;
+    for group in lac.get_output_groups() {
+        println!(
+            "{}: {}",
+            group.get_name(),
+            format_values(&outputs[group.get_name()])
+        );
+    }
+    Ok(())
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    let (positional, flags) = parse_args(args)?;
+    let lac = read_circuit(get_circuit_path(&positional)?)?;
+    let content = match get_flag(&flags, "format") {
+        Some("json") => lac.to_json(),
+        Some("dot") => lac.to_dot(),
+        Some("r1cs") => {
+            check_valid(&lac)?;
+            lac.to_r1cs_json()
+        }
+        _ => return Err(format!("--format must be r1cs, dot or json\n{}", USAGE)),
+    };
+    write_output(&flags, content.as_bytes())
+}
+
+fn check_valid(lac: &LAC<i64>) -> Result<(), String> {
+    lac.validate().map_err(|errors| {
+        let mut message = format!("invalid circuit, {} problems:", errors.len());
+        for error in errors.iter().take(20) {
+            message.push_str(&format!("\n  {}", error));
+        }
+        if errors.len() > 20 {
+            message.push_str("\n  ...");
+        }
+        message
+    })
+}
+
+fn validate(args: &[String]) -> Result<(), String> {
+    let (positional, _) = parse_args(args)?;
+    let lac = read_circuit(get_circuit_path(&positional)?)?;
+    check_valid(&lac)?;
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use lac::interface::*;
+use lac::utils::*;
+use lac::xor::*;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn lac_command(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lac"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Directory of the files of one test, removed with everything in it when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("lac_cli_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+/// XOR of two bytes, `a` public and `b` private
+fn write_xor_circuit(dir: &TempDir, name: &str) -> PathBuf {
+    let in_ids0: Vec<u64> = (2..10).collect();
+    let in_ids1: Vec<u64> = (10..18).collect();
+    let mut lac: LAC<i64> = LAC::new();
+    let mut basic_layer: BasicLayer<i64> = BasicLayer::new();
+    for id in 0..18 {
+        let mut value: Value<i64> = Value::new();
+        value.set_all(id, if id == 1 { 1 } else { 0 });
+        basic_layer.append_value(value);
+    }
+    lac.set_basic_layer(basic_layer);
+    lac.append_layers(get_xor_bitstring_as_layers(
+        in_ids0.clone(),
+        in_ids1.clone(),
+        in_ids0.clone(),
+        1,
+    ));
+    lac.declare_input("a", Visibility::Public, in_ids0.clone())
+        .unwrap();
+    lac.declare_input("b", Visibility::Private, in_ids1)
+        .unwrap();
+    lac.declare_output("a_xor_b", in_ids0).unwrap();
+
+    let path = dir.path(name);
+    std::fs::write(&path, lac.to_bytes()).unwrap();
+    path
+}
+
+#[test]
+fn test_cli_eval_and_validate() {
+    let dir = TempDir::new("eval");
+    let path = write_xor_circuit(&dir, "xor.bin");
+    let path = path.to_str().unwrap();
+
+    let output = lac_command(&["validate", path]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "ok\n");
+
+    let output = lac_command(&[
+        "eval",
+        path,
+        "--input",
+        "a=hex:0f",
+        "--input",
+        "b=bits:11000000",
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "a_xor_b: hex:0c\n");
+
+    let output = lac_command(&["eval", path, "--input", "hex:0f"]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("use --input <group>=<value>"));
+
+    let output = lac_command(&["eval", path, "--input", "a=hex:0f0f", "--input", "b=hex:00"]);
+    assert!(!output.status.success());
+    assert_eq!(stderr(&output), "input group a expects 8 values, got 16\n");
+}
+
+#[test]
+fn test_cli_stats_and_export() {
+    let dir = TempDir::new("export");
+    let path = write_xor_circuit(&dir, "xor.bin");
+    let path = path.to_str().unwrap();
+
+    let output = lac_command(&["stats", path]);
+    assert!(output.status.success());
+    let stats = stdout(&output);
+    assert!(stats.contains("layers: 2\n"));
+    assert!(stats.contains("input a (public): 8 values\n"));
+    assert!(stats.contains("input b (private): 8 values\n"));
+    assert!(stats.contains("output a_xor_b: 8 values\n"));
+
+    let output = lac_command(&["export", path, "--format", "json"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("{\"basic_layer\":["));
+
+    let dot_path = dir.path("xor.dot");
+    let output = lac_command(&[
+        "export",
+        path,
+        "--format",
+        "dot",
+        "--out",
+        dot_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert!(std::fs::read_to_string(&dot_path)
+        .unwrap()
+        .starts_with("digraph lac {"));
+
+    let output = lac_command(&["export", path, "--format", "r1cs"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("\"n_public_inputs\":8,\"n_private_inputs\":8"));
+
+    let output = lac_command(&["export", path, "--format", "svg"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_build_keccak() {
+    let dir = TempDir::new("build");
+    let path = dir.path("keccak.bin");
+    let path = path.to_str().unwrap();
+    let output = lac_command(&[
+        "build",
+        "keccak",
+        "--rate",
+        "72",
+        "--capacity",
+        "128",
+        "--out-bits",
+        "16",
+        "--message-bits",
+        "8",
+        "--out",
+        path,
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = lac_command(&["stats", path]);
+    let stats = stdout(&output);
+    assert!(stats.contains("input message (private): 8 values\n"));
+    assert!(stats.contains("output digest: 16 values\n"));
+
+    let output = lac_command(&[
+        "build",
+        "keccak",
+        "--rate",
+        "150",
+        "--capacity",
+        "100",
+        "--out-bits",
+        "16",
+    ]);
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("rate + capacity must be 25 * w"));
+}
+
+#[test]
+fn test_cli_build_keccak_empty_message() {
+    let dir = TempDir::new("build_empty");
+    let path = dir.path("keccak.bin");
+    let path = path.to_str().unwrap();
+    let output = lac_command(&[
+        "build",
+        "keccak",
+        "--rate",
+        "72",
+        "--capacity",
+        "128",
+        "--out-bits",
+        "16",
+        "--message-bits",
+        "0",
+        "--out",
+        path,
+    ]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = lac_command(&["stats", path]);
+    assert!(stdout(&output).contains("input message (private): 0 values\n"));
+
+    //Keccak[r = 72, c = 128] of the empty message, from tests/kat/keccak_small_widths.txt
+    let output = lac_command(&["eval", path, "--input", "message=bits:"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "digest: hex:d7e5\n");
+}